@@ -1,56 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
 pub type Program = Vec<Stmt>;
 
-#[derive(Debug, PartialEq, Clone)]
+// Byte offsets of a node in the source code
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+// `program` with every span reset, so that tests can compare ASTs by their structure only
+#[cfg(test)]
+pub fn without_spans(mut program: Program) -> Program {
+    fn block(stmts: &mut [Stmt]) {
+        stmts.iter_mut().for_each(stmt);
+    }
+    fn stmt(stmt: &mut Stmt) {
+        let span = match stmt {
+            Stmt::BlockStmt(stmts, span) | Stmt::Unreachable(stmts, span) => {
+                block(stmts);
+                span
+            }
+            Stmt::FuncDecl(func_decl, span) => {
+                ident(&mut func_decl.ident_func);
+                func_decl.ident_param.iter_mut().for_each(ident);
+                func_decl.default_param.iter_mut().flatten().for_each(expr);
+                block(&mut func_decl.block);
+                span
+            }
+            Stmt::IfStmt(if_stmt_, span) => {
+                if_stmt(if_stmt_);
+                span
+            }
+            Stmt::ForStmt(for_stmt, span) => {
+                for_stmt.label.iter_mut().for_each(ident);
+                match &mut for_stmt.init {
+                    Some(ForStmtInit::Var(var_stmt_)) => var_stmt(var_stmt_),
+                    Some(ForStmtInit::Expr(init)) => expr(init),
+                    None => (),
+                }
+                for_stmt.cond.iter_mut().for_each(expr);
+                for_stmt.afterthought.iter_mut().for_each(expr);
+                block(&mut for_stmt.block);
+                span
+            }
+            Stmt::WhileStmt(while_stmt, span) => {
+                while_stmt.label.iter_mut().for_each(ident);
+                expr(&mut while_stmt.cond);
+                block(&mut while_stmt.block);
+                span
+            }
+            Stmt::DoWhileStmt(do_while_stmt, span) => {
+                do_while_stmt.label.iter_mut().for_each(ident);
+                block(&mut do_while_stmt.block);
+                expr(&mut do_while_stmt.cond);
+                span
+            }
+            Stmt::SwitchStmt(switch_stmt, span) => {
+                expr(&mut switch_stmt.subject);
+                for case in &mut switch_stmt.cases {
+                    expr(&mut case.value);
+                    block(&mut case.block);
+                }
+                switch_stmt
+                    .default
+                    .iter_mut()
+                    .for_each(|stmts| block(stmts));
+                span
+            }
+            Stmt::VarStmt(var_stmt_, span) => {
+                var_stmt(var_stmt_);
+                span
+            }
+            Stmt::ExprStmt(expr_stmt, span) => {
+                expr(expr_stmt);
+                span
+            }
+            Stmt::ContinueStmt(label, span) | Stmt::BreakStmt(label, span) => {
+                label.iter_mut().for_each(ident);
+                span
+            }
+            Stmt::ReturnStmt(return_stmt, span) => {
+                return_stmt.expr.iter_mut().for_each(expr);
+                span
+            }
+            Stmt::Error(span) => span,
+        };
+        *span = Span::default();
+    }
+    fn if_stmt(if_stmt_: &mut IfStmt) {
+        expr(&mut if_stmt_.condition);
+        block(&mut if_stmt_.block);
+        match &mut if_stmt_.else_clause {
+            Some(IfStmtElseClause::IfStmtBlock(stmts)) => block(stmts),
+            Some(IfStmtElseClause::IfStmt(else_if_stmt)) => if_stmt(else_if_stmt),
+            None => (),
+        }
+    }
+    fn var_stmt(var_stmt: &mut VarStmt) {
+        ident(&mut var_stmt.ident);
+        var_stmt.expr.iter_mut().for_each(expr);
+    }
+    fn expr(expr_: &mut Expr) {
+        match expr_ {
+            Expr::Literal(Literal::Array(items)) => items.iter_mut().for_each(expr),
+            Expr::Literal(_) => (),
+            Expr::Ident(ident_) => ident(ident_),
+            Expr::PrefixOp { rhs, .. } => expr(rhs),
+            Expr::InfixOp { lhs, rhs, .. } => {
+                expr(lhs);
+                expr(rhs);
+            }
+            Expr::PostfixOp { kind, lhs } => {
+                match kind {
+                    PostfixOpKind::Index(index) => expr(index),
+                    PostfixOpKind::Call(args) => args.iter_mut().for_each(expr),
+                }
+                expr(lhs);
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                expr(cond);
+                expr(then);
+                expr(otherwise);
+            }
+        }
+    }
+    fn ident(ident: &mut Ident) {
+        ident.span = Span::default();
+    }
+
+    block(&mut program);
+    program
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(into = "crate::json::StmtJson", from = "crate::json::StmtJson")]
 pub enum Stmt {
     #[allow(clippy::enum_variant_names)]
-    BlockStmt(BlockStmt),
-    FuncDecl(FuncDecl),
+    BlockStmt(BlockStmt, Span),
+    FuncDecl(FuncDecl, Span),
+    #[allow(clippy::enum_variant_names)]
+    IfStmt(IfStmt, Span),
+    #[allow(clippy::enum_variant_names)]
+    ForStmt(ForStmt, Span),
     #[allow(clippy::enum_variant_names)]
-    IfStmt(IfStmt),
+    WhileStmt(WhileStmt, Span),
     #[allow(clippy::enum_variant_names)]
-    ForStmt(ForStmt),
+    DoWhileStmt(DoWhileStmt, Span),
     #[allow(clippy::enum_variant_names)]
-    WhileStmt(WhileStmt),
+    SwitchStmt(SwitchStmt, Span),
     #[allow(clippy::enum_variant_names)]
-    VarStmt(VarStmt),
+    VarStmt(VarStmt, Span),
     #[allow(clippy::enum_variant_names)]
-    ExprStmt(ExprStmt),
+    ExprStmt(ExprStmt, Span),
     #[allow(clippy::enum_variant_names)]
-    ContinueStmt,
+    ContinueStmt(Option<Ident>, Span), // With the label of the loop to continue, if any
     #[allow(clippy::enum_variant_names)]
-    BreakStmt,
+    BreakStmt(Option<Ident>, Span),
     #[allow(clippy::enum_variant_names)]
-    ReturnStmt(ReturnStmt),
+    ReturnStmt(ReturnStmt, Span),
+    // Statements after `return`, `break` or `continue` in a block, which are never run
+    Unreachable(BlockStmt, Span),
+    // Source which failed to parse, only built by `parse_recovering`
+    Error(Span),
+}
+impl Stmt {
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::BlockStmt(_, span)
+            | Stmt::FuncDecl(_, span)
+            | Stmt::IfStmt(_, span)
+            | Stmt::ForStmt(_, span)
+            | Stmt::WhileStmt(_, span)
+            | Stmt::DoWhileStmt(_, span)
+            | Stmt::SwitchStmt(_, span)
+            | Stmt::VarStmt(_, span)
+            | Stmt::ExprStmt(_, span)
+            | Stmt::ContinueStmt(_, span)
+            | Stmt::BreakStmt(_, span)
+            | Stmt::ReturnStmt(_, span)
+            | Stmt::Unreachable(_, span)
+            | Stmt::Error(span) => *span,
+        }
+    }
 }
 pub type BlockStmt = Vec<Stmt>;
 
-#[derive(Debug, PartialEq, Clone)]
+// Statements of a block without the unreachable ones at its end
+pub fn reachable(stmts: &[Stmt]) -> &[Stmt] {
+    match stmts.split_last() {
+        Some((Stmt::Unreachable(..), stmts)) => stmts,
+        _ => stmts,
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct FuncDecl {
     pub ident_func: Ident,
     pub ident_param: Vec<Ident>,
+    pub annotation_param: Vec<Option<TypeAnnotation>>, // Of each parameter
+    pub default_param: Vec<Option<Expr>>,              // Of each parameter, only of the last ones
+    pub annotation_return: Option<TypeAnnotation>,
     pub block: BlockStmt,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct IfStmt {
     pub condition: Expr,
     pub block: BlockStmt,
     pub else_clause: Option<IfStmtElseClause>,
 }
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum IfStmtElseClause {
     IfStmtBlock(BlockStmt),
     IfStmt(Box<IfStmt>),
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ForStmt {
+    pub label: Option<Ident>, // Written as `label:` before the loop for `break` and `continue`
     pub init: Option<ForStmtInit>,
     pub cond: Option<ForStmtCond>,
     pub afterthought: Option<ForStmtAfterthought>,
     pub block: BlockStmt,
 }
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum ForStmtInit {
     Var(VarStmt),
     Expr(Expr),
@@ -58,27 +244,75 @@ pub enum ForStmtInit {
 pub type ForStmtCond = Expr;
 pub type ForStmtAfterthought = Expr;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct WhileStmt {
+    pub label: Option<Ident>,
     pub cond: WhileStmtCond,
     pub block: BlockStmt,
 }
 pub type WhileStmtCond = Expr;
 
-#[derive(Debug, PartialEq, Clone)]
+// The condition is checked after each run of the block, outside of its scope
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct DoWhileStmt {
+    pub label: Option<Ident>,
+    pub block: BlockStmt,
+    pub cond: DoWhileStmtCond,
+}
+pub type DoWhileStmtCond = Expr;
+
+// Runs the block of the first case whose value is `==` to the subject, or else the default one
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SwitchStmt {
+    pub subject: Expr,
+    pub cases: Vec<SwitchStmtCase>,
+    pub default: Option<BlockStmt>,
+}
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct SwitchStmtCase {
+    pub value: Expr, // A bool, int or string literal, or a negated int literal
+    pub block: BlockStmt,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct VarStmt {
+    pub is_const: bool, // Declared with `const`, so that the variable cannot be assigned to
     pub ident: Ident,
+    pub annotation: Option<TypeAnnotation>,
     pub expr: Option<Expr>,
 }
 
+// Type written after `:` in a declaration, which values bound to the name are checked against
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum TypeAnnotation {
+    Int,
+    Bool,
+    String,
+    Array,
+    None,
+}
+impl fmt::Display for TypeAnnotation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TypeAnnotation::Int => "int",
+            TypeAnnotation::Bool => "bool",
+            TypeAnnotation::String => "string",
+            TypeAnnotation::Array => "array",
+            TypeAnnotation::None => "none",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 pub type ExprStmt = Expr;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ReturnStmt {
     pub expr: Option<Expr>,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "node")]
 pub enum Expr {
     Literal(Literal),
     Ident(Ident),
@@ -95,6 +329,12 @@ pub enum Expr {
         kind: PostfixOpKind,
         lhs: Box<Expr>,
     },
+    // `cond ? then : otherwise`, which only evaluates the chosen branch
+    Conditional {
+        cond: Box<Expr>,
+        then: Box<Expr>,
+        otherwise: Box<Expr>,
+    },
 }
 impl Expr {
     // Constructors for the conviniences of tests
@@ -122,6 +362,7 @@ impl Expr {
     pub fn ident(name: &str) -> Self {
         Self::Ident(Ident {
             name: name.to_string(),
+            span: Span::default(),
         })
     }
     #[allow(dead_code)]
@@ -139,22 +380,32 @@ impl Expr {
             rhs: Box::new(rhs),
         }
     }
+    #[allow(dead_code)]
+    pub fn conditional(cond: Expr, then: Expr, otherwise: Expr) -> Self {
+        Self::Conditional {
+            cond: Box::new(cond),
+            then: Box::new(then),
+            otherwise: Box::new(otherwise),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum PrefixOpKind {
     Pos,
     Neg,
     Not,
+    BitNot,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub enum InfixOpKind {
     Add,
     Subtract,
     Multiply,
     Divide,
     Modulo,
+    Power,
     Eq,
     NotEq,
     Gt,
@@ -163,16 +414,46 @@ pub enum InfixOpKind {
     Le,
     And,
     Or,
+    BitAnd,
+    BitOr,
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
     Assign,
+    AddAssign,
+    SubtractAssign,
+    MultiplyAssign,
+    DivideAssign,
+    ModuloAssign,
+}
+
+impl InfixOpKind {
+    // Operator a compound assignment applies to the target and the value, such as `Add` of `+=`
+    pub fn compound(&self) -> Option<InfixOpKind> {
+        match self {
+            InfixOpKind::AddAssign => Some(InfixOpKind::Add),
+            InfixOpKind::SubtractAssign => Some(InfixOpKind::Subtract),
+            InfixOpKind::MultiplyAssign => Some(InfixOpKind::Multiply),
+            InfixOpKind::DivideAssign => Some(InfixOpKind::Divide),
+            InfixOpKind::ModuloAssign => Some(InfixOpKind::Modulo),
+            _ => None,
+        }
+    }
+
+    pub fn is_assign(&self) -> bool {
+        *self == InfixOpKind::Assign || self.compound().is_some()
+    }
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum PostfixOpKind {
     Index(Box<Expr>),
     Call(Vec<Expr>),
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
 pub enum Literal {
     Bool(bool),
     Int(u64),
@@ -181,7 +462,8 @@ pub enum Literal {
     None,
 }
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
 pub struct Ident {
     pub name: String, // TODO: Consider changing to `&str`
+    pub span: Span,
 }
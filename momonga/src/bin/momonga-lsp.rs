@@ -0,0 +1,279 @@
+// Language server of Momonga speaking LSP over stdio, for editors other than the playground.
+//
+// Documents are synchronized in full. Positions are converted between byte offsets and the
+// UTF-16 based lines and characters of LSP.
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, Write};
+
+use serde_json::{json, Value};
+
+use momonga::{
+    completions, diagnostics, hover, resolve, CompletionKind, Severity, Span, Symbol, SymbolKind,
+};
+
+// Error code of JSON-RPC for requests of unknown methods
+const METHOD_NOT_FOUND: i64 = -32601;
+
+#[derive(Default)]
+struct Server {
+    documents: HashMap<String, String>, // Text of each document by its URI
+    shutdown: bool,
+}
+
+fn main() {
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+    let mut server = Server::default();
+    while let Some(message) = read_message(&mut stdin) {
+        let method = message["method"].as_str().unwrap_or_default();
+        if method == "exit" {
+            std::process::exit(if server.shutdown { 0 } else { 1 });
+        }
+        let id = message.get("id").cloned();
+        let params = &message["params"];
+        let outgoing = match id {
+            Some(id) => vec![match server.request(method, params) {
+                Some(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+                None => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": {"code": METHOD_NOT_FOUND, "message": format!("Unknown method: {}", method)},
+                }),
+            }],
+            None => server.notification(method, params),
+        };
+        for message in outgoing {
+            write_message(&mut stdout, &message);
+        }
+    }
+}
+
+// Content of the next message, or `None` at the end of the input
+fn read_message(input: &mut impl BufRead) -> Option<Value> {
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header).ok()? == 0 {
+            return None;
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse().ok()?;
+            }
+        }
+    }
+    let mut content = vec![0; content_length];
+    input.read_exact(&mut content).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn write_message(output: &mut impl Write, message: &Value) {
+    let content = message.to_string();
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )
+    .unwrap();
+    output.flush().unwrap();
+}
+
+impl Server {
+    // Result of a request, or `None` if the method is not supported
+    fn request(&mut self, method: &str, params: &Value) -> Option<Value> {
+        match method {
+            "initialize" => Some(json!({
+                "capabilities": {
+                    "textDocumentSync": 1, // Full
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "documentSymbolProvider": true,
+                    "completionProvider": {},
+                },
+                "serverInfo": {"name": "momonga-lsp"},
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Some(Value::Null)
+            }
+            "textDocument/hover" => Some(self.hover(params).unwrap_or(Value::Null)),
+            "textDocument/definition" => Some(self.definition(params).unwrap_or(Value::Null)),
+            "textDocument/documentSymbol" => Some(self.document_symbols(params)),
+            "textDocument/completion" => Some(self.completion(params)),
+            _ => None,
+        }
+    }
+
+    // Messages to send in response to a notification
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let text = match method {
+            "textDocument/didOpen" => params["textDocument"]["text"].as_str(),
+            "textDocument/didChange" => params["contentChanges"]
+                .as_array()
+                .and_then(|changes| changes.last())
+                .and_then(|change| change["text"].as_str()),
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                return vec![publish_diagnostics(uri, vec![])];
+            }
+            _ => None,
+        };
+        let Some(text) = text else {
+            return vec![];
+        };
+
+        let diagnostics = diagnostics(text)
+            .into_iter()
+            .map(|diagnostic| {
+                json!({
+                    "range": range(text, diagnostic.span),
+                    "severity": match diagnostic.severity {
+                        Severity::Error => 1,
+                        Severity::Warning => 2,
+                    },
+                    "source": "momonga",
+                    "message": diagnostic.message,
+                })
+            })
+            .collect();
+        self.documents.insert(uri.to_string(), text.to_string());
+        vec![publish_diagnostics(uri, diagnostics)]
+    }
+
+    // Text of the document and the byte offset of the position in `params`
+    fn locate<'a>(&'a self, params: &'a Value) -> Option<(&'a str, &'a str, usize)> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+        let document = self.documents.get(uri)?;
+        let offset = offset(document, &params["position"]);
+        Some((uri, document, offset))
+    }
+
+    fn hover(&self, params: &Value) -> Option<Value> {
+        let (_, document, offset) = self.locate(params)?;
+        let value = hover(document, offset)?;
+        Some(json!({"contents": {"kind": "markdown", "value": value}}))
+    }
+
+    fn definition(&self, params: &Value) -> Option<Value> {
+        let (uri, document, offset) = self.locate(params)?;
+        let resolution = resolve(document);
+        let symbol = resolution.symbol_at(offset)?;
+        if symbol.kind == SymbolKind::Builtin {
+            return None;
+        }
+        Some(json!({"uri": uri, "range": range(document, symbol.span)}))
+    }
+
+    // Functions with the functions declared in them as children
+    fn document_symbols(&self, params: &Value) -> Value {
+        let Some(document) = params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|uri| self.documents.get(uri))
+        else {
+            return json!([]);
+        };
+        let resolution = resolve(document);
+        let funcs: Vec<&Symbol> = resolution
+            .symbols
+            .iter()
+            .filter(|symbol| symbol.kind == SymbolKind::Function)
+            .collect();
+        // Index of the innermost function each function is declared in
+        let parents: Vec<Option<usize>> = funcs
+            .iter()
+            .map(|func| {
+                (0..funcs.len())
+                    .filter(|&i| {
+                        funcs[i].decl.start < func.decl.start && func.decl.end <= funcs[i].decl.end
+                    })
+                    .max_by_key(|&i| funcs[i].decl.start)
+            })
+            .collect();
+        document_symbols(document, &funcs, &parents, None)
+    }
+
+    fn completion(&self, params: &Value) -> Value {
+        let Some((_, document, offset)) = self.locate(params) else {
+            return json!([]);
+        };
+        completions(document, offset)
+            .into_iter()
+            .map(|completion| {
+                let kind = match completion.kind {
+                    CompletionKind::Keyword => 14,
+                    CompletionKind::Variable => 6,
+                    CompletionKind::Constant => 21,
+                    CompletionKind::Function => 3,
+                };
+                json!({"label": completion.label, "kind": kind, "detail": completion.detail})
+            })
+            .collect()
+    }
+}
+
+fn document_symbols(
+    text: &str,
+    funcs: &[&Symbol],
+    parents: &[Option<usize>],
+    parent: Option<usize>,
+) -> Value {
+    (0..funcs.len())
+        .filter(|&i| parents[i] == parent)
+        .map(|i| {
+            json!({
+                "name": funcs[i].name,
+                "detail": funcs[i].detail,
+                "kind": 12, // Function
+                "range": range(text, funcs[i].decl),
+                "selectionRange": range(text, funcs[i].span),
+                "children": document_symbols(text, funcs, parents, Some(i)),
+            })
+        })
+        .collect()
+}
+
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": {"uri": uri, "diagnostics": diagnostics},
+    })
+}
+
+fn position(text: &str, offset: usize) -> Value {
+    let offset = offset.min(text.len());
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = text[..line_start].matches('\n').count();
+    let character: usize = text[line_start..offset].chars().map(char::len_utf16).sum();
+    json!({"line": line, "character": character})
+}
+
+fn range(text: &str, span: Span) -> Value {
+    json!({"start": position(text, span.start), "end": position(text, span.end)})
+}
+
+fn offset(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or_default() as usize;
+    let mut character = position["character"].as_u64().unwrap_or_default() as usize;
+    let line_start = match line {
+        0 => 0,
+        _ => match text.match_indices('\n').nth(line - 1) {
+            Some((i, _)) => i + 1,
+            None => return text.len(),
+        },
+    };
+    for (i, char) in text[line_start..].char_indices() {
+        if char == '\n' || character < char.len_utf16() {
+            return line_start + i;
+        }
+        character -= char.len_utf16();
+    }
+    text.len()
+}
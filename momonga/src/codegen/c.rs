@@ -0,0 +1,831 @@
+// C backend.
+//
+// The generated program needs nothing but the C standard library: `runtime.c` is pasted in front,
+// implementing tagged values, arrays, strings and the builtins with the same errors as `EvalError`,
+// which are printed to stderr before exiting with status 1.
+//
+// C has no nested functions, so every function is hoisted to file scope. Variables of the program
+// body and of functions declaring other functions become file-scope globals for the inner
+// functions to see, which recursive calls of such a function share. Functions and builtins can
+// only be called by name, and assigning to a function is a `Type error`. Labelled `break` and
+// `continue` become `goto`s to labels put after the loop and at the end of its body.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::*;
+use crate::codegen::{
+    constants, declares_func, escape_string, labelled_jumps, refers, Jump, SideBySide, Writer,
+};
+use crate::data::{builtin, Arity, Builtin};
+use crate::error::{EvalError, ParseError};
+use crate::parser::parse;
+
+const RUNTIME: &str = include_str!("runtime.c");
+
+// C keywords and names of the runtime and the standard library, which are valid names in Momonga
+const RESERVED: [&str; 72] = [
+    "_Bool",
+    "_Complex",
+    "_Imaginary",
+    "auto",
+    "bool",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+    "EOF",
+    "FILE",
+    "INT64_MIN",
+    "MoArray",
+    "MoTag",
+    "MoValue",
+    "NULL",
+    "PRId64",
+    "abs",
+    "calloc",
+    "div",
+    "errno",
+    "exit",
+    "fprintf",
+    "fputs",
+    "free",
+    "getchar",
+    "index",
+    "int64_t",
+    "labs",
+    "main",
+    "malloc",
+    "memcpy",
+    "printf",
+    "putchar",
+    "rand",
+    "realloc",
+    "size_t",
+    "stderr",
+    "stdin",
+    "stdout",
+    "strcmp",
+    "strlen",
+    "uint64_t",
+];
+
+const MAX_INT: u64 = i64::MAX as u64;
+
+pub fn to_c(source: &str) -> Result<SideBySide, ParseError> {
+    let program = parse(source)?;
+    let mut c = C {
+        source,
+        contexts: vec![],
+        names: HashSet::new(),
+        globals: vec![],
+        prototypes: vec![],
+        functions: vec![],
+        hoisted: HashMap::new(),
+        constants: constants(source),
+        labels: HashSet::new(),
+    };
+    c.enter(Some(String::new()));
+    c.w().detach();
+    c.w().line("int main(void) {");
+    c.w().indent();
+    c.hoist(&program);
+    c.stmts(&program);
+    c.w().detach();
+    c.w().line("return 0;");
+    c.w().dedent();
+    c.w().line("}");
+    let main = c.contexts.pop().unwrap().w;
+
+    let mut prelude: Vec<&str> = RUNTIME.lines().collect();
+    prelude.push("");
+    for lines in [&c.globals, &c.prototypes] {
+        if !lines.is_empty() {
+            prelude.extend(lines.iter().map(|line| line.as_str()));
+            prelude.push("");
+        }
+    }
+    let mut w = Writer::new(source, "    ");
+    w.prepend(&prelude);
+    for function in c.functions {
+        w.append(function);
+    }
+    w.append(main);
+    Ok(w.finish(source))
+}
+
+#[derive(Clone)]
+enum Binding {
+    Var(String),
+    Func { c_name: String, arity: Arity },
+}
+
+// Function being generated, or the program body
+struct Context<'a> {
+    w: Writer,
+    blocks: Vec<HashMap<&'a str, Binding>>,
+    globals_prefix: Option<String>, // Variables are file-scope globals named with this prefix
+    labels: Vec<(&'a str, String)>, // Prefixes of the C labels of the labelled loops being generated
+}
+
+struct C<'a> {
+    source: &'a str,
+    contexts: Vec<Context<'a>>,
+    names: HashSet<String>, // File-scope names
+    globals: Vec<String>,
+    prototypes: Vec<String>,
+    functions: Vec<Writer>,
+    hoisted: HashMap<usize, String>, // C name of each top-level function by the start of its declaration
+    constants: HashSet<usize>,
+    labels: HashSet<String>, // Prefixes of the C labels, which are unique in the file
+}
+
+impl<'a> C<'a> {
+    fn w(&mut self) -> &mut Writer {
+        &mut self.contexts.last_mut().unwrap().w
+    }
+
+    fn enter(&mut self, globals_prefix: Option<String>) {
+        self.contexts.push(Context {
+            w: Writer::new(self.source, "    "),
+            blocks: vec![HashMap::new()],
+            globals_prefix,
+            labels: vec![],
+        });
+    }
+
+    fn blocks(&mut self) -> &mut Vec<HashMap<&'a str, Binding>> {
+        &mut self.contexts.last_mut().unwrap().blocks
+    }
+
+    fn bind(&mut self, name: &'a str, binding: Binding) {
+        self.blocks().last_mut().unwrap().insert(name, binding);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.contexts
+            .iter()
+            .rev()
+            .flat_map(|context| context.blocks.iter().rev())
+            .find_map(|block| block.get(name))
+    }
+
+    // Unused file-scope name based on `name`
+    fn file_scope_name(&mut self, name: &str) -> String {
+        let base = c_ident(name);
+        let mut c_name = base.clone();
+        let mut n = 2;
+        while self.names.contains(&c_name) {
+            c_name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        self.names.insert(c_name.clone());
+        c_name
+    }
+
+    fn global(&mut self, name: &str) -> String {
+        let c_name = self.file_scope_name(name);
+        self.globals.push(format!("static MoValue {};", c_name));
+        c_name
+    }
+
+    // Top-level functions and variables can be used by functions declared before them
+    fn hoist(&mut self, program: &'a [Stmt]) {
+        for stmt in program {
+            match stmt {
+                Stmt::VarStmt(VarStmt { ident, .. }, _) if self.lookup(&ident.name).is_none() => {
+                    let c_name = self.global(&ident.name);
+                    self.bind(&ident.name, Binding::Var(c_name));
+                }
+                Stmt::FuncDecl(func_decl, span)
+                    if self.lookup(&func_decl.ident_func.name).is_none() =>
+                {
+                    let c_name = self.file_scope_name(&func_decl.ident_func.name);
+                    self.hoisted.insert(span.start, c_name.clone());
+                    let arity = Arity::params(&func_decl.default_param);
+                    self.bind(&func_decl.ident_func.name, Binding::Func { c_name, arity });
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn stmts(&mut self, stmts: &'a [Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    // Statements of a block, closed with `}` by the caller
+    fn body(&mut self, stmts: &'a [Stmt]) {
+        self.w().indent();
+        self.stmts(stmts);
+        self.w().dedent();
+    }
+
+    fn block(&mut self, stmts: &'a [Stmt]) {
+        self.blocks().push(HashMap::new());
+        self.body(stmts);
+        self.blocks().pop();
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt) {
+        self.w().at(stmt.span());
+        match stmt {
+            Stmt::BlockStmt(stmts, _) => {
+                self.w().line("{");
+                self.block(stmts);
+                self.w().line("}");
+            }
+            Stmt::FuncDecl(func_decl, span) => self.func_decl(func_decl, *span),
+            Stmt::IfStmt(if_stmt, _) => {
+                self.if_stmt(if_stmt, "if");
+                self.w().line("}");
+            }
+            Stmt::ForStmt(for_stmt, _) => self.for_stmt(for_stmt),
+            Stmt::WhileStmt(WhileStmt { label, cond, block }, _) => {
+                let line = format!("while (mo_test({})) {{", self.expr(cond));
+                self.w().line(&line);
+                self.blocks().push(HashMap::new());
+                let break_label = self.loop_body(label, block);
+                self.blocks().pop();
+                self.w().line("}");
+                if let Some(line) = break_label {
+                    self.w().line(&line);
+                }
+            }
+            Stmt::DoWhileStmt(DoWhileStmt { label, block, cond }, _) => {
+                self.w().line("do {");
+                self.blocks().push(HashMap::new());
+                let break_label = self.loop_body(label, block);
+                self.blocks().pop();
+                let line = format!("}} while (mo_test({}));", self.expr(cond));
+                self.w().line(&line);
+                if let Some(line) = break_label {
+                    self.w().line(&line);
+                }
+            }
+            Stmt::SwitchStmt(switch_stmt, _) => self.switch_stmt(switch_stmt),
+            Stmt::VarStmt(var_stmt, _) => {
+                let line = format!("{};", self.var_stmt(var_stmt));
+                self.w().line(&line);
+            }
+            Stmt::ExprStmt(expr, _) => {
+                let line = match expr {
+                    Expr::Ident(_) => format!("mo_check({});", self.expr(expr)), // Reading an unassigned variable fails
+                    _ => format!("{};", self.expr(expr)),
+                };
+                self.w().line(&line);
+            }
+            Stmt::ContinueStmt(None, _) => self.w().line("continue;"),
+            Stmt::BreakStmt(None, _) => self.w().line("break;"),
+            Stmt::ContinueStmt(Some(label), _) => {
+                let line = format!("goto {}_continue;", self.label(label));
+                self.w().line(&line);
+            }
+            Stmt::BreakStmt(Some(label), _) => {
+                let line = format!("goto {}_break;", self.label(label));
+                self.w().line(&line);
+            }
+            Stmt::ReturnStmt(ReturnStmt { expr }, _) => {
+                let line = match expr {
+                    Some(expr) => format!("return {};", self.expr(expr)),
+                    None => "return mo_none();".to_string(),
+                };
+                self.w().line(&line);
+            }
+            Stmt::Unreachable(..) => (),
+            Stmt::Error(_) => unreachable!(),
+        }
+    }
+
+    fn func_decl(&mut self, func_decl: &'a FuncDecl, span: Span) {
+        let FuncDecl {
+            ident_func,
+            ident_param,
+            default_param,
+            block,
+            ..
+        } = func_decl;
+        let c_name = match self.hoisted.remove(&span.start) {
+            Some(c_name) => c_name,
+            None => self.file_scope_name(&ident_func.name),
+        };
+        let arity = Arity::params(default_param);
+        let binding = Binding::Func {
+            c_name: c_name.clone(),
+            arity,
+        };
+        self.bind(&ident_func.name, binding);
+
+        // Parameters share the scope with the body in Momonga
+        let index = self.functions.len(); // Nested functions follow the enclosing one
+        let globals_prefix = declares_func(block).then(|| format!("{}_", c_name));
+        self.enter(globals_prefix.clone());
+        self.w().at(span);
+        let mut params = vec![];
+        let mut prologue = vec![];
+        for (ident, default) in ident_param.iter().zip(default_param) {
+            let param = c_ident(&ident.name);
+            // Missing arguments are undefined, and get their default values seeing the parameters before them
+            if let Some(default) = default {
+                let default = self.expr(default);
+                prologue.push(format!(
+                    "if ({}.tag == MO_UNDEFINED) {{ {} = {}; }}",
+                    param, param, default
+                ));
+            }
+            let c_name = match &globals_prefix {
+                Some(prefix) => {
+                    let global = self.global(&format!("{}{}", prefix, ident.name));
+                    prologue.push(format!("{} = {};", global, param));
+                    global
+                }
+                None => param.clone(),
+            };
+            self.bind(&ident.name, Binding::Var(c_name));
+            params.push(format!("MoValue {}", param));
+        }
+        let params = match params.is_empty() {
+            true => "void".to_string(),
+            false => params.join(", "),
+        };
+        let signature = format!("static MoValue {}({})", c_name, params);
+        self.prototypes.push(format!("{};", signature));
+
+        self.w().line(&format!("{} {{", signature));
+        self.w().indent();
+        for line in prologue {
+            self.w().line(&line);
+        }
+        self.stmts(block);
+        if !matches!(reachable(block).last(), Some(Stmt::ReturnStmt(..))) {
+            self.w().line("return mo_none();");
+        }
+        self.w().dedent();
+        self.w().line("}");
+        self.w().detach();
+        self.w().line("");
+        let function = self.contexts.pop().unwrap().w;
+        self.functions.insert(index, function);
+    }
+
+    fn if_stmt(&mut self, if_stmt: &'a IfStmt, keyword: &str) {
+        let IfStmt {
+            condition,
+            block,
+            else_clause,
+        } = if_stmt;
+        let line = format!("{} (mo_test({})) {{", keyword, self.expr(condition));
+        self.w().line(&line);
+        self.block(block);
+        match else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => self.if_stmt(if_stmt, "} else if"),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => {
+                self.w().line("} else {");
+                self.block(block);
+            }
+            None => (),
+        }
+    }
+
+    // A chain of `if` comparing the subject with `mo_eq`, as `switch` of C only takes integers and
+    // is left by `break`
+    fn switch_stmt(&mut self, switch_stmt: &'a SwitchStmt) {
+        let SwitchStmt {
+            subject,
+            cases,
+            default,
+        } = switch_stmt;
+        let subject = match subject {
+            Expr::Ident(_) => format!("mo_check({})", self.expr(subject)),
+            _ => self.expr(subject),
+        };
+        if cases.is_empty() {
+            self.w().line(&format!("{};", subject));
+            if let Some(block) = default {
+                self.w().line("{");
+                self.block(block);
+                self.w().line("}");
+            }
+            return;
+        }
+        self.w().line("{");
+        self.w().indent();
+        self.w().line(&format!("MoValue mo_subject = {};", subject));
+        for (i, SwitchStmtCase { value, block }) in cases.iter().enumerate() {
+            let keyword = if i == 0 { "if" } else { "} else if" };
+            let value = self.expr(value);
+            let line = format!("{} (mo_test(mo_eq(mo_subject, {}))) {{", keyword, value);
+            self.w().line(&line);
+            self.block(block);
+        }
+        if let Some(block) = default {
+            self.w().line("} else {");
+            self.block(block);
+        }
+        self.w().line("}");
+        self.w().dedent();
+        self.w().line("}");
+    }
+
+    fn for_stmt(&mut self, for_stmt: &'a ForStmt) {
+        let ForStmt {
+            label,
+            init,
+            cond,
+            afterthought,
+            block,
+        } = for_stmt;
+        // The loop variable shares the scope with the body in Momonga
+        self.blocks().push(HashMap::new());
+        let init = match init {
+            Some(ForStmtInit::Var(var_stmt)) => self.var_stmt(var_stmt),
+            Some(ForStmtInit::Expr(expr)) => self.expr(expr),
+            None => String::new(),
+        };
+        let cond = match cond {
+            Some(cond) => format!(" mo_test({})", self.expr(cond)),
+            None => String::new(),
+        };
+        let afterthought = match afterthought {
+            Some(afterthought) => format!(" {}", self.expr(afterthought)),
+            None => String::new(),
+        };
+        let line = format!("for ({};{};{}) {{", init, cond, afterthought);
+        self.w().line(&line);
+        let break_label = self.loop_body(label, block);
+        self.w().line("}");
+        if let Some(line) = break_label {
+            self.w().line(&line);
+        }
+        self.blocks().pop();
+    }
+
+    // Statements of the block of a loop, closed with `}` by the caller, followed by the label
+    // which a labelled `continue` jumps to. The label for `break`, to be put after the loop, is
+    // returned if it is jumped to.
+    fn loop_body(&mut self, label: &'a Option<Ident>, block: &'a [Stmt]) -> Option<String> {
+        let Some(label) = label else {
+            self.body(block);
+            return None;
+        };
+        let base = c_ident(&label.name);
+        let mut prefix = base.clone();
+        let mut n = 2;
+        while self.labels.contains(&prefix) {
+            prefix = format!("{}_{}", base, n);
+            n += 1;
+        }
+        self.labels.insert(prefix.clone());
+        let context = self.contexts.last_mut().unwrap();
+        context.labels.push((&label.name, prefix.clone()));
+
+        let jumps = labelled_jumps(block);
+        self.w().indent();
+        self.stmts(block);
+        if jumps.contains(&(&label.name, Jump::Continue)) {
+            self.w().line(&format!("{}_continue:;", prefix));
+        }
+        self.w().dedent();
+        self.contexts.last_mut().unwrap().labels.pop();
+        jumps
+            .contains(&(&label.name, Jump::Break))
+            .then(|| format!("{}_break:;", prefix))
+    }
+
+    // Prefix of the C labels of the loop labelled `label`
+    fn label(&self, label: &Ident) -> String {
+        let labels = &self.contexts.last().unwrap().labels;
+        let (_, prefix) = labels
+            .iter()
+            .rev()
+            .find(|(name, _)| *name == label.name)
+            .unwrap();
+        prefix.clone()
+    }
+
+    // Declaration, or assignment to a global or a variable redeclared in the same block
+    fn var_stmt(&mut self, var_stmt: &'a VarStmt) -> String {
+        let VarStmt { ident, expr, .. } = var_stmt;
+        let value = match expr {
+            Some(expr) => self.expr(expr),
+            None => "mo_none()".to_string(),
+        };
+        if let Some(Binding::Var(c_name)) = self.blocks().last().unwrap().get(ident.name.as_str()) {
+            return format!("{} = {}", c_name, value);
+        }
+        if let Some(prefix) = self.contexts.last().unwrap().globals_prefix.clone() {
+            let c_name = self.global(&format!("{}{}", prefix, ident.name));
+            self.bind(&ident.name, Binding::Var(c_name.clone()));
+            return format!("{} = {}", c_name, value);
+        }
+
+        // The initializer refers to the shadowed variable, which the declaration would hide
+        let base = c_ident(&ident.name);
+        let mut c_name = base.clone();
+        if expr.as_ref().is_some_and(|expr| refers(expr, &ident.name))
+            && self.lookup(&ident.name).is_some()
+        {
+            let mut n = 2;
+            c_name = format!("{}_{}", base, n);
+            while self.blocks().iter().any(|block| {
+                block
+                    .values()
+                    .any(|binding| matches!(binding, Binding::Var(name) if *name == c_name))
+            }) {
+                n += 1;
+                c_name = format!("{}_{}", base, n);
+            }
+        }
+        self.bind(&ident.name, Binding::Var(c_name.clone()));
+        format!("MoValue {} = {}", c_name, value)
+    }
+
+    fn expr(&mut self, expr: &Expr) -> String {
+        match expr {
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Ident(ident) => match self.lookup(&ident.name) {
+                Some(Binding::Var(c_name)) => c_name.clone(),
+                Some(Binding::Func { .. }) => error("Invalid expression error"),
+                None => error("Name error"),
+            },
+            Expr::PrefixOp { kind, rhs } => match (kind, &**rhs) {
+                (PrefixOpKind::Neg, Expr::Literal(Literal::Int(int))) if *int == MAX_INT + 1 => {
+                    "mo_int(INT64_MIN)".to_string()
+                }
+                (PrefixOpKind::Neg, Expr::Literal(Literal::Int(int))) if *int <= MAX_INT => {
+                    format!("mo_int(-{})", int)
+                }
+                (PrefixOpKind::Neg, _) => format!("mo_neg({})", self.expr(rhs)),
+                (PrefixOpKind::Pos, _) => format!("mo_pos({})", self.expr(rhs)),
+                (PrefixOpKind::Not, _) => format!("mo_not({})", self.expr(rhs)),
+                (PrefixOpKind::BitNot, _) => format!("mo_bit_not({})", self.expr(rhs)),
+            },
+            Expr::InfixOp { kind, lhs, rhs } => self.infix(kind, lhs, rhs),
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs,
+            } => format!("mo_index({}, {})", self.expr(lhs), self.expr(index)),
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Call(args),
+                lhs,
+            } => self.call(lhs, args),
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let cond = self.expr(cond);
+                let then = self.expr(then);
+                let otherwise = match &**otherwise {
+                    // The last operand of `?:` cannot be an assignment in C
+                    Expr::InfixOp { kind, .. } if kind.is_assign() => {
+                        format!("({})", self.expr(otherwise))
+                    }
+                    _ => self.expr(otherwise),
+                };
+                format!("(mo_test({}) ? {} : {})", cond, then, otherwise)
+            }
+        }
+    }
+
+    fn infix(&mut self, kind: &InfixOpKind, lhs: &Expr, rhs: &Expr) -> String {
+        let helper = match kind {
+            InfixOpKind::Add
+            | InfixOpKind::Subtract
+            | InfixOpKind::Multiply
+            | InfixOpKind::Divide
+            | InfixOpKind::Modulo => arithmetic_helper(kind),
+            InfixOpKind::Power => "mo_pow",
+            InfixOpKind::Eq => "mo_eq",
+            InfixOpKind::NotEq => "mo_ne",
+            InfixOpKind::Gt => "mo_gt",
+            InfixOpKind::Ge => "mo_ge",
+            InfixOpKind::Lt => "mo_lt",
+            InfixOpKind::Le => "mo_le",
+            InfixOpKind::BitAnd => "mo_bit_and",
+            InfixOpKind::BitOr => "mo_bit_or",
+            InfixOpKind::BitXor => "mo_bit_xor",
+            InfixOpKind::ShiftLeft => "mo_shl",
+            InfixOpKind::ShiftRight => "mo_shr",
+            InfixOpKind::And | InfixOpKind::Or => {
+                let symbol = if *kind == InfixOpKind::And {
+                    "&&"
+                } else {
+                    "||"
+                };
+                return format!(
+                    "mo_bool(mo_test({}) {} mo_test({}))",
+                    self.expr(lhs),
+                    symbol,
+                    self.expr(rhs)
+                );
+            }
+            InfixOpKind::Assign
+            | InfixOpKind::AddAssign
+            | InfixOpKind::SubtractAssign
+            | InfixOpKind::MultiplyAssign
+            | InfixOpKind::DivideAssign
+            | InfixOpKind::ModuloAssign => return self.assign(kind, lhs, rhs),
+        };
+        format!("{}({}, {})", helper, self.expr(lhs), self.expr(rhs))
+    }
+
+    // `x += v` as `x = x + v`, and assignments to items through helpers checking the index
+    fn assign(&mut self, kind: &InfixOpKind, lhs: &Expr, rhs: &Expr) -> String {
+        match lhs {
+            Expr::Ident(ident) => {
+                let value = match kind.compound() {
+                    Some(op) => self.infix(&op, lhs, rhs),
+                    None => self.expr(rhs),
+                };
+                if self.constants.contains(&ident.span.start) {
+                    return format!("({}, {})", value, error("Constant assignment error"));
+                }
+                match self.lookup(&ident.name) {
+                    Some(Binding::Var(c_name)) => format!("{} = {}", c_name, value),
+                    Some(Binding::Func { .. }) => format!("({}, {})", value, error("Type error")),
+                    None => format!("({}, {})", value, error("Name error")),
+                }
+            }
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs: array,
+            } => {
+                let args = [self.expr(array), self.expr(index), self.expr(rhs)].join(", ");
+                match kind.compound() {
+                    Some(op) => format!("mo_update({}, {})", args, arithmetic_helper(&op)),
+                    None => format!("mo_store({})", args),
+                }
+            }
+            _ => error("Type error"), // Only variables and items can be assigned
+        }
+    }
+
+    fn call(&mut self, callee: &Expr, args: &[Expr]) -> String {
+        let Expr::Ident(ident) = callee else {
+            return error("Type error"); // Functions are only called by name
+        };
+        match self.lookup(&ident.name).cloned() {
+            // Checked before evaluating the arguments
+            Some(Binding::Func { arity, .. }) if !arity.accepts(args.len()) => {
+                argument_error(&ident.name, arity, args.len())
+            }
+            // Missing arguments are undefined for the function to give them their default values
+            Some(Binding::Func { c_name, arity }) => {
+                let mut args: Vec<String> = args.iter().map(|arg| self.expr(arg)).collect();
+                args.resize(arity.max.unwrap(), "MO_UNDEFINED_VALUE".to_string());
+                format!("{}({})", c_name, args.join(", "))
+            }
+            Some(Binding::Var(_)) => error("Type error"),
+            // Each builtin is implemented by `mo_<name>` in the runtime
+            None => match builtin(&ident.name) {
+                Some(&Builtin { name, arity, .. }) if !arity.accepts(args.len()) => {
+                    argument_error(name, arity, args.len())
+                }
+                Some(&Builtin { name, arity, .. }) => {
+                    let args: Vec<String> = args.iter().map(|arg| self.expr(arg)).collect();
+                    if arity.max.is_some() {
+                        return format!("mo_{}({})", name, args.join(", "));
+                    }
+                    // Variadic builtins are folded over functions of two arguments, which for
+                    // `print` and `push` write each value but the last and append each item
+                    let (step, end) = match name {
+                        "print" => ("mo_spaced".to_string(), Some("mo_print")),
+                        "push" => ("mo_append".to_string(), Some("mo_copy")),
+                        _ => (format!("mo_{}", name), None),
+                    };
+                    let folded = args
+                        .into_iter()
+                        .reduce(|acc, arg| format!("{}({}, {})", step, acc, arg))
+                        .unwrap();
+                    match end {
+                        Some(end) => format!("{}({})", end, folded),
+                        None => folded,
+                    }
+                }
+                None => error("Name error"),
+            },
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) -> String {
+        match literal {
+            Literal::Bool(bool) => format!("mo_bool({})", bool),
+            Literal::Int(int) if *int > MAX_INT => error("Out of range error"),
+            Literal::Int(int) => format!("mo_int({})", int),
+            Literal::String(string) => format!("mo_str(\"{}\")", escape_string(string)),
+            Literal::Array(exprs) => {
+                let mut items = vec![exprs.len().to_string()];
+                items.extend(exprs.iter().map(|expr| self.expr(expr)));
+                format!("mo_array({})", items.join(", "))
+            }
+            Literal::None => "mo_none()".to_string(),
+        }
+    }
+}
+
+fn arithmetic_helper(kind: &InfixOpKind) -> &'static str {
+    match kind {
+        InfixOpKind::Add => "mo_add",
+        InfixOpKind::Subtract => "mo_sub",
+        InfixOpKind::Multiply => "mo_mul",
+        InfixOpKind::Divide => "mo_div",
+        _ => "mo_mod",
+    }
+}
+
+fn error(message: &str) -> String {
+    format!("mo_error(\"{}\")", message)
+}
+
+fn argument_error(name: &str, arity: Arity, count: usize) -> String {
+    let name = name.to_string();
+    error(&EvalError::Argument { name, arity, count }.to_string())
+}
+
+fn c_ident(name: &str) -> String {
+    if RESERVED.contains(&name) || name.starts_with("mo_") || name.starts_with("MO_") {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::c::*;
+
+    fn body(src: &str) -> String {
+        let target = to_c(src).unwrap().target;
+        target.strip_prefix(RUNTIME).unwrap().to_string()
+    }
+
+    #[test]
+    fn program_is_translated_to_c() {
+        let tests = [
+            ("", "\nint main(void) {\n    return 0;\n}\n"),
+            (
+                "var x = [1, \"a\", true, none]; { var x = x[0]; var x; }",
+                "\nstatic MoValue x;\nstatic MoValue x_2;\n\nint main(void) {\n    x = mo_array(4, mo_int(1), mo_str(\"a\"), mo_bool(true), mo_none());\n    {\n        x_2 = mo_index(x, mo_int(0));\n        x_2 = mo_none();\n    }\n    return 0;\n}\n",
+            ),
+            (
+                "print(add(1)); print(add()); func add(a, b = a) { var c = a + b; return c; }",
+                "\nstatic MoValue add(MoValue a, MoValue b);\n\nstatic MoValue add(MoValue a, MoValue b) {\n    if (b.tag == MO_UNDEFINED) { b = a; }\n    MoValue c = mo_add(a, b);\n    return c;\n}\n\nint main(void) {\n    mo_print(add(mo_int(1), MO_UNDEFINED_VALUE));\n    mo_print(mo_error(\"`add` expects 1 to 2 arguments, got 0\"));\n    return 0;\n}\n",
+            ),
+            (
+                "func f(n) { func g() { return n; } if (n > 0 || !true) { n = g(); } }",
+                "\nstatic MoValue f_n;\n\nstatic MoValue f(MoValue n);\nstatic MoValue g(void);\n\nstatic MoValue f(MoValue n) {\n    f_n = n;\n    if (mo_test(mo_bool(mo_test(mo_gt(f_n, mo_int(0))) || mo_test(mo_not(mo_bool(true)))))) {\n        f_n = g();\n    }\n    return mo_none();\n}\n\nstatic MoValue g(void) {\n    return f_n;\n}\n\nint main(void) {\n    return 0;\n}\n",
+            ),
+            (
+                "func f() { for (var i = 0; i < 2; i = i + 1) { var int = -i; } } f; y = 1; len(); 9223372036854775808; -9223372036854775808;",
+                "\nstatic MoValue f(void);\n\nstatic MoValue f(void) {\n    for (MoValue i = mo_int(0); mo_test(mo_lt(i, mo_int(2))); i = mo_add(i, mo_int(1))) {\n        MoValue int_ = mo_neg(i);\n    }\n    return mo_none();\n}\n\nint main(void) {\n    mo_check(mo_error(\"Invalid expression error\"));\n    (mo_int(1), mo_error(\"Name error\"));\n    mo_error(\"`len` expects 1 argument, got 0\");\n    mo_error(\"Out of range error\");\n    mo_int(INT64_MIN);\n    return 0;\n}\n",
+            ),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(body(src), expected, "Failed in test case: {}", src);
+        }
+    }
+
+    #[test]
+    fn runtime_is_not_attributed_to_source() {
+        let side_by_side = to_c("var x = 1;\nprint(x);\n").unwrap();
+        let runtime_lines = RUNTIME.lines().count();
+        assert!(side_by_side.source_lines[..runtime_lines]
+            .iter()
+            .all(|&line| line == 0));
+        assert_eq!(
+            side_by_side.source_lines[runtime_lines..],
+            [0, 0, 0, 0, 1, 2, 0, 0]
+        );
+    }
+}
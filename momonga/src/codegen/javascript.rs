@@ -0,0 +1,871 @@
+// JavaScript backend.
+//
+// Unlike the Python backend, the generated code behaves exactly like `eval` wherever JavaScript
+// allows: Integers are `BigInt`s, and operations JavaScript would coerce are routed through small
+// runtime helpers (prefixed with `$`, which Momonga names cannot contain) throwing the same errors
+// as `EvalError`. Helpers are emitted only when used.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::*;
+use crate::codegen::{constants, escape_string, refers, SideBySide, Writer};
+use crate::data::{builtin, Arity};
+use crate::error::{EvalError, ParseError};
+use crate::parser::parse;
+
+// JavaScript reserved words and globals, which are valid names in Momonga
+const RESERVED: [&str; 46] = [
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "void",
+    "yield",
+];
+
+// Name, helpers it depends on, and code of each runtime helper, dependencies first
+const HELPERS: [(&str, &[&str], &str); 26] = [
+    ("$error", &[], "function $error(message) { throw new Error(message); }"),
+    ("$bool", &["$error"], "function $bool(v) { return typeof v === \"boolean\" ? v : $error(\"Type error\"); }"),
+    ("$int", &["$error"], "function $int(v) { return typeof v === \"bigint\" ? v : $error(\"Type error\"); }"),
+    ("$neg", &["$int"], "function $neg(v) { return $int(v) === -9223372036854775808n ? $error(\"Out of range error\") : -v; }"),
+    ("$range", &["$error"], "function $range(v) { return v === BigInt.asIntN(64, v) ? v : $error(\"Out of range error\"); }"),
+    (
+        "$add",
+        &["$range"],
+        "function $add(a, b) { return typeof a === typeof b && typeof a === \"bigint\" ? $range(a + b) : typeof a === typeof b && typeof a === \"string\" ? a + b : $error(\"Type error\"); }",
+    ),
+    ("$div", &["$int", "$range"], "function $div(a, b) { $int(a); return $int(b) === 0n ? $error(\"Zero division error\") : $range(a / b); }"),
+    (
+        "$mod",
+        &["$div"],
+        "function $mod(a, b) { $div(a, b); return a % b; }", // Out of range where the quotient is
+    ),
+    (
+        "$shl",
+        &["$int"],
+        "function $shl(a, b) { $int(a); return $int(b) < 0n || b > 63n ? $error(\"Out of range error\") : BigInt.asIntN(64, a << b); }",
+    ),
+    ("$shr", &["$int"], "function $shr(a, b) { $int(a); return $int(b) < 0n || b > 63n ? $error(\"Out of range error\") : a >> b; }"),
+    (
+        "$pow",
+        &["$int"],
+        "function $pow(a, b) { $int(a); if ($int(b) < 0n) $error(\"Out of range error\"); const v = a ** (b > 64n ? 64n + b % 2n : b); return v === BigInt.asIntN(64, v) ? v : $error(\"Out of range error\"); }",
+    ),
+    (
+        "$same",
+        &[],
+        "function $same(a, b) { return Array.isArray(a) ? Array.isArray(b) && a.length === b.length && a.every((v, i) => $same(v, b[i])) : a === b || (a == null && b == null); }",
+    ),
+    (
+        "$eq",
+        &["$error", "$same"],
+        "function $eq(a, b) { const type = (v) => (Array.isArray(v) ? \"array\" : typeof v); return type(a) === type(b) && [\"boolean\", \"bigint\", \"string\", \"array\"].includes(type(a)) ? $same(a, b) : $error(\"Type error\"); }",
+    ),
+    (
+        "$index",
+        &["$int"],
+        "function $index(a, i) { if ($int(i) < 0n) $error(\"Index error\"); if (!Array.isArray(a)) $error(\"Type error\"); return i < a.length ? a[i] : $error(\"Index error\"); }",
+    ),
+    ("$store", &["$index"], "function $store(a, i, v) { $index(a, i); a[i] = v; return v; }"),
+    ("$update", &["$store"], "function $update(a, i, v, f) { return $store(a, i, f($index(a, i), v)); }"),
+    (
+        "$len",
+        &["$error"],
+        "function $len(v) { return typeof v === \"string\" ? BigInt(new TextEncoder().encode(v).length) : Array.isArray(v) ? BigInt(v.length) : $error(\"Type error\"); }",
+    ),
+    ("$push", &["$error"], "function $push(a, ...vs) { return Array.isArray(a) ? (a.push(...vs), [...a]) : $error(\"Type error\"); }"),
+    (
+        "$pop",
+        &["$error"],
+        "function $pop(a) { return !Array.isArray(a) ? $error(\"Type error\") : a.length > 0 ? a.pop() : $error(\"Index error\"); }",
+    ),
+    (
+        "$print",
+        &[],
+        "function $print(...vs) { const str = (v) => (v == null ? \"none\" : Array.isArray(v) ? `[${v.map(str).join(\", \")}]` : String(v)); console.log(vs.map(str).join(\" \")); return null; }",
+    ),
+    ("$abs", &["$neg"], "function $abs(v) { return $int(v) < 0n ? $neg(v) : v; }"),
+    ("$min", &["$int"], "function $min(...vs) { vs.forEach($int); return vs.reduce((a, b) => (b < a ? b : a)); }"),
+    ("$max", &["$int"], "function $max(...vs) { vs.forEach($int); return vs.reduce((a, b) => (b > a ? b : a)); }"),
+    (
+        "$sqrt",
+        &["$int"],
+        "function $sqrt(v) { if ($int(v) < 0n) $error(\"Out of range error\"); if (v < 2n) return v; let root = v / 2n + 1n, next = (root + v / root) / 2n; while (next < root) [root, next] = [next, (next + v / next) / 2n]; return root; }",
+    ),
+    (
+        "$gcd",
+        &["$int"],
+        "function $gcd(a, b) { $int(a); $int(b); [a, b] = [a < 0n ? -a : a, b < 0n ? -b : b]; while (b !== 0n) [a, b] = [b, a % b]; return a === BigInt.asIntN(64, a) ? a : $error(\"Out of range error\"); }",
+    ),
+    (
+        "$input",
+        &[],
+        "function $input() { $input.lines ??= typeof require === \"function\" ? require(\"fs\").readFileSync(0, \"utf8\").split(/\\r?\\n/).filter((line, i, lines) => i < lines.length - 1 || line !== \"\") : []; return $input.lines.shift() ?? null; }",
+    ),
+];
+
+const PREC_ASSIGN: u8 = 1;
+const PREC_CONDITIONAL: u8 = 2;
+const PREC_OR: u8 = 3;
+const PREC_AND: u8 = 4;
+const PREC_BIT_OR: u8 = 5;
+const PREC_BIT_XOR: u8 = 6;
+const PREC_BIT_AND: u8 = 7;
+const PREC_RELATIONAL: u8 = 8;
+const PREC_SUM: u8 = 9;
+const PREC_PRODUCT: u8 = 10;
+const PREC_UNARY: u8 = 11;
+const PREC_ATOM: u8 = 12;
+
+const MAX_ABS_INT: u64 = i64::MIN.unsigned_abs();
+
+pub fn to_javascript(source: &str) -> Result<SideBySide, ParseError> {
+    let program = parse(source)?;
+    let mut javascript = JavaScript {
+        w: Writer::new(source, "    "),
+        blocks: vec![HashMap::new()],
+        helpers: vec![],
+        constants: constants(source),
+    };
+    javascript.stmts(&program);
+
+    let mut prelude = vec!["\"use strict\";"];
+    for (name, _, code) in HELPERS {
+        if javascript.helpers.contains(&name) {
+            prelude.push(code);
+        }
+    }
+    javascript.w.prepend(&prelude);
+    Ok(javascript.w.finish(source))
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Type {
+    Bool,
+    Int,
+    Unknown,
+}
+
+#[derive(Clone)]
+struct Binding {
+    js_name: String,
+    arity: Option<Arity>, // Of a function
+}
+
+struct JavaScript<'a> {
+    w: Writer,
+    blocks: Vec<HashMap<&'a str, Binding>>,
+    helpers: Vec<&'static str>,
+    constants: HashSet<usize>,
+}
+
+impl<'a> JavaScript<'a> {
+    fn helper(&mut self, name: &'static str) -> &'static str {
+        if !self.helpers.contains(&name) {
+            let (_, deps, _) = HELPERS.iter().find(|(helper, ..)| *helper == name).unwrap();
+            for dep in deps.iter() {
+                self.helper(dep);
+            }
+            self.helpers.push(name);
+        }
+        name
+    }
+
+    // Helper implementing the builtin `name`
+    fn builtin(&mut self, name: &str) -> &'static str {
+        let (helper, ..) = HELPERS
+            .iter()
+            .find(|(helper, ..)| helper[1..] == *name)
+            .unwrap();
+        self.helper(helper)
+    }
+
+    fn error(&mut self, message: &str) -> String {
+        format!("{}(\"{}\")", self.helper("$error"), message)
+    }
+
+    // Whether `name` is already declared in the current block, which JavaScript does not allow
+    fn is_redeclared(&self, name: &str) -> bool {
+        self.blocks.last().unwrap().contains_key(name)
+    }
+
+    fn declare(&mut self, name: &'a str, arity: Option<Arity>, js_name: String) {
+        let binding = Binding { js_name, arity };
+        self.blocks.last_mut().unwrap().insert(name, binding);
+    }
+
+    fn lookup(&self, name: &str) -> Option<&Binding> {
+        self.blocks.iter().rev().find_map(|block| block.get(name))
+    }
+
+    fn resolve(&self, name: &str) -> String {
+        match self.lookup(name) {
+            Some(binding) => binding.js_name.clone(),
+            None => js_ident(name),
+        }
+    }
+
+    fn stmts(&mut self, stmts: &'a [Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    // Statements of a block, closed with `}` by the caller
+    fn body(&mut self, stmts: &'a [Stmt]) {
+        self.w.indent();
+        self.stmts(stmts);
+        self.w.dedent();
+    }
+
+    fn block(&mut self, stmts: &'a [Stmt]) {
+        self.blocks.push(HashMap::new());
+        self.body(stmts);
+        self.blocks.pop();
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt) {
+        self.w.at(stmt.span());
+        match stmt {
+            Stmt::BlockStmt(stmts, _) => {
+                self.w.line("{");
+                self.block(stmts);
+                self.w.line("}");
+            }
+            Stmt::FuncDecl(func_decl, _) => self.func_decl(func_decl),
+            Stmt::IfStmt(if_stmt, _) => {
+                self.if_stmt(if_stmt, "if");
+                self.w.line("}");
+            }
+            Stmt::ForStmt(for_stmt, _) => self.for_stmt(for_stmt),
+            Stmt::WhileStmt(WhileStmt { label, cond, block }, _) => {
+                let line = format!("{}while ({}) {{", loop_label(label), self.condition(cond));
+                self.w.line(&line);
+                self.block(block);
+                self.w.line("}");
+            }
+            Stmt::DoWhileStmt(DoWhileStmt { label, block, cond }, _) => {
+                self.w.line(&format!("{}do {{", loop_label(label)));
+                self.block(block);
+                let line = format!("}} while ({});", self.condition(cond));
+                self.w.line(&line);
+            }
+            Stmt::SwitchStmt(switch_stmt, _) => self.switch_stmt(switch_stmt),
+            Stmt::VarStmt(var_stmt, _) => {
+                let line = format!("{};", self.var_stmt(var_stmt));
+                self.w.line(&line);
+            }
+            Stmt::ExprStmt(expr, _) => {
+                let line = format!("{};", self.expr(expr));
+                self.w.line(&line);
+            }
+            Stmt::ContinueStmt(label, _) => self.w.line(&format!("continue{};", jump_label(label))),
+            Stmt::BreakStmt(label, _) => self.w.line(&format!("break{};", jump_label(label))),
+            Stmt::ReturnStmt(ReturnStmt { expr }, _) => {
+                let line = match expr {
+                    Some(expr) => format!("return {};", self.expr(expr)),
+                    None => "return;".to_string(),
+                };
+                self.w.line(&line);
+            }
+            Stmt::Unreachable(..) => (),
+            Stmt::Error(_) => unreachable!(),
+        }
+    }
+
+    fn func_decl(&mut self, func_decl: &'a FuncDecl) {
+        let FuncDecl {
+            ident_func,
+            ident_param,
+            default_param,
+            block,
+            ..
+        } = func_decl;
+        let redeclared = self.is_redeclared(&ident_func.name);
+        let name = match redeclared {
+            true => self.resolve(&ident_func.name),
+            false => js_ident(&ident_func.name),
+        };
+        let arity = Arity::params(default_param);
+        self.declare(&ident_func.name, Some(arity), name.clone());
+
+        // Parameters share the scope with the body in Momonga, and default values of JavaScript see
+        // the parameters before them as well
+        self.blocks.push(HashMap::new());
+        let params: Vec<String> = ident_param
+            .iter()
+            .zip(default_param)
+            .map(|(ident, default)| {
+                let js_name = js_ident(&ident.name);
+                let param = match default {
+                    Some(default) => format!("{} = {}", js_name, self.expr(default)),
+                    None => js_name.clone(),
+                };
+                self.declare(&ident.name, None, js_name);
+                param
+            })
+            .collect();
+        let line = match redeclared {
+            true => format!("{} = function ({}) {{", name, params.join(", ")),
+            false => format!("function {}({}) {{", name, params.join(", ")),
+        };
+        self.w.line(&line);
+        self.body(block);
+        self.blocks.pop();
+        self.w.line(if redeclared { "};" } else { "}" });
+    }
+
+    fn if_stmt(&mut self, if_stmt: &'a IfStmt, keyword: &str) {
+        let IfStmt {
+            condition,
+            block,
+            else_clause,
+        } = if_stmt;
+        let line = format!("{} ({}) {{", keyword, self.condition(condition));
+        self.w.line(&line);
+        self.block(block);
+        match else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => self.if_stmt(if_stmt, "} else if"),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => {
+                self.w.line("} else {");
+                self.block(block);
+            }
+            None => (),
+        }
+    }
+
+    // A chain of `if` comparing the subject with `$eq`, as `switch` of JavaScript compares with
+    // `===` and is left by `break`
+    fn switch_stmt(&mut self, switch_stmt: &'a SwitchStmt) {
+        let SwitchStmt {
+            subject,
+            cases,
+            default,
+        } = switch_stmt;
+        let subject = self.expr(subject);
+        if cases.is_empty() {
+            self.w.line(&format!("{};", subject));
+            if let Some(block) = default {
+                self.w.line("{");
+                self.block(block);
+                self.w.line("}");
+            }
+            return;
+        }
+        self.w.line("{");
+        self.w.indent();
+        self.w.line(&format!("const $subject = {};", subject));
+        for (i, SwitchStmtCase { value, block }) in cases.iter().enumerate() {
+            let keyword = if i == 0 { "if" } else { "} else if" };
+            let eq = self.helper("$eq");
+            let line = format!("{} ({}($subject, {})) {{", keyword, eq, self.expr(value));
+            self.w.line(&line);
+            self.block(block);
+        }
+        if let Some(block) = default {
+            self.w.line("} else {");
+            self.block(block);
+        }
+        self.w.line("}");
+        self.w.dedent();
+        self.w.line("}");
+    }
+
+    fn for_stmt(&mut self, for_stmt: &'a ForStmt) {
+        let ForStmt {
+            label,
+            init,
+            cond,
+            afterthought,
+            block,
+        } = for_stmt;
+        // The loop variable shares the scope with the body in Momonga
+        self.blocks.push(HashMap::new());
+        let init = match init {
+            Some(ForStmtInit::Var(var_stmt)) => self.var_stmt(var_stmt),
+            Some(ForStmtInit::Expr(expr)) => self.expr(expr),
+            None => String::new(),
+        };
+        let cond = match cond {
+            Some(cond) => format!(" {}", self.condition(cond)),
+            None => String::new(),
+        };
+        let afterthought = match afterthought {
+            Some(afterthought) => format!(" {}", self.expr(afterthought)),
+            None => String::new(),
+        };
+        let line = format!(
+            "{}for ({};{};{}) {{",
+            loop_label(label),
+            init,
+            cond,
+            afterthought
+        );
+        self.w.line(&line);
+        self.body(block);
+        self.w.line("}");
+        self.blocks.pop();
+    }
+
+    // Declaration, or assignment when redeclared in the same block
+    fn var_stmt(&mut self, var_stmt: &'a VarStmt) -> String {
+        let VarStmt { ident, expr, .. } = var_stmt;
+        let value = match expr {
+            Some(expr) => self.expr(expr),
+            None => "null".to_string(),
+        };
+        if self.is_redeclared(&ident.name) {
+            let name = self.resolve(&ident.name);
+            self.declare(&ident.name, None, name.clone());
+            return format!("{} = {}", name, value);
+        }
+
+        // The initializer refers to the shadowed variable, which `let` would hide
+        let mut name = js_ident(&ident.name);
+        if expr.as_ref().is_some_and(|expr| refers(expr, &ident.name))
+            && self.lookup(&ident.name).is_some()
+        {
+            let base = name;
+            let mut n = 2;
+            name = format!("{}_{}", base, n);
+            while self
+                .blocks
+                .iter()
+                .any(|block| block.values().any(|binding| binding.js_name == name))
+            {
+                n += 1;
+                name = format!("{}_{}", base, n);
+            }
+        }
+        self.declare(&ident.name, None, name.clone());
+        format!("let {} = {}", name, value)
+    }
+
+    fn condition(&mut self, expr: &Expr) -> String {
+        self.typed_operand(expr, Type::Bool, 0)
+    }
+
+    fn expr(&mut self, expr: &Expr) -> String {
+        self.prec_expr(expr).0
+    }
+
+    fn operand(&mut self, expr: &Expr, min_prec: u8) -> String {
+        match self.prec_expr(expr) {
+            (code, prec) if prec < min_prec => format!("({})", code),
+            (code, _) => code,
+        }
+    }
+
+    // Operand checked to be of `ty` at runtime unless it is known statically
+    fn typed_operand(&mut self, expr: &Expr, ty: Type, min_prec: u8) -> String {
+        if self.type_of(expr) == ty {
+            return self.operand(expr, min_prec);
+        }
+        let helper = match ty {
+            Type::Bool => self.helper("$bool"),
+            _ => self.helper("$int"),
+        };
+        format!("{}({})", helper, self.expr(expr))
+    }
+
+    fn type_of(&self, expr: &Expr) -> Type {
+        match expr {
+            Expr::Literal(Literal::Bool(_)) => Type::Bool,
+            Expr::Literal(Literal::Int(int)) if *int < MAX_ABS_INT => Type::Int,
+            Expr::PrefixOp { kind, .. } => match kind {
+                PrefixOpKind::Not => Type::Bool,
+                _ => Type::Int,
+            },
+            Expr::InfixOp { kind, rhs, .. } => match kind.compound().as_ref().unwrap_or(kind) {
+                InfixOpKind::Add => Type::Unknown,
+                InfixOpKind::Subtract
+                | InfixOpKind::Multiply
+                | InfixOpKind::Divide
+                | InfixOpKind::Modulo
+                | InfixOpKind::Power
+                | InfixOpKind::BitAnd
+                | InfixOpKind::BitOr
+                | InfixOpKind::BitXor
+                | InfixOpKind::ShiftLeft
+                | InfixOpKind::ShiftRight => Type::Int,
+                InfixOpKind::Assign => self.type_of(rhs),
+                _ => Type::Bool,
+            },
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Call(_),
+                lhs,
+            } => match &**lhs {
+                Expr::Ident(ident) if ident.name == "len" && self.lookup("len").is_none() => {
+                    Type::Int
+                }
+                _ => Type::Unknown,
+            },
+            Expr::Conditional {
+                then, otherwise, ..
+            } => match self.type_of(then) {
+                ty if ty == self.type_of(otherwise) => ty,
+                _ => Type::Unknown,
+            },
+            _ => Type::Unknown,
+        }
+    }
+
+    fn prec_expr(&mut self, expr: &Expr) -> (String, u8) {
+        match expr {
+            Expr::Literal(literal) => (self.literal(literal), PREC_ATOM),
+            Expr::Ident(ident) => {
+                let code = match self.lookup(&ident.name).cloned() {
+                    Some(Binding { arity: Some(_), .. }) => self.error("Invalid expression error"),
+                    Some(binding) => binding.js_name,
+                    None if builtin(&ident.name).is_some() => self.builtin(&ident.name).to_string(),
+                    None => js_ident(&ident.name),
+                };
+                (code, PREC_ATOM)
+            }
+            Expr::PrefixOp { kind, rhs } => match kind {
+                PrefixOpKind::Pos => (self.typed_operand(rhs, Type::Int, PREC_UNARY), PREC_UNARY),
+                PrefixOpKind::Neg => match **rhs {
+                    Expr::Literal(Literal::Int(int)) if int <= MAX_ABS_INT => {
+                        (format!("-{}n", int), PREC_UNARY)
+                    }
+                    _ => (
+                        format!("{}({})", self.helper("$neg"), self.expr(rhs)),
+                        PREC_ATOM,
+                    ),
+                },
+                PrefixOpKind::Not => (
+                    format!("!{}", self.typed_operand(rhs, Type::Bool, PREC_UNARY)),
+                    PREC_UNARY,
+                ),
+                PrefixOpKind::BitNot => (
+                    format!("~{}", self.typed_operand(rhs, Type::Int, PREC_UNARY)),
+                    PREC_UNARY,
+                ),
+            },
+            Expr::InfixOp { kind, lhs, rhs } => self.infix(kind, lhs, rhs),
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs,
+            } => {
+                let helper = self.helper("$index");
+                (
+                    format!("{}({}, {})", helper, self.expr(lhs), self.expr(index)),
+                    PREC_ATOM,
+                )
+            }
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Call(args),
+                lhs,
+            } => {
+                let builtin = match &**lhs {
+                    Expr::Ident(ident) if self.lookup(&ident.name).is_none() => {
+                        builtin(&ident.name)
+                    }
+                    _ => None,
+                };
+                // Functions declared later are not known here, and called unchecked
+                let arity = match &**lhs {
+                    Expr::Ident(ident) => match self.lookup(&ident.name) {
+                        Some(binding) => binding.arity,
+                        None => builtin.map(|builtin| builtin.arity),
+                    },
+                    _ => None,
+                };
+                let callee = match (&**lhs, builtin, arity) {
+                    // Checked before evaluating the arguments
+                    (Expr::Ident(ident), _, Some(arity)) if !arity.accepts(args.len()) => {
+                        let error = EvalError::Argument {
+                            name: ident.name.clone(),
+                            arity,
+                            count: args.len(),
+                        };
+                        return (self.error(&error.to_string()), PREC_ATOM);
+                    }
+                    (_, Some(builtin), _) => self.builtin(builtin.name).to_string(),
+                    (Expr::Ident(ident), None, _) => self.resolve(&ident.name),
+                    // Functions are only called by name
+                    _ => return (self.error("Type error"), PREC_ATOM),
+                };
+                let args: Vec<String> = args.iter().map(|arg| self.expr(arg)).collect();
+                (format!("{}({})", callee, args.join(", ")), PREC_ATOM)
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let code = format!(
+                    "{} ? {} : {}",
+                    self.typed_operand(cond, Type::Bool, PREC_CONDITIONAL + 1),
+                    self.operand(then, PREC_ASSIGN),
+                    self.operand(otherwise, PREC_CONDITIONAL)
+                );
+                (code, PREC_CONDITIONAL)
+            }
+        }
+    }
+
+    fn infix(&mut self, kind: &InfixOpKind, lhs: &Expr, rhs: &Expr) -> (String, u8) {
+        let call = |javascript: &mut Self, helper| {
+            let helper = javascript.helper(helper);
+            (
+                format!(
+                    "{}({}, {})",
+                    helper,
+                    javascript.expr(lhs),
+                    javascript.expr(rhs)
+                ),
+                PREC_ATOM,
+            )
+        };
+        let native = |javascript: &mut Self, symbol, ty, prec| {
+            (
+                format!(
+                    "{} {} {}",
+                    javascript.typed_operand(lhs, ty, prec),
+                    symbol,
+                    javascript.typed_operand(rhs, ty, prec + 1)
+                ),
+                prec,
+            )
+        };
+        match kind {
+            InfixOpKind::Add => call(self, "$add"),
+            InfixOpKind::Subtract => {
+                let (code, _) = native(self, "-", Type::Int, PREC_SUM);
+                (format!("{}({})", self.helper("$range"), code), PREC_ATOM)
+            }
+            InfixOpKind::Multiply => {
+                let (code, _) = native(self, "*", Type::Int, PREC_PRODUCT);
+                (format!("{}({})", self.helper("$range"), code), PREC_ATOM)
+            }
+            InfixOpKind::Divide => call(self, "$div"),
+            InfixOpKind::Modulo => call(self, "$mod"),
+            InfixOpKind::Power => call(self, "$pow"),
+            InfixOpKind::Eq => call(self, "$eq"),
+            InfixOpKind::NotEq => {
+                let (code, _) = call(self, "$eq");
+                (format!("!{}", code), PREC_UNARY)
+            }
+            InfixOpKind::Gt => native(self, ">", Type::Int, PREC_RELATIONAL),
+            InfixOpKind::Ge => native(self, ">=", Type::Int, PREC_RELATIONAL),
+            InfixOpKind::Lt => native(self, "<", Type::Int, PREC_RELATIONAL),
+            InfixOpKind::Le => native(self, "<=", Type::Int, PREC_RELATIONAL),
+            InfixOpKind::And => native(self, "&&", Type::Bool, PREC_AND),
+            InfixOpKind::Or => native(self, "||", Type::Bool, PREC_OR),
+            InfixOpKind::BitAnd => native(self, "&", Type::Int, PREC_BIT_AND),
+            InfixOpKind::BitOr => native(self, "|", Type::Int, PREC_BIT_OR),
+            InfixOpKind::BitXor => native(self, "^", Type::Int, PREC_BIT_XOR),
+            // Range checked, unlike the native operators
+            InfixOpKind::ShiftLeft => call(self, "$shl"),
+            InfixOpKind::ShiftRight => call(self, "$shr"),
+            InfixOpKind::Assign
+            | InfixOpKind::AddAssign
+            | InfixOpKind::SubtractAssign
+            | InfixOpKind::MultiplyAssign
+            | InfixOpKind::DivideAssign
+            | InfixOpKind::ModuloAssign => self.assign(kind, lhs, rhs),
+        }
+    }
+
+    // `x += v` as `x = x + v`, and assignments to items through helpers checking the index
+    fn assign(&mut self, kind: &InfixOpKind, lhs: &Expr, rhs: &Expr) -> (String, u8) {
+        match lhs {
+            Expr::Ident(ident) => {
+                let value = match kind.compound() {
+                    Some(op) => self.infix(&op, lhs, rhs).0,
+                    None => self.operand(rhs, PREC_ASSIGN),
+                };
+                if self.constants.contains(&ident.span.start) {
+                    let error = self.error("Constant assignment error");
+                    return (format!("({}, {})", value, error), PREC_ATOM);
+                }
+                (
+                    format!("{} = {}", self.resolve(&ident.name), value),
+                    PREC_ASSIGN,
+                )
+            }
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs: array,
+            } => {
+                let args = [self.expr(array), self.expr(index), self.expr(rhs)].join(", ");
+                let code = match kind.compound() {
+                    Some(op) => {
+                        let operator = self.operator(&op);
+                        format!("{}({}, {})", self.helper("$update"), args, operator)
+                    }
+                    None => format!("{}({})", self.helper("$store"), args),
+                };
+                (code, PREC_ATOM)
+            }
+            _ => (self.error("Type error"), PREC_ATOM), // Only variables and items can be assigned
+        }
+    }
+
+    // Function applying the operator of a compound assignment to an item and the value
+    fn operator(&mut self, op: &InfixOpKind) -> String {
+        match op {
+            InfixOpKind::Add => self.helper("$add").to_string(),
+            InfixOpKind::Divide => self.helper("$div").to_string(),
+            InfixOpKind::Modulo => self.helper("$mod").to_string(),
+            _ => {
+                let (int, range) = (self.helper("$int"), self.helper("$range"));
+                let symbol = if *op == InfixOpKind::Subtract {
+                    "-"
+                } else {
+                    "*"
+                };
+                format!("(a, b) => {}({}(a) {} {}(b))", range, int, symbol, int)
+            }
+        }
+    }
+
+    fn literal(&mut self, literal: &Literal) -> String {
+        match literal {
+            Literal::Bool(bool) => bool.to_string(),
+            Literal::Int(int) if *int >= MAX_ABS_INT => self.error("Out of range error"),
+            Literal::Int(int) => format!("{}n", int),
+            Literal::String(string) => format!("\"{}\"", escape_string(string)),
+            Literal::Array(exprs) => {
+                let exprs: Vec<String> = exprs.iter().map(|expr| self.expr(expr)).collect();
+                format!("[{}]", exprs.join(", "))
+            }
+            Literal::None => "null".to_string(),
+        }
+    }
+}
+
+fn js_ident(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn loop_label(label: &Option<Ident>) -> String {
+    match label {
+        Some(label) => format!("{}: ", js_ident(&label.name)),
+        None => String::new(),
+    }
+}
+
+fn jump_label(label: &Option<Ident>) -> String {
+    match label {
+        Some(label) => format!(" {}", js_ident(&label.name)),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::javascript::*;
+
+    fn body(src: &str) -> String {
+        let target = to_javascript(src).unwrap().target;
+        target
+            .lines()
+            .filter(|line| !line.starts_with("\"use strict\"") && !line.starts_with("function $"))
+            .map(|line| format!("{}\n", line))
+            .collect()
+    }
+
+    #[test]
+    fn program_is_translated_to_javascript() {
+        let tests = [
+            ("", ""),
+            ("var x; var y = [1, \"a\", true, none];", "let x = null;\nlet y = [1n, \"a\", true, null];\n"),
+            (
+                "func add(a, b) { return a + b; } print(add(1, 2));",
+                "function add(a, b) {\n    return $add(a, b);\n}\n$print(add(1n, 2n));\n",
+            ),
+            (
+                "var x = 1; if (x == 1) { x = 2; } else if (!(x > 2)) {} else { x = x / 2 - 3 * x; }",
+                "let x = 1n;\nif ($eq(x, 1n)) {\n    x = 2n;\n} else if (!($int(x) > 2n)) {\n} else {\n    x = $range($div(x, 2n) - $range(3n * $int(x)));\n}\n",
+            ),
+            ("var x = 1; var x = 2; { var x = x + 1; }", "let x = 1n;\nx = 2n;\n{\n    let x_2 = $add(x, 1n);\n}\n"),
+            (
+                "for (var i = 0; i < 3; i = i + 1) { var i = 5; }",
+                "for (let i = 0n; $int(i) < 3n; i = $add(i, 1n)) {\n    i = 5n;\n}\n",
+            ),
+            ("len(1, 2); func f() {} f; 9223372036854775808; -9223372036854775808;", "$error(\"`len` expects 1 argument, got 2\");\nfunction f() {\n}\n$error(\"Invalid expression error\");\n$error(\"Out of range error\");\n-9223372036854775808n;\n"),
+            ("var let = 1 = 2;", "let let_ = $error(\"Type error\");\n"),
+            (
+                "outer: for (;;) { do { continue outer; } while (false); }",
+                "outer: for (;;) {\n    do {\n        continue outer;\n    } while (false);\n}\n",
+            ),
+            (
+                "for (;;) { switch (x) { case -1: { break; } default: { x; } } } switch (f()) {}",
+                "for (;;) {\n    {\n        const $subject = x;\n        if ($eq($subject, -1n)) {\n            break;\n        } else {\n            x;\n        }\n    }\n}\nf();\n",
+            ),
+            (
+                "var y = x > 0 ? x : a || b ? 1 : 2; (x ? 1 : 2) + 3;",
+                "let y = $int(x) > 0n ? x : $bool(a) || $bool(b) ? 1n : 2n;\n$add($bool(x) ? 1n : 2n, 3n);\n",
+            ),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(body(src), expected, "Failed in test case: {}", src);
+        }
+    }
+
+    #[test]
+    fn only_used_helpers_are_emitted() {
+        assert_eq!(
+            to_javascript("1 < 2;").unwrap().target,
+            "\"use strict\";\n1n < 2n;\n"
+        );
+        let target = to_javascript("print(1 / 2);").unwrap().target;
+        let helpers: Vec<&str> = target
+            .lines()
+            .filter_map(|line| line.strip_prefix("function "))
+            .map(|line| line.split('(').next().unwrap())
+            .collect();
+        assert_eq!(helpers, vec!["$error", "$int", "$range", "$div", "$print"]);
+        assert_eq!(
+            to_javascript("print(1 / 2);").unwrap().source_lines,
+            vec![0, 0, 0, 0, 0, 0, 1]
+        );
+    }
+}
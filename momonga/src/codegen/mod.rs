@@ -0,0 +1,221 @@
+// Code generators translating a program into the languages learners move on to.
+// Type annotations are left out, so values of other types are not rejected as in `eval`.
+mod c;
+mod javascript;
+mod python;
+mod wasm;
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::ast::*;
+use crate::debugger::line_starts;
+use crate::resolver::{resolve, SymbolKind};
+
+pub use c::to_c;
+pub use javascript::to_javascript;
+pub use python::to_python;
+pub use wasm::to_wasm;
+
+// Generated code paired with the source for a side-by-side view
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SideBySide {
+    pub source: String,
+    pub target: String,
+    pub source_lines: Vec<usize>, // Source line (1-based) each target line is generated from, 0 if none
+}
+
+impl SideBySide {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap() // Consists of strings and numbers only
+    }
+}
+
+// Line-oriented output which remembers the source line of each generated line
+struct Writer {
+    line_starts: Vec<usize>,
+    indent_unit: &'static str,
+    indent: usize,
+    source_line: usize,
+    target: String,
+    source_lines: Vec<usize>,
+}
+
+impl Writer {
+    fn new(source: &str, indent_unit: &'static str) -> Self {
+        Self {
+            line_starts: line_starts(source),
+            indent_unit,
+            indent: 0,
+            source_line: 1,
+            target: String::new(),
+            source_lines: vec![],
+        }
+    }
+
+    // Attribute the following lines to the statement at `span`
+    fn at(&mut self, span: Span) {
+        self.source_line = self
+            .line_starts
+            .partition_point(|&start| start <= span.start);
+    }
+
+    // Attribute the following lines to no statement
+    fn detach(&mut self) {
+        self.source_line = 0;
+    }
+
+    fn line(&mut self, line: &str) {
+        self.target.push_str(&self.indent_unit.repeat(self.indent));
+        self.target.push_str(line);
+        self.target.push('\n');
+        self.source_lines.push(self.source_line);
+    }
+
+    // Lines not generated from any statement, such as runtime support code
+    fn prepend(&mut self, lines: &[&str]) {
+        let mut target: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        target.push_str(&self.target);
+        self.target = target;
+        self.source_lines.splice(0..0, lines.iter().map(|_| 0));
+    }
+
+    // Lines generated separately, such as function definitions hoisted out of their scope
+    fn append(&mut self, other: Writer) {
+        self.target.push_str(&other.target);
+        self.source_lines.extend(other.source_lines);
+    }
+
+    fn len(&self) -> usize {
+        self.source_lines.len()
+    }
+
+    fn indent(&mut self) {
+        self.indent += 1;
+    }
+
+    fn dedent(&mut self) {
+        self.indent -= 1;
+    }
+
+    fn finish(self, source: &str) -> SideBySide {
+        SideBySide {
+            source: source.to_string(),
+            target: self.target,
+            source_lines: self.source_lines,
+        }
+    }
+}
+
+// Body of a string literal between double quotes, escaped as in C-family languages
+fn escape_string(string: &str) -> String {
+    let mut escaped = String::new();
+    for char in string.chars() {
+        match char {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            char => escaped.push(char),
+        }
+    }
+    escaped
+}
+
+// Starts of the identifiers referring to constants, which are errors to assign to
+fn constants(source: &str) -> HashSet<usize> {
+    let resolution = resolve(source);
+    resolution
+        .references
+        .iter()
+        .filter(|(_, symbol)| {
+            symbol.is_some_and(|symbol| resolution.symbols[symbol].kind == SymbolKind::Constant)
+        })
+        .map(|(span, _)| span.start)
+        .collect()
+}
+
+// Whether `expr` refers to the variable `name`
+fn refers(expr: &Expr, name: &str) -> bool {
+    match expr {
+        Expr::Literal(Literal::Array(exprs)) => exprs.iter().any(|expr| refers(expr, name)),
+        Expr::Literal(_) => false,
+        Expr::Ident(ident) => ident.name == name,
+        Expr::PrefixOp { rhs, .. } => refers(rhs, name),
+        Expr::InfixOp { lhs, rhs, .. } => refers(lhs, name) || refers(rhs, name),
+        Expr::PostfixOp { kind, lhs } => {
+            refers(lhs, name)
+                || match kind {
+                    PostfixOpKind::Index(index) => refers(index, name),
+                    PostfixOpKind::Call(args) => args.iter().any(|arg| refers(arg, name)),
+                }
+        }
+        Expr::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => [cond, then, otherwise]
+            .iter()
+            .any(|expr| refers(expr, name)),
+    }
+}
+
+// Whether a function is declared in `stmts`, not counting the bodies of functions
+fn declares_func(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::FuncDecl(..) => true,
+        Stmt::BlockStmt(stmts, _) => declares_func(stmts),
+        Stmt::IfStmt(if_stmt, _) => if_declares_func(if_stmt),
+        Stmt::ForStmt(ForStmt { block, .. }, _)
+        | Stmt::WhileStmt(WhileStmt { block, .. }, _)
+        | Stmt::DoWhileStmt(DoWhileStmt { block, .. }, _) => declares_func(block),
+        _ => false,
+    })
+}
+
+fn if_declares_func(if_stmt: &IfStmt) -> bool {
+    declares_func(&if_stmt.block)
+        || match &if_stmt.else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => if_declares_func(if_stmt),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => declares_func(block),
+            None => false,
+        }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Jump {
+    Break,
+    Continue,
+}
+
+// Labelled `break` and `continue` statements run in `stmts`, not counting the bodies of functions
+fn labelled_jumps(stmts: &[Stmt]) -> Vec<(&str, Jump)> {
+    let mut jumps = vec![];
+    for stmt in stmts {
+        match stmt {
+            Stmt::BreakStmt(Some(label), _) => jumps.push((label.name.as_str(), Jump::Break)),
+            Stmt::ContinueStmt(Some(label), _) => jumps.push((label.name.as_str(), Jump::Continue)),
+            Stmt::BlockStmt(stmts, _)
+            | Stmt::ForStmt(ForStmt { block: stmts, .. }, _)
+            | Stmt::WhileStmt(WhileStmt { block: stmts, .. }, _)
+            | Stmt::DoWhileStmt(DoWhileStmt { block: stmts, .. }, _) => {
+                jumps.extend(labelled_jumps(stmts))
+            }
+            Stmt::IfStmt(if_stmt, _) => jumps.extend(if_labelled_jumps(if_stmt)),
+            _ => (),
+        }
+    }
+    jumps
+}
+
+fn if_labelled_jumps(if_stmt: &IfStmt) -> Vec<(&str, Jump)> {
+    let mut jumps = labelled_jumps(&if_stmt.block);
+    match &if_stmt.else_clause {
+        Some(IfStmtElseClause::IfStmt(if_stmt)) => jumps.extend(if_labelled_jumps(if_stmt)),
+        Some(IfStmtElseClause::IfStmtBlock(block)) => jumps.extend(labelled_jumps(block)),
+        None => (),
+    }
+    jumps
+}
@@ -0,0 +1,1016 @@
+// Python backend.
+//
+// The generated code aims to be what a person would write in Python rather than an exact
+// emulation, so values print in Python's own way (`True`, `None`, `['a']`) and `/` and `%`
+// round towards negative infinity. Integers never overflow, and `push` used as a value gives `None`
+// like `list.append`. Block scoping is kept by renaming variables which shadow another one in the
+// same function, and C-style `for` loops counting up become `range`. Python has neither
+// `do`-`while` nor labelled jumps, so `do {} while (c);` becomes `while True:` checking `c` at the
+// end, and a jump out of an inner loop sets a flag checked after each loop it leaves.
+
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::*;
+use crate::codegen::{escape_string, labelled_jumps, Jump, SideBySide, Writer};
+use crate::error::ParseError;
+use crate::parser::parse;
+
+// Python keywords, and builtins the generated code relies on, which are valid names in Momonga
+const RESERVED: [&str; 37] = [
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield", "range", "math",
+];
+
+const PREC_CONDITIONAL: u8 = 1;
+const PREC_OR: u8 = 2;
+const PREC_AND: u8 = 3;
+const PREC_NOT: u8 = 4;
+const PREC_COMPARISON: u8 = 5;
+const PREC_BIT_OR: u8 = 6;
+const PREC_BIT_XOR: u8 = 7;
+const PREC_BIT_AND: u8 = 8;
+const PREC_SHIFT: u8 = 9;
+const PREC_SUM: u8 = 10;
+const PREC_PRODUCT: u8 = 11;
+const PREC_UNARY: u8 = 12;
+const PREC_POWER: u8 = 13;
+const PREC_POSTFIX: u8 = 14;
+const PREC_ATOM: u8 = 15;
+
+pub fn to_python(source: &str) -> Result<SideBySide, ParseError> {
+    let program = parse(source)?;
+    let mut python = Python {
+        w: Writer::new(source, "    "),
+        funcs: vec![FuncScope::new()],
+        uses_math: Cell::new(false),
+    };
+    python.stmts(&program);
+    if python.uses_math.get() {
+        python.w.prepend(&["import math"]);
+    }
+    Ok(python.w.finish(source))
+}
+
+struct Python<'a> {
+    w: Writer,
+    funcs: Vec<FuncScope<'a>>, // The module scope first
+    uses_math: Cell<bool>,     // Whether `sqrt` or `gcd` needs `import math`
+}
+
+// Python has function scope only, so the blocks of a function are tracked here
+struct FuncScope<'a> {
+    blocks: Vec<HashMap<&'a str, String>>, // Momonga names to Python names
+    names: HashSet<String>,                // Python names bound in the function
+    loops: Vec<Loop<'a>>,                  // Enclosing loops, the innermost last
+}
+
+impl<'a> FuncScope<'a> {
+    fn new() -> Self {
+        Self {
+            blocks: vec![HashMap::new()],
+            names: HashSet::new(),
+            loops: vec![],
+        }
+    }
+}
+
+struct Loop<'a> {
+    label: Option<&'a str>,
+    afterthought: Option<&'a Expr>, // Run before `continue`
+    until: Option<String>,          // Checked before `continue` to break out of `do`-`while`
+    flags: Vec<(Jump, String)>,     // Set by the jumps to this loop from inner loops
+}
+
+impl<'a> Loop<'a> {
+    fn new(label: &'a Option<Ident>) -> Self {
+        Self {
+            label: label.as_ref().map(|label| label.name.as_str()),
+            afterthought: None,
+            until: None,
+            flags: vec![],
+        }
+    }
+}
+
+impl<'a> Python<'a> {
+    fn func(&mut self) -> &mut FuncScope<'a> {
+        self.funcs.last_mut().unwrap() // The module scope is never popped
+    }
+
+    fn push_block(&mut self) {
+        self.func().blocks.push(HashMap::new());
+    }
+
+    fn pop_block(&mut self) {
+        self.func().blocks.pop();
+    }
+
+    fn declare(&mut self, name: &'a str) -> String {
+        let func = self.func();
+        if let Some(py_name) = func.blocks.last().unwrap().get(name) {
+            return py_name.clone(); // Redeclared in the same block
+        }
+
+        let shadows = func.blocks.iter().any(|block| block.contains_key(name));
+        let base = py_ident(name);
+        let mut py_name = base.clone();
+        let mut n = 2;
+        while shadows && func.names.contains(&py_name) {
+            py_name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        func.names.insert(py_name.clone());
+        func.blocks
+            .last_mut()
+            .unwrap()
+            .insert(name, py_name.clone());
+        py_name
+    }
+
+    // Python name and the index of the function scope declaring `name`, if declared
+    fn lookup(&self, name: &str) -> Option<(usize, String)> {
+        self.funcs.iter().enumerate().rev().find_map(|(i, func)| {
+            func.blocks
+                .iter()
+                .rev()
+                .find_map(|block| block.get(name))
+                .map(|py_name| (i, py_name.clone()))
+        })
+    }
+
+    fn resolve(&self, name: &str) -> String {
+        match self.lookup(name) {
+            Some((_, py_name)) => py_name,
+            None => py_ident(name),
+        }
+    }
+
+    fn stmts(&mut self, stmts: &'a [Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    // Indented body of a compound statement, which must not be empty in Python
+    fn body(&mut self, stmts: &'a [Stmt], is_loop: bool) {
+        self.w.indent();
+        let len = self.w.len();
+        self.stmts(stmts);
+        if is_loop
+            && !matches!(
+                reachable(stmts).last(),
+                Some(Stmt::ContinueStmt(..) | Stmt::BreakStmt(..) | Stmt::ReturnStmt(..))
+            )
+        {
+            self.loop_end();
+        }
+        if self.w.len() == len {
+            self.w.line("pass");
+        }
+        self.w.dedent();
+    }
+
+    fn block(&mut self, stmts: &'a [Stmt]) {
+        self.push_block();
+        self.body(stmts, false);
+        self.pop_block();
+    }
+
+    // Body of a loop, after which the jumps out of it to outer loops are followed
+    fn loop_body(&mut self, stmts: &'a [Stmt], jumps: Vec<(usize, Jump)>) {
+        self.push_block();
+        self.body(stmts, true);
+        self.pop_block();
+        self.func().loops.pop();
+        for (index, jump) in jumps {
+            let flag = self.flag(index, jump);
+            self.w.line(&format!("if {}:", flag));
+            self.w.indent();
+            if index + 1 == self.func().loops.len() {
+                self.jump(jump, &None);
+            } else {
+                self.w.line("break");
+            }
+            self.w.dedent();
+        }
+    }
+
+    // Loops enclosing a loop to be generated which labelled jumps in `stmts`, its block, jump to.
+    // The flags for the jumps are reset before each run of the loop directly inside the one
+    // jumped to.
+    fn outer_jumps(&mut self, stmts: &'a [Stmt]) -> Vec<(usize, Jump)> {
+        let mut jumps = vec![];
+        for (label, jump) in labelled_jumps(stmts) {
+            let loops = &self.func().loops;
+            let Some(index) = loops.iter().rposition(|l| l.label == Some(label)) else {
+                continue; // To the loop itself or to an inner one
+            };
+            if !jumps.contains(&(index, jump)) {
+                jumps.push((index, jump));
+            }
+        }
+        for &(index, jump) in &jumps {
+            if index + 1 == self.func().loops.len() {
+                let flag = self.flag(index, jump);
+                self.w.line(&format!("{} = False", flag));
+            }
+        }
+        jumps
+    }
+
+    // Flag set by `jump` to the loop at `index` from inner loops, named after its label
+    fn flag(&mut self, index: usize, jump: Jump) -> String {
+        let func = self.func();
+        let flags = &func.loops[index].flags;
+        if let Some((_, flag)) = flags.iter().find(|(flag_jump, _)| *flag_jump == jump) {
+            return flag.clone();
+        }
+        let keyword = match jump {
+            Jump::Break => "break",
+            Jump::Continue => "continue",
+        };
+        let base = format!("{}_{}", keyword, func.loops[index].label.unwrap());
+        let mut flag = base.clone();
+        let mut n = 2;
+        while func.names.contains(&flag) {
+            flag = format!("{}_{}", base, n);
+            n += 1;
+        }
+        func.names.insert(flag.clone());
+        func.loops[index].flags.push((jump, flag.clone()));
+        flag
+    }
+
+    fn jump(&mut self, jump: Jump, label: &Option<Ident>) {
+        let loops = &self.func().loops;
+        let index = match label {
+            Some(label) => loops
+                .iter()
+                .rposition(|l| l.label == Some(label.name.as_str()))
+                .unwrap(),
+            None => loops.len() - 1,
+        };
+        if index + 1 < loops.len() {
+            let flag = self.flag(index, jump);
+            self.w.line(&format!("{} = True", flag));
+            self.w.line("break");
+            return;
+        }
+        if jump == Jump::Continue {
+            self.loop_end();
+        }
+        self.w.line(if jump == Jump::Break {
+            "break"
+        } else {
+            "continue"
+        });
+    }
+
+    // What the innermost loop runs before its next iteration
+    fn loop_end(&mut self) {
+        let innermost = self.func().loops.last().unwrap();
+        let (afterthought, until) = (innermost.afterthought, innermost.until.clone());
+        if let Some(afterthought) = afterthought {
+            self.expr_stmt(afterthought);
+        }
+        if let Some(until) = until {
+            self.w.line(&format!("if {}:", until));
+            self.w.indent();
+            self.w.line("break");
+            self.w.dedent();
+        }
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt) {
+        self.w.at(stmt.span());
+        match stmt {
+            Stmt::BlockStmt(stmts, _) => {
+                self.push_block();
+                self.stmts(stmts);
+                self.pop_block();
+            }
+            Stmt::FuncDecl(func_decl, _) => self.func_decl(func_decl),
+            Stmt::IfStmt(if_stmt, _) => self.if_stmt(if_stmt, "if"),
+            Stmt::ForStmt(for_stmt, _) => self.for_stmt(for_stmt),
+            Stmt::WhileStmt(WhileStmt { label, cond, block }, _) => {
+                let jumps = self.outer_jumps(block);
+                let line = format!("while {}:", self.expr(cond));
+                self.w.line(&line);
+                self.func().loops.push(Loop::new(label));
+                self.loop_body(block, jumps);
+            }
+            Stmt::DoWhileStmt(DoWhileStmt { label, block, cond }, _) => {
+                let jumps = self.outer_jumps(block);
+                self.w.line("while True:");
+                let mut do_while = Loop::new(label);
+                // Out of the scope of the block
+                do_while.until = Some(format!("not {}", self.operand(cond, PREC_NOT)));
+                self.func().loops.push(do_while);
+                self.loop_body(block, jumps);
+            }
+            Stmt::SwitchStmt(switch_stmt, _) => self.switch_stmt(switch_stmt),
+            Stmt::VarStmt(var_stmt, _) => self.var_stmt(var_stmt),
+            Stmt::ExprStmt(expr, _) => self.expr_stmt(expr),
+            Stmt::ContinueStmt(label, _) => self.jump(Jump::Continue, label),
+            Stmt::BreakStmt(label, _) => self.jump(Jump::Break, label),
+            Stmt::ReturnStmt(ReturnStmt { expr }, _) => {
+                let line = match expr {
+                    Some(expr) => format!("return {}", self.expr(expr)),
+                    None => "return".to_string(),
+                };
+                self.w.line(&line);
+            }
+            Stmt::Unreachable(..) => (),
+            Stmt::Error(_) => unreachable!(),
+        }
+    }
+
+    fn func_decl(&mut self, func_decl: &'a FuncDecl) {
+        let FuncDecl {
+            ident_func,
+            ident_param,
+            default_param,
+            block,
+            ..
+        } = func_decl;
+        let name = self.declare(&ident_func.name);
+
+        // Names assigned but not declared in the function belong to the enclosing scopes
+        let mut declared: HashSet<&str> = ident_param
+            .iter()
+            .map(|ident| ident.name.as_str())
+            .collect();
+        let mut assigned = vec![];
+        collect_names(block, &mut declared, &mut assigned);
+        let mut outer_names = vec![];
+        for name in assigned {
+            if declared.contains(name) {
+                continue;
+            }
+            let line = match self.lookup(name) {
+                Some((i, py_name)) if i > 0 => format!("nonlocal {}", py_name),
+                Some((_, py_name)) => format!("global {}", py_name),
+                None => format!("global {}", py_ident(name)),
+            };
+            if !outer_names.contains(&line) {
+                outer_names.push(line);
+            }
+        }
+
+        self.funcs.push(FuncScope::new());
+        let params: Vec<String> = ident_param
+            .iter()
+            .map(|ident| self.declare(&ident.name))
+            .collect();
+        // Default values of Python are evaluated only once, so they are given in the body instead
+        let mut defaults = vec![];
+        let params: Vec<String> = params
+            .into_iter()
+            .zip(default_param)
+            .map(|(param, default)| match default {
+                Some(default) => {
+                    let value = self.expr(default);
+                    defaults.push(format!("if {} is None: {} = {}", param, param, value));
+                    format!("{}=None", param)
+                }
+                None => param,
+            })
+            .collect();
+        self.w
+            .line(&format!("def {}({}):", name, params.join(", ")));
+        self.w.indent();
+        for line in outer_names.iter().chain(&defaults) {
+            self.w.line(line);
+        }
+        self.w.dedent();
+        self.body(block, false);
+        self.funcs.pop();
+    }
+
+    fn if_stmt(&mut self, if_stmt: &'a IfStmt, keyword: &str) {
+        let IfStmt {
+            condition,
+            block,
+            else_clause,
+        } = if_stmt;
+        let line = format!("{} {}:", keyword, self.expr(condition));
+        self.w.line(&line);
+        self.block(block);
+        match else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => self.if_stmt(if_stmt, "elif"),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => {
+                self.w.line("else:");
+                self.block(block);
+            }
+            None => (),
+        }
+    }
+
+    // `match` of Python, whose literal patterns compare with `==` as well
+    fn switch_stmt(&mut self, switch_stmt: &'a SwitchStmt) {
+        let SwitchStmt {
+            subject,
+            cases,
+            default,
+        } = switch_stmt;
+        if cases.is_empty() && default.is_none() {
+            self.expr_stmt(subject); // `match` needs a case
+            return;
+        }
+        let line = format!("match {}:", self.expr(subject));
+        self.w.line(&line);
+        self.w.indent();
+        for SwitchStmtCase { value, block } in cases {
+            let line = format!("case {}:", self.expr(value));
+            self.w.line(&line);
+            self.block(block);
+        }
+        if let Some(block) = default {
+            self.w.line("case _:");
+            self.block(block);
+        }
+        self.w.dedent();
+    }
+
+    fn for_stmt(&mut self, for_stmt: &'a ForStmt) {
+        let ForStmt {
+            label,
+            init,
+            cond,
+            afterthought,
+            block,
+        } = for_stmt;
+        let jumps = self.outer_jumps(block);
+        self.push_block(); // The loop variable belongs to the loop
+        if let Some((ident, args)) = self.range(for_stmt) {
+            let name = self.declare(&ident.name);
+            self.w.line(&format!("for {} in range({}):", name, args));
+            self.func().loops.push(Loop::new(label));
+        } else {
+            match init {
+                Some(ForStmtInit::Var(var_stmt)) => self.var_stmt(var_stmt),
+                Some(ForStmtInit::Expr(expr)) => self.expr_stmt(expr),
+                None => (),
+            }
+            let line = match cond {
+                Some(cond) => format!("while {}:", self.expr(cond)),
+                None => "while True:".to_string(),
+            };
+            self.w.line(&line);
+            let mut for_loop = Loop::new(label);
+            for_loop.afterthought = afterthought.as_ref();
+            self.func().loops.push(for_loop);
+        }
+        self.loop_body(block, jumps);
+        self.pop_block();
+    }
+
+    // `for (var i = a; i < b; i = i + k) {}` as `for i in range(a, b, k):` when the body
+    // modifies neither `i` nor `b`, so that the number of iterations is fixed beforehand
+    fn range(&self, for_stmt: &'a ForStmt) -> Option<(&'a Ident, String)> {
+        let ForStmt {
+            label: _,
+            init:
+                Some(ForStmtInit::Var(VarStmt {
+                    ident,
+                    expr: Some(start),
+                    ..
+                })),
+            cond:
+                Some(Expr::InfixOp {
+                    kind: cmp @ (InfixOpKind::Lt | InfixOpKind::Le),
+                    lhs,
+                    rhs: bound,
+                }),
+            afterthought:
+                Some(Expr::InfixOp {
+                    kind: next_kind,
+                    lhs: target,
+                    rhs: next,
+                }),
+            block,
+        } = for_stmt
+        else {
+            return None;
+        };
+        let is_var =
+            |expr: &Expr| matches!(expr, Expr::Ident(Ident { name, .. }) if *name == ident.name);
+        if !is_var(lhs) || !is_var(target) || assigns(block, &ident.name) {
+            return None;
+        }
+        // `i = i + k` or `i += k`
+        let step = match (next_kind, &**next) {
+            (
+                InfixOpKind::Assign,
+                Expr::InfixOp {
+                    kind: InfixOpKind::Add,
+                    lhs: var,
+                    rhs: step,
+                },
+            ) if is_var(var) => &**step,
+            (InfixOpKind::AddAssign, step) => step,
+            _ => return None,
+        };
+        let Expr::Literal(Literal::Int(step)) = step else {
+            return None;
+        };
+        let stop = match (&**bound, cmp) {
+            (Expr::Literal(Literal::Int(int)), InfixOpKind::Lt) => int.to_string(),
+            (Expr::Literal(Literal::Int(int)), _) => (int + 1).to_string(),
+            (Expr::Ident(bound), _) if bound.name == ident.name || assigns(block, &bound.name) => {
+                return None
+            }
+            (Expr::Ident(bound), InfixOpKind::Lt) => self.resolve(&bound.name),
+            (Expr::Ident(bound), _) => format!("{} + 1", self.resolve(&bound.name)),
+            _ => return None,
+        };
+        let start = self.expr(start);
+        let args = match (start.as_str(), step) {
+            (_, 0) => return None,
+            ("0", 1) => stop,
+            (_, 1) => format!("{}, {}", start, stop),
+            _ => format!("{}, {}, {}", start, stop, step),
+        };
+        Some((ident, args))
+    }
+
+    fn var_stmt(&mut self, var_stmt: &'a VarStmt) {
+        let VarStmt { ident, expr, .. } = var_stmt;
+        let value = match expr {
+            Some(expr) => self.expr(expr), // Refers to the shadowed variable, if any
+            None => "None".to_string(),
+        };
+        let name = self.declare(&ident.name);
+        self.w.line(&format!("{} = {}", name, value));
+    }
+
+    fn expr_stmt(&mut self, expr: &'a Expr) {
+        let line = match expr {
+            // Python rejects such code at compile time, while Momonga fails when it is run
+            expr if has_invalid_target(expr) => {
+                "raise TypeError(\"cannot assign to expression\")".to_string()
+            }
+            Expr::InfixOp {
+                kind: InfixOpKind::Assign,
+                ..
+            } => {
+                let mut targets = vec![];
+                let mut value = expr;
+                while let Expr::InfixOp {
+                    kind: InfixOpKind::Assign,
+                    lhs,
+                    rhs,
+                } = value
+                {
+                    targets.push(self.expr(lhs));
+                    value = rhs;
+                }
+                format!("{} = {}", targets.join(" = "), self.expr(value))
+            }
+            Expr::InfixOp { kind, lhs, rhs } if kind.is_assign() => {
+                let (symbol, _) = infix(&kind.compound().unwrap());
+                format!("{} {}= {}", self.expr(lhs), symbol, self.expr(rhs))
+            }
+            expr => self.expr(expr),
+        };
+        self.w.line(&line);
+    }
+
+    fn expr(&self, expr: &Expr) -> String {
+        self.prec_expr(expr).0
+    }
+
+    fn operand(&self, expr: &Expr, min_prec: u8) -> String {
+        match self.prec_expr(expr) {
+            (code, prec) if prec < min_prec => format!("({})", code),
+            (code, _) => code,
+        }
+    }
+
+    fn prec_expr(&self, expr: &Expr) -> (String, u8) {
+        match expr {
+            Expr::Literal(literal) => (self.literal(literal), PREC_ATOM),
+            Expr::Ident(ident) => (self.resolve(&ident.name), PREC_ATOM),
+            Expr::PrefixOp { kind, rhs } => match kind {
+                PrefixOpKind::Pos => (format!("+{}", self.operand(rhs, PREC_UNARY)), PREC_UNARY),
+                PrefixOpKind::Neg => (format!("-{}", self.operand(rhs, PREC_UNARY)), PREC_UNARY),
+                PrefixOpKind::Not => (format!("not {}", self.operand(rhs, PREC_NOT)), PREC_NOT),
+                PrefixOpKind::BitNot => (format!("~{}", self.operand(rhs, PREC_UNARY)), PREC_UNARY),
+            },
+            Expr::InfixOp { kind, lhs, rhs } if kind.is_assign() => {
+                // A compound assignment is spelled out, as `+=` is a statement in Python
+                let value = match kind.compound() {
+                    Some(op) => self.binary(&op, lhs, rhs).0,
+                    None => self.expr(rhs),
+                };
+                match &**lhs {
+                    Expr::PostfixOp {
+                        kind: PostfixOpKind::Index(index),
+                        lhs,
+                    } => (
+                        format!(
+                            "{}.__setitem__({}, {})",
+                            self.operand(lhs, PREC_POSTFIX),
+                            self.expr(index),
+                            value
+                        ),
+                        PREC_POSTFIX,
+                    ),
+                    _ => (format!("({} := {})", self.expr(lhs), value), PREC_ATOM),
+                }
+            }
+            Expr::InfixOp { kind, lhs, rhs } => self.binary(kind, lhs, rhs),
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs,
+            } => (
+                format!("{}[{}]", self.operand(lhs, PREC_POSTFIX), self.expr(index)),
+                PREC_POSTFIX,
+            ),
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Call(args),
+                lhs,
+            } => {
+                let builtin = match &**lhs {
+                    Expr::Ident(ident) if self.lookup(&ident.name).is_none() => ident.name.as_str(),
+                    _ => "",
+                };
+                let code = match (builtin, args.as_slice()) {
+                    ("push", [array, value]) => format!(
+                        "{}.append({})",
+                        self.operand(array, PREC_POSTFIX),
+                        self.expr(value)
+                    ),
+                    ("push", [array, values @ ..]) if !values.is_empty() => {
+                        let values: Vec<String> =
+                            values.iter().map(|value| self.expr(value)).collect();
+                        format!(
+                            "{}.extend([{}])",
+                            self.operand(array, PREC_POSTFIX),
+                            values.join(", ")
+                        )
+                    }
+                    ("pop", [array]) => format!("{}.pop()", self.operand(array, PREC_POSTFIX)),
+                    ("sqrt", [int]) => {
+                        self.uses_math.set(true);
+                        format!("math.isqrt({})", self.expr(int))
+                    }
+                    ("gcd", [a, b]) => {
+                        self.uses_math.set(true);
+                        format!("math.gcd({}, {})", self.expr(a), self.expr(b))
+                    }
+                    _ => {
+                        let args: Vec<String> = args.iter().map(|arg| self.expr(arg)).collect();
+                        format!("{}({})", self.operand(lhs, PREC_POSTFIX), args.join(", "))
+                    }
+                };
+                (code, PREC_POSTFIX)
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => (
+                format!(
+                    "{} if {} else {}",
+                    self.operand(then, PREC_CONDITIONAL + 1),
+                    self.operand(cond, PREC_CONDITIONAL + 1),
+                    self.operand(otherwise, PREC_CONDITIONAL)
+                ),
+                PREC_CONDITIONAL,
+            ),
+        }
+    }
+
+    fn binary(&self, kind: &InfixOpKind, lhs: &Expr, rhs: &Expr) -> (String, u8) {
+        let (symbol, prec) = infix(kind);
+        let (lhs_prec, rhs_prec) = match prec {
+            PREC_COMPARISON => (prec + 1, prec + 1), // Comparisons are not chained in Momonga
+            PREC_POWER => (prec + 1, PREC_UNARY),    // Right associative, as in `2 ** -1`
+            _ => (prec, prec + 1),
+        };
+        (
+            format!(
+                "{} {} {}",
+                self.operand(lhs, lhs_prec),
+                symbol,
+                self.operand(rhs, rhs_prec)
+            ),
+            prec,
+        )
+    }
+
+    fn literal(&self, literal: &Literal) -> String {
+        match literal {
+            Literal::Bool(true) => "True".to_string(),
+            Literal::Bool(false) => "False".to_string(),
+            Literal::Int(int) => int.to_string(),
+            Literal::String(string) => format!("\"{}\"", escape_string(string)),
+            Literal::Array(exprs) => {
+                let exprs: Vec<String> = exprs.iter().map(|expr| self.expr(expr)).collect();
+                format!("[{}]", exprs.join(", "))
+            }
+            Literal::None => "None".to_string(),
+        }
+    }
+}
+
+fn infix(kind: &InfixOpKind) -> (&'static str, u8) {
+    match kind {
+        InfixOpKind::Add => ("+", PREC_SUM),
+        InfixOpKind::Subtract => ("-", PREC_SUM),
+        InfixOpKind::Multiply => ("*", PREC_PRODUCT),
+        InfixOpKind::Divide => ("//", PREC_PRODUCT),
+        InfixOpKind::Modulo => ("%", PREC_PRODUCT),
+        InfixOpKind::Power => ("**", PREC_POWER),
+        InfixOpKind::Eq => ("==", PREC_COMPARISON),
+        InfixOpKind::NotEq => ("!=", PREC_COMPARISON),
+        InfixOpKind::Gt => (">", PREC_COMPARISON),
+        InfixOpKind::Ge => (">=", PREC_COMPARISON),
+        InfixOpKind::Lt => ("<", PREC_COMPARISON),
+        InfixOpKind::Le => ("<=", PREC_COMPARISON),
+        InfixOpKind::And => ("and", PREC_AND),
+        InfixOpKind::Or => ("or", PREC_OR),
+        // Bound tighter than comparisons in Python, unlike in Momonga
+        InfixOpKind::BitAnd => ("&", PREC_BIT_AND),
+        InfixOpKind::BitOr => ("|", PREC_BIT_OR),
+        InfixOpKind::BitXor => ("^", PREC_BIT_XOR),
+        InfixOpKind::ShiftLeft => ("<<", PREC_SHIFT),
+        InfixOpKind::ShiftRight => (">>", PREC_SHIFT),
+        InfixOpKind::Assign
+        | InfixOpKind::AddAssign
+        | InfixOpKind::SubtractAssign
+        | InfixOpKind::MultiplyAssign
+        | InfixOpKind::DivideAssign
+        | InfixOpKind::ModuloAssign => unreachable!(), // Handled by the caller
+    }
+}
+
+fn py_ident(name: &str) -> String {
+    if RESERVED.contains(&name) {
+        format!("{}_", name)
+    } else {
+        name.to_string()
+    }
+}
+
+// Names declared in `stmts`, and names assigned in order, not looking into nested functions
+fn collect_names<'a>(
+    stmts: &'a [Stmt],
+    declared: &mut HashSet<&'a str>,
+    assigned: &mut Vec<&'a str>,
+) {
+    for stmt in stmts {
+        match stmt {
+            Stmt::BlockStmt(stmts, _) => collect_names(stmts, declared, assigned),
+            Stmt::FuncDecl(FuncDecl { ident_func, .. }, _) => {
+                declared.insert(&ident_func.name);
+            }
+            Stmt::IfStmt(if_stmt, _) => {
+                let mut if_stmt = if_stmt;
+                loop {
+                    collect_assigned(&if_stmt.condition, assigned);
+                    collect_names(&if_stmt.block, declared, assigned);
+                    match &if_stmt.else_clause {
+                        Some(IfStmtElseClause::IfStmt(else_if)) => if_stmt = else_if,
+                        Some(IfStmtElseClause::IfStmtBlock(block)) => {
+                            collect_names(block, declared, assigned);
+                            break;
+                        }
+                        None => break,
+                    }
+                }
+            }
+            Stmt::ForStmt(
+                ForStmt {
+                    init,
+                    cond,
+                    afterthought,
+                    block,
+                    ..
+                },
+                _,
+            ) => {
+                match init {
+                    Some(ForStmtInit::Var(VarStmt { ident, expr, .. })) => {
+                        declared.insert(&ident.name);
+                        expr.iter()
+                            .for_each(|expr| collect_assigned(expr, assigned));
+                    }
+                    Some(ForStmtInit::Expr(expr)) => collect_assigned(expr, assigned),
+                    None => (),
+                }
+                cond.iter()
+                    .chain(afterthought)
+                    .for_each(|expr| collect_assigned(expr, assigned));
+                collect_names(block, declared, assigned);
+            }
+            Stmt::WhileStmt(WhileStmt { cond, block, .. }, _)
+            | Stmt::DoWhileStmt(DoWhileStmt { block, cond, .. }, _) => {
+                collect_assigned(cond, assigned);
+                collect_names(block, declared, assigned);
+            }
+            Stmt::SwitchStmt(
+                SwitchStmt {
+                    subject,
+                    cases,
+                    default,
+                },
+                _,
+            ) => {
+                collect_assigned(subject, assigned);
+                for SwitchStmtCase { block, .. } in cases {
+                    collect_names(block, declared, assigned);
+                }
+                default
+                    .iter()
+                    .for_each(|block| collect_names(block, declared, assigned));
+            }
+            Stmt::VarStmt(VarStmt { ident, expr, .. }, _) => {
+                declared.insert(&ident.name);
+                expr.iter()
+                    .for_each(|expr| collect_assigned(expr, assigned));
+            }
+            Stmt::ExprStmt(expr, _) | Stmt::ReturnStmt(ReturnStmt { expr: Some(expr) }, _) => {
+                collect_assigned(expr, assigned)
+            }
+            Stmt::ContinueStmt(..) | Stmt::BreakStmt(..) | Stmt::ReturnStmt(..) => (),
+            Stmt::Unreachable(..) => (),
+            Stmt::Error(_) => unreachable!(),
+        }
+    }
+}
+
+fn collect_assigned<'a>(expr: &'a Expr, assigned: &mut Vec<&'a str>) {
+    match expr {
+        Expr::Literal(Literal::Array(exprs)) => exprs
+            .iter()
+            .for_each(|expr| collect_assigned(expr, assigned)),
+        Expr::Literal(_) | Expr::Ident(_) => (),
+        Expr::PrefixOp { rhs, .. } => collect_assigned(rhs, assigned),
+        Expr::InfixOp { kind, lhs, rhs } => {
+            if let (true, Expr::Ident(ident)) = (kind.is_assign(), &**lhs) {
+                assigned.push(&ident.name);
+            }
+            collect_assigned(lhs, assigned);
+            collect_assigned(rhs, assigned);
+        }
+        Expr::PostfixOp { kind, lhs } => {
+            collect_assigned(lhs, assigned);
+            match kind {
+                PostfixOpKind::Index(index) => collect_assigned(index, assigned),
+                PostfixOpKind::Call(args) => {
+                    args.iter().for_each(|arg| collect_assigned(arg, assigned))
+                }
+            }
+        }
+        Expr::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => {
+            collect_assigned(cond, assigned);
+            collect_assigned(then, assigned);
+            collect_assigned(otherwise, assigned);
+        }
+    }
+}
+
+fn has_invalid_target(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(Literal::Array(exprs)) => exprs.iter().any(has_invalid_target),
+        Expr::Literal(_) | Expr::Ident(_) => false,
+        Expr::PrefixOp { rhs, .. } => has_invalid_target(rhs),
+        Expr::InfixOp { kind, lhs, rhs } => {
+            let invalid = kind.is_assign()
+                && !matches!(
+                    **lhs,
+                    Expr::Ident(_)
+                        | Expr::PostfixOp {
+                            kind: PostfixOpKind::Index(_),
+                            ..
+                        }
+                );
+            invalid || has_invalid_target(lhs) || has_invalid_target(rhs)
+        }
+        Expr::PostfixOp { kind, lhs } => {
+            has_invalid_target(lhs)
+                || match kind {
+                    PostfixOpKind::Index(index) => has_invalid_target(index),
+                    PostfixOpKind::Call(args) => args.iter().any(has_invalid_target),
+                }
+        }
+        Expr::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => has_invalid_target(cond) || has_invalid_target(then) || has_invalid_target(otherwise),
+    }
+}
+
+fn assigns(stmts: &[Stmt], name: &str) -> bool {
+    let mut declared = HashSet::new();
+    let mut assigned = vec![];
+    collect_names(stmts, &mut declared, &mut assigned);
+    assigned.contains(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::python::*;
+
+    #[test]
+    fn program_is_translated_to_python() {
+        let tests = [
+            ("", ""),
+            ("var x; var y = [1, \"a\\n\", true, none];", "x = None\ny = [1, \"a\\\\n\", True, None]\n"),
+            (
+                "func add(a, b) { return a + b; } print(add(1, 2));",
+                "def add(a, b):\n    return a + b\nprint(add(1, 2))\n",
+            ),
+            (
+                "var x = 1; if (x == 1) { x = 2; } else if (!(x > 2)) {} else { x = x / 2 % 3; }",
+                "x = 1\nif x == 1:\n    x = 2\nelif not x > 2:\n    pass\nelse:\n    x = x // 2 % 3\n",
+            ),
+            (
+                "var arr = []; push(arr, 1); pop(arr); len(arr);",
+                "arr = []\narr.append(1)\narr.pop()\nlen(arr)\n",
+            ),
+            ("!true == false; -(1 + 2) * 3; (1 < 2) == true;", "(not True) == False\n-(1 + 2) * 3\n(1 < 2) == True\n"),
+            ("a == b & c; a & b == c; ~a << 1 | b ^ c;", "(a == b) & c\na & (b == c)\n~a << 1 | b ^ c\n"),
+            ("var def = 1; var x = y = 2;", "def_ = 1\nx = (y := 2)\n"),
+            ("1 = x;", "raise TypeError(\"cannot assign to expression\")\n"),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                to_python(src).unwrap().target,
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn loops_are_translated_to_python() {
+        let tests = [
+            (
+                "for (var i = 0; i < 10; i = i + 1) { print(i); }",
+                "for i in range(10):\n    print(i)\n",
+            ),
+            (
+                "var n = 3; for (var i = 1; i <= n; i = i + 2) {}",
+                "n = 3\nfor i in range(1, n + 1, 2):\n    pass\n",
+            ),
+            (
+                "var arr = [1]; for (var i = 0; i < len(arr); i = i + 1) { if (i == 0) { continue; } }",
+                "arr = [1]\ni = 0\nwhile i < len(arr):\n    if i == 0:\n        i = i + 1\n        continue\n    i = i + 1\n",
+            ),
+            ("while (true) { break; }", "while True:\n    break\n"),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                to_python(src).unwrap().target,
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn scopes_are_kept_in_python() {
+        let tests = [
+            (
+                "var x = 1; { var x = x + 1; print(x); } print(x);",
+                "x = 1\nx_2 = x + 1\nprint(x_2)\nprint(x)\n",
+            ),
+            (
+                "var count = 0; func f() { func g() { var y; y = 1; count = count + y; } g(); } f();",
+                "count = 0\ndef f():\n    def g():\n        global count\n        y = None\n        y = 1\n        count = count + y\n    g()\nf()\n",
+            ),
+            (
+                "func f() { var x = 0; func g() { x = 1; } g(); return x; }",
+                "def f():\n    x = 0\n    def g():\n        nonlocal x\n        x = 1\n    g()\n    return x\n",
+            ),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                to_python(src).unwrap().target,
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn source_lines_are_recorded() {
+        let side_by_side = to_python("var x = 1;\nif (x == 1) {\n    print(x);\n}\n").unwrap();
+        assert_eq!(side_by_side.target, "x = 1\nif x == 1:\n    print(x)\n");
+        assert_eq!(side_by_side.source_lines, vec![1, 2, 3]);
+    }
+}
@@ -0,0 +1,466 @@
+/* Runtime of Momonga programs translated into C. Values are never freed. */
+#include <inttypes.h>
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+typedef enum { MO_UNDEFINED, MO_NONE, MO_BOOL, MO_INT, MO_STRING, MO_ARRAY } MoTag;
+
+typedef struct MoValue {
+    MoTag tag; /* MO_UNDEFINED for variables not assigned yet and arguments left to default values */
+    union {
+        bool b;
+        int64_t i;
+        const char *s;
+        struct MoArray *a;
+    } as;
+} MoValue;
+
+typedef struct MoArray {
+    size_t len, cap;
+    MoValue *items;
+} MoArray;
+
+#define MO_UNDEFINED_VALUE ((MoValue){MO_UNDEFINED, {false}})
+
+static inline MoValue mo_error(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "%s\n", message);
+    exit(1);
+}
+
+static inline void *mo_alloc(size_t size) {
+    void *ptr = malloc(size > 0 ? size : 1);
+    if (ptr == NULL) {
+        mo_error("Out of memory");
+    }
+    return ptr;
+}
+
+static inline MoValue mo_check(MoValue v) {
+    return v.tag == MO_UNDEFINED ? mo_error("Name error") : v;
+}
+
+static inline MoValue mo_none(void) {
+    MoValue v = {MO_NONE, {false}};
+    return v;
+}
+
+static inline MoValue mo_bool(bool b) {
+    MoValue v = {MO_BOOL, {false}};
+    v.as.b = b;
+    return v;
+}
+
+static inline MoValue mo_int(int64_t i) {
+    MoValue v = {MO_INT, {false}};
+    v.as.i = i;
+    return v;
+}
+
+static inline MoValue mo_str(const char *s) {
+    MoValue v = {MO_STRING, {false}};
+    v.as.s = s;
+    return v;
+}
+
+static inline MoValue mo_array_of(MoArray *a) {
+    MoValue v = {MO_ARRAY, {false}};
+    v.as.a = a;
+    return v;
+}
+
+static inline void mo_array_push(MoArray *a, MoValue item) {
+    if (a->len == a->cap) {
+        MoValue *items = mo_alloc(sizeof(MoValue) * (a->cap * 2 + 1));
+        if (a->len > 0) {
+            memcpy(items, a->items, sizeof(MoValue) * a->len);
+        }
+        a->items = items;
+        a->cap = a->cap * 2 + 1;
+    }
+    a->items[a->len++] = item;
+}
+
+static inline MoValue mo_array(size_t len, ...) {
+    MoArray *a = mo_alloc(sizeof(MoArray));
+    a->len = a->cap = 0;
+    a->items = NULL;
+    va_list items;
+    va_start(items, len);
+    for (size_t i = 0; i < len; i++) {
+        mo_array_push(a, mo_check(va_arg(items, MoValue)));
+    }
+    va_end(items);
+    return mo_array_of(a);
+}
+
+/* Condition of `if`, `for`, `while`, `&&` and `||` */
+static inline bool mo_test(MoValue v) {
+    return mo_check(v).tag == MO_BOOL ? v.as.b : mo_error("Type error").as.b;
+}
+
+static inline bool mo_ints(MoValue a, MoValue b) {
+    mo_check(a);
+    mo_check(b);
+    return a.tag == MO_INT && b.tag == MO_INT ? true : mo_error("Type error").as.b;
+}
+
+static inline MoValue mo_add(MoValue a, MoValue b) {
+    mo_check(a);
+    mo_check(b);
+    if (a.tag == MO_INT && b.tag == MO_INT) {
+        /* Overflowed if the sum has another sign than both operands */
+        int64_t sum = (int64_t)((uint64_t)a.as.i + (uint64_t)b.as.i);
+        return ((a.as.i ^ sum) & (b.as.i ^ sum)) < 0 ? mo_error("Out of range error") : mo_int(sum);
+    }
+    if (a.tag == MO_STRING && b.tag == MO_STRING) {
+        size_t len_a = strlen(a.as.s), len_b = strlen(b.as.s);
+        char *s = mo_alloc(len_a + len_b + 1);
+        memcpy(s, a.as.s, len_a);
+        memcpy(s + len_a, b.as.s, len_b + 1);
+        return mo_str(s);
+    }
+    return mo_error("Type error");
+}
+
+static inline MoValue mo_sub(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    /* Overflowed if the operands differ in sign and the difference has the sign of the right one */
+    int64_t difference = (int64_t)((uint64_t)a.as.i - (uint64_t)b.as.i);
+    if (((a.as.i ^ b.as.i) & (a.as.i ^ difference)) < 0) {
+        return mo_error("Out of range error");
+    }
+    return mo_int(difference);
+}
+
+static inline MoValue mo_mul(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    /* Dividing the product back detects an overflow, except for -1 times the minimum */
+    int64_t product = (int64_t)((uint64_t)a.as.i * (uint64_t)b.as.i);
+    bool overflows = a.as.i < -1 || a.as.i > 1 ? product / a.as.i != b.as.i
+                                               : a.as.i == -1 && b.as.i == INT64_MIN;
+    return overflows ? mo_error("Out of range error") : mo_int(product);
+}
+
+static inline MoValue mo_div(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    if (b.as.i == 0) {
+        return mo_error("Zero division error");
+    }
+    if (a.as.i == INT64_MIN && b.as.i == -1) {
+        return mo_error("Out of range error");
+    }
+    return mo_int(a.as.i / b.as.i);
+}
+
+static inline MoValue mo_mod(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    if (b.as.i == 0) {
+        return mo_error("Zero division error");
+    }
+    if (a.as.i == INT64_MIN && b.as.i == -1) {
+        return mo_error("Out of range error"); /* Like the quotient */
+    }
+    return mo_int(a.as.i % b.as.i);
+}
+
+static inline MoValue mo_bit_and(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return mo_int(a.as.i & b.as.i);
+}
+
+static inline MoValue mo_bit_or(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return mo_int(a.as.i | b.as.i);
+}
+
+static inline MoValue mo_bit_xor(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return mo_int(a.as.i ^ b.as.i);
+}
+
+static inline MoValue mo_shl(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    if (b.as.i < 0 || b.as.i > 63) {
+        return mo_error("Out of range error");
+    }
+    return mo_int((int64_t)((uint64_t)a.as.i << b.as.i));
+}
+
+/* Arithmetic shift, keeping the sign */
+static inline MoValue mo_shr(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    if (b.as.i < 0 || b.as.i > 63) {
+        return mo_error("Out of range error");
+    }
+    return mo_int(a.as.i < 0 ? ~(~a.as.i >> b.as.i) : a.as.i >> b.as.i);
+}
+
+/* Structural equality, which never fails */
+static inline bool mo_same(MoValue a, MoValue b) {
+    if (a.tag != b.tag) {
+        return false;
+    }
+    switch (a.tag) {
+    case MO_BOOL:
+        return a.as.b == b.as.b;
+    case MO_INT:
+        return a.as.i == b.as.i;
+    case MO_STRING:
+        return strcmp(a.as.s, b.as.s) == 0;
+    case MO_ARRAY:
+        if (a.as.a->len != b.as.a->len) {
+            return false;
+        }
+        for (size_t i = 0; i < a.as.a->len; i++) {
+            if (!mo_same(a.as.a->items[i], b.as.a->items[i])) {
+                return false;
+            }
+        }
+        return true;
+    default:
+        return true;
+    }
+}
+
+static inline MoValue mo_eq(MoValue a, MoValue b) {
+    mo_check(a);
+    mo_check(b);
+    if (a.tag != b.tag || a.tag == MO_NONE) {
+        return mo_error("Type error");
+    }
+    return mo_bool(mo_same(a, b));
+}
+
+static inline MoValue mo_ne(MoValue a, MoValue b) {
+    return mo_bool(!mo_eq(a, b).as.b);
+}
+
+static inline MoValue mo_lt(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return mo_bool(a.as.i < b.as.i);
+}
+
+static inline MoValue mo_le(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return mo_bool(a.as.i <= b.as.i);
+}
+
+static inline MoValue mo_gt(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return mo_bool(a.as.i > b.as.i);
+}
+
+static inline MoValue mo_ge(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return mo_bool(a.as.i >= b.as.i);
+}
+
+static inline MoValue mo_not(MoValue v) {
+    return mo_bool(!mo_test(v));
+}
+
+static inline MoValue mo_pos(MoValue v) {
+    return mo_check(v).tag == MO_INT ? v : mo_error("Type error");
+}
+
+static inline MoValue mo_neg(MoValue v) {
+    mo_pos(v);
+    return v.as.i == INT64_MIN ? mo_error("Out of range error") : mo_int(-v.as.i);
+}
+
+static inline MoValue mo_bit_not(MoValue v) {
+    mo_pos(v);
+    return mo_int(~v.as.i);
+}
+
+static inline MoValue mo_index(MoValue a, MoValue i) {
+    if (mo_check(i).tag != MO_INT) {
+        return mo_error("Type error");
+    }
+    if (i.as.i < 0) {
+        return mo_error("Index error");
+    }
+    if (mo_check(a).tag != MO_ARRAY) {
+        return mo_error("Type error");
+    }
+    return (uint64_t)i.as.i < a.as.a->len ? a.as.a->items[i.as.i] : mo_error("Index error");
+}
+
+static inline MoValue mo_store(MoValue a, MoValue i, MoValue v) {
+    mo_check(v);
+    mo_index(a, i);
+    a.as.a->items[i.as.i] = v;
+    return v;
+}
+
+/* Compound assignment such as `a[i] += v`, applying `op` to the item and `v` */
+static inline MoValue mo_update(MoValue a, MoValue i, MoValue v, MoValue (*op)(MoValue, MoValue)) {
+    return mo_store(a, i, op(mo_index(a, i), v));
+}
+
+static inline MoValue mo_len(MoValue v) {
+    switch (mo_check(v).tag) {
+    case MO_STRING:
+        return mo_int((int64_t)strlen(v.as.s));
+    case MO_ARRAY:
+        return mo_int((int64_t)v.as.a->len);
+    default:
+        return mo_error("Type error");
+    }
+}
+
+/* Item by item of `push`, returning the array itself */
+static inline MoValue mo_append(MoValue a, MoValue v) {
+    if (mo_check(a).tag != MO_ARRAY) {
+        return mo_error("Type error");
+    }
+    mo_array_push(a.as.a, mo_check(v));
+    return a;
+}
+
+/* What `push` returns like `data::momonga_push` */
+static inline MoValue mo_copy(MoValue a) {
+    MoValue copy = mo_array(0);
+    for (size_t i = 0; i < a.as.a->len; i++) {
+        mo_array_push(copy.as.a, a.as.a->items[i]);
+    }
+    return copy;
+}
+
+static inline MoValue mo_pop(MoValue a) {
+    if (mo_check(a).tag != MO_ARRAY) {
+        return mo_error("Type error");
+    }
+    return a.as.a->len > 0 ? a.as.a->items[--a.as.a->len] : mo_error("Index error");
+}
+
+static inline void mo_display(MoValue v) {
+    switch (v.tag) {
+    case MO_BOOL:
+        fputs(v.as.b ? "true" : "false", stdout);
+        break;
+    case MO_INT:
+        printf("%" PRId64, v.as.i);
+        break;
+    case MO_STRING:
+        fputs(v.as.s, stdout);
+        break;
+    case MO_ARRAY:
+        putchar('[');
+        for (size_t i = 0; i < v.as.a->len; i++) {
+            if (i > 0) {
+                fputs(", ", stdout);
+            }
+            mo_display(v.as.a->items[i]);
+        }
+        putchar(']');
+        break;
+    default:
+        fputs("none", stdout);
+    }
+}
+
+/* Value by value of `print` but the last, returning the next one */
+static inline MoValue mo_spaced(MoValue v, MoValue next) {
+    mo_check(next);
+    mo_display(mo_check(v));
+    putchar(' ');
+    return next;
+}
+
+static inline MoValue mo_print(MoValue v) {
+    mo_display(mo_check(v));
+    putchar('\n');
+    return mo_none();
+}
+
+static inline MoValue mo_abs(MoValue v) {
+    mo_pos(v);
+    return v.as.i < 0 ? mo_neg(v) : v;
+}
+
+static inline MoValue mo_min(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return b.as.i < a.as.i ? b : a;
+}
+
+static inline MoValue mo_max(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    return b.as.i > a.as.i ? b : a;
+}
+
+/* Like `data::int_pow`, out of range for negative exponents as well as on overflow */
+static inline MoValue mo_pow(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    if (b.as.i < 0) {
+        return mo_error("Out of range error");
+    }
+    int64_t exp = b.as.i > 64 ? 64 + b.as.i % 2 : b.as.i, result = 1;
+    for (int64_t i = 0; i < exp; i++) {
+        /* Only bases other than -1, 0 and 1 can overflow, and dividing by them never does */
+        int64_t product = (int64_t)((uint64_t)result * (uint64_t)a.as.i);
+        if ((a.as.i < -1 || a.as.i > 1) && product / a.as.i != result) {
+            return mo_error("Out of range error");
+        }
+        result = product;
+    }
+    return mo_int(result);
+}
+
+/* Square root rounded down, by Newton's method from above */
+static inline MoValue mo_sqrt(MoValue v) {
+    mo_pos(v);
+    if (v.as.i < 0) {
+        return mo_error("Out of range error");
+    }
+    if (v.as.i < 2) {
+        return v;
+    }
+    int64_t root = v.as.i / 2 + 1, next = (root + v.as.i / root) / 2;
+    while (next < root) {
+        root = next;
+        next = (root + v.as.i / root) / 2;
+    }
+    return mo_int(root);
+}
+
+static inline MoValue mo_gcd(MoValue a, MoValue b) {
+    mo_ints(a, b);
+    uint64_t x = a.as.i < 0 ? 0 - (uint64_t)a.as.i : (uint64_t)a.as.i;
+    uint64_t y = b.as.i < 0 ? 0 - (uint64_t)b.as.i : (uint64_t)b.as.i;
+    while (y != 0) {
+        uint64_t r = x % y;
+        x = y;
+        y = r;
+    }
+    return x > INT64_MAX ? mo_error("Out of range error") : mo_int((int64_t)x);
+}
+
+/* Next line of the standard input without the line break, or `none` at the end */
+static inline MoValue mo_input(void) {
+    size_t len = 0, cap = 16;
+    char *s = mo_alloc(cap);
+    int c;
+    while ((c = getchar()) != EOF && c != '\n') {
+        if (len + 1 == cap) {
+            char *grown = mo_alloc(cap * 2);
+            memcpy(grown, s, len);
+            s = grown;
+            cap *= 2;
+        }
+        s[len++] = (char)c;
+    }
+    if (c == EOF && len == 0) {
+        return mo_none();
+    }
+    if (len > 0 && s[len - 1] == '\r') {
+        len--;
+    }
+    s[len] = '\0';
+    return mo_str(s);
+}
@@ -0,0 +1,373 @@
+// WebAssembly binary format, limited to what the compiler emits
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ValType {
+    I32,
+    I64,
+}
+
+impl ValType {
+    fn byte(self) -> u8 {
+        match self {
+            ValType::I32 => 0x7F,
+            ValType::I64 => 0x7E,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FuncType {
+    pub params: Vec<ValType>,
+    pub results: Vec<ValType>,
+}
+
+#[derive(Clone, Copy)]
+pub enum BlockType {
+    Empty,
+    Value(ValType),
+}
+
+// Opcodes without immediates
+pub const UNREACHABLE: u8 = 0x00;
+pub const ELSE: u8 = 0x05;
+pub const END: u8 = 0x0B;
+pub const RETURN: u8 = 0x0F;
+pub const DROP: u8 = 0x1A;
+pub const SELECT: u8 = 0x1B;
+pub const I32_EQZ: u8 = 0x45;
+pub const I32_EQ: u8 = 0x46;
+pub const I32_NE: u8 = 0x47;
+pub const I32_GT_U: u8 = 0x4B;
+pub const I32_LE_U: u8 = 0x4D;
+pub const I32_GE_S: u8 = 0x4E;
+pub const I64_EQZ: u8 = 0x50;
+pub const I64_EQ: u8 = 0x51;
+pub const I64_NE: u8 = 0x52;
+pub const I64_LT_S: u8 = 0x53;
+pub const I64_GT_S: u8 = 0x55;
+pub const I64_GT_U: u8 = 0x56;
+pub const I64_LE_S: u8 = 0x57;
+pub const I64_GE_S: u8 = 0x59;
+pub const I64_GE_U: u8 = 0x5A;
+pub const I32_ADD: u8 = 0x6A;
+pub const I32_SUB: u8 = 0x6B;
+pub const I32_MUL: u8 = 0x6C;
+pub const I32_AND: u8 = 0x71;
+pub const I32_OR: u8 = 0x72;
+pub const I32_SHL: u8 = 0x74;
+pub const I64_ADD: u8 = 0x7C;
+pub const I64_SUB: u8 = 0x7D;
+pub const I64_MUL: u8 = 0x7E;
+pub const I64_DIV_S: u8 = 0x7F;
+pub const I64_DIV_U: u8 = 0x80;
+pub const I64_REM_S: u8 = 0x81;
+pub const I64_REM_U: u8 = 0x82;
+pub const I64_AND: u8 = 0x83;
+pub const I64_OR: u8 = 0x84;
+pub const I64_XOR: u8 = 0x85;
+pub const I64_SHL: u8 = 0x86;
+pub const I64_SHR_S: u8 = 0x87;
+pub const I32_WRAP_I64: u8 = 0xA7;
+pub const I64_EXTEND_I32_U: u8 = 0xAD;
+
+// Memory access opcodes and the alignment (log2) of each
+pub const I32_LOAD: (u8, u32) = (0x28, 2);
+pub const I64_LOAD: (u8, u32) = (0x29, 3);
+pub const I32_LOAD8_U: (u8, u32) = (0x2D, 0);
+pub const I32_STORE: (u8, u32) = (0x36, 2);
+pub const I64_STORE: (u8, u32) = (0x37, 3);
+pub const I32_STORE8: (u8, u32) = (0x3A, 0);
+
+// Instructions of a function body
+#[derive(Default)]
+pub struct Code {
+    bytes: Vec<u8>,
+}
+
+impl Code {
+    pub fn op(&mut self, opcode: u8) -> &mut Self {
+        self.bytes.push(opcode);
+        self
+    }
+
+    fn op_index(&mut self, opcode: u8, index: u32) -> &mut Self {
+        self.bytes.push(opcode);
+        uleb(&mut self.bytes, index as u64);
+        self
+    }
+
+    fn block_op(&mut self, opcode: u8, block_type: BlockType) -> &mut Self {
+        self.bytes.push(opcode);
+        self.bytes.push(match block_type {
+            BlockType::Empty => 0x40,
+            BlockType::Value(val_type) => val_type.byte(),
+        });
+        self
+    }
+
+    pub fn block(&mut self, block_type: BlockType) -> &mut Self {
+        self.block_op(0x02, block_type)
+    }
+
+    pub fn loop_(&mut self, block_type: BlockType) -> &mut Self {
+        self.block_op(0x03, block_type)
+    }
+
+    pub fn if_(&mut self, block_type: BlockType) -> &mut Self {
+        self.block_op(0x04, block_type)
+    }
+
+    pub fn br(&mut self, depth: u32) -> &mut Self {
+        self.op_index(0x0C, depth)
+    }
+
+    pub fn br_if(&mut self, depth: u32) -> &mut Self {
+        self.op_index(0x0D, depth)
+    }
+
+    pub fn call(&mut self, func: u32) -> &mut Self {
+        self.op_index(0x10, func)
+    }
+
+    pub fn local_get(&mut self, local: u32) -> &mut Self {
+        self.op_index(0x20, local)
+    }
+
+    pub fn local_set(&mut self, local: u32) -> &mut Self {
+        self.op_index(0x21, local)
+    }
+
+    pub fn local_tee(&mut self, local: u32) -> &mut Self {
+        self.op_index(0x22, local)
+    }
+
+    pub fn global_get(&mut self, global: u32) -> &mut Self {
+        self.op_index(0x23, global)
+    }
+
+    pub fn global_set(&mut self, global: u32) -> &mut Self {
+        self.op_index(0x24, global)
+    }
+
+    pub fn mem(&mut self, (opcode, align): (u8, u32), offset: u32) -> &mut Self {
+        self.bytes.push(opcode);
+        uleb(&mut self.bytes, align as u64);
+        uleb(&mut self.bytes, offset as u64);
+        self
+    }
+
+    pub fn memory_size(&mut self) -> &mut Self {
+        self.bytes.extend([0x3F, 0x00]);
+        self
+    }
+
+    pub fn memory_grow(&mut self) -> &mut Self {
+        self.bytes.extend([0x40, 0x00]);
+        self
+    }
+
+    pub fn i32_const(&mut self, value: i32) -> &mut Self {
+        self.bytes.push(0x41);
+        sleb(&mut self.bytes, value as i64);
+        self
+    }
+
+    pub fn i64_const(&mut self, value: i64) -> &mut Self {
+        self.bytes.push(0x42);
+        sleb(&mut self.bytes, value);
+        self
+    }
+}
+
+pub struct Function {
+    pub type_index: u32,
+    pub locals: Vec<ValType>, // Excluding parameters
+    pub code: Code,
+}
+
+pub struct Import {
+    pub name: &'static str, // Imported from the module "env"
+    pub type_index: u32,
+}
+
+pub struct Global {
+    pub val_type: ValType,
+    pub init: i64,
+}
+
+#[derive(Default)]
+pub struct Module {
+    pub types: Vec<FuncType>,
+    pub imports: Vec<Import>,
+    pub functions: Vec<Function>,
+    pub globals: Vec<Global>,
+    pub exports: Vec<(&'static str, u8, u32)>, // Name, kind (0 for functions, 2 for memories) and index
+    pub memory_pages: u32,
+    pub data: Vec<u8>, // Placed at address 0
+}
+
+impl Module {
+    pub fn type_index(&mut self, params: &[ValType], results: &[ValType]) -> u32 {
+        let func_type = FuncType {
+            params: params.to_vec(),
+            results: results.to_vec(),
+        };
+        match self.types.iter().position(|t| *t == func_type) {
+            Some(index) => index as u32,
+            None => {
+                self.types.push(func_type);
+                self.types.len() as u32 - 1
+            }
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+
+        section(&mut bytes, 1, &self.types, |out, func_type| {
+            out.push(0x60);
+            vec(out, &func_type.params, |out, val_type| {
+                out.push(val_type.byte())
+            });
+            vec(out, &func_type.results, |out, val_type| {
+                out.push(val_type.byte())
+            });
+        });
+        section(&mut bytes, 2, &self.imports, |out, import| {
+            name(out, "env");
+            name(out, import.name);
+            out.push(0x00);
+            uleb(out, import.type_index as u64);
+        });
+        section(&mut bytes, 3, &self.functions, |out, function| {
+            uleb(out, function.type_index as u64)
+        });
+        section(&mut bytes, 5, &[self.memory_pages], |out, pages| {
+            out.push(0x00);
+            uleb(out, *pages as u64);
+        });
+        section(&mut bytes, 6, &self.globals, |out, global| {
+            out.push(global.val_type.byte());
+            out.push(0x01); // Mutable
+            match global.val_type {
+                ValType::I32 => out.push(0x41),
+                ValType::I64 => out.push(0x42),
+            }
+            sleb(out, global.init);
+            out.push(END);
+        });
+        section(
+            &mut bytes,
+            7,
+            &self.exports,
+            |out, (export, kind, index)| {
+                name(out, export);
+                out.push(*kind);
+                uleb(out, *index as u64);
+            },
+        );
+        section(&mut bytes, 10, &self.functions, |out, function| {
+            let mut body = vec![];
+            let mut groups: Vec<(u32, ValType)> = vec![];
+            for &local in &function.locals {
+                match groups.last_mut() {
+                    Some((count, val_type)) if *val_type == local => *count += 1,
+                    _ => groups.push((1, local)),
+                }
+            }
+            vec(&mut body, &groups, |out, (count, val_type)| {
+                uleb(out, *count as u64);
+                out.push(val_type.byte());
+            });
+            body.extend(&function.code.bytes);
+            body.push(END);
+            uleb(out, body.len() as u64);
+            out.extend(body);
+        });
+        section(&mut bytes, 11, &[&self.data], |out, data| {
+            out.push(0x00); // Active in memory 0
+            out.push(0x41);
+            sleb(out, 0);
+            out.push(END);
+            uleb(out, data.len() as u64);
+            out.extend(data.iter());
+        });
+        bytes
+    }
+}
+
+fn section<T>(bytes: &mut Vec<u8>, id: u8, items: &[T], item: impl Fn(&mut Vec<u8>, &T)) {
+    if items.is_empty() {
+        return;
+    }
+    let mut content = vec![];
+    vec(&mut content, items, item);
+    bytes.push(id);
+    uleb(bytes, content.len() as u64);
+    bytes.extend(content);
+}
+
+fn vec<T>(out: &mut Vec<u8>, items: &[T], item: impl Fn(&mut Vec<u8>, &T)) {
+    uleb(out, items.len() as u64);
+    for i in items {
+        item(out, i);
+    }
+}
+
+fn name(out: &mut Vec<u8>, name: &str) {
+    uleb(out, name.len() as u64);
+    out.extend(name.as_bytes());
+}
+
+fn uleb(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn sleb(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7F) as u8;
+        value >>= 7;
+        if (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0) {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::wasm::encode::*;
+
+    #[test]
+    fn integers_are_leb128_encoded() {
+        let tests: [(i64, &[u8]); 6] = [
+            (0, &[0x00]),
+            (63, &[0x3F]),
+            (64, &[0xC0, 0x00]),
+            (-1, &[0x7F]),
+            (-65, &[0xBF, 0x7F]),
+            (
+                i64::MIN,
+                &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7F],
+            ),
+        ];
+        for (value, expected) in tests {
+            let mut out = vec![];
+            sleb(&mut out, value);
+            assert_eq!(out, expected, "Failed in test case: {}", value);
+        }
+
+        let mut out = vec![];
+        uleb(&mut out, 624485);
+        assert_eq!(out, [0xE5, 0x8E, 0x26]);
+    }
+}
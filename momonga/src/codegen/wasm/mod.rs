@@ -0,0 +1,799 @@
+// WebAssembly backend.
+//
+// `to_wasm` compiles a program into a module importing `print`, `input` and `error` from "env"
+// (see `runtime::IMPORTS`) and exporting its memory and a `main` function running the program.
+// A runtime error calls `error` with the address and length of its message and then traps.
+//
+// As in the C backend, functions are hoisted to the module level, and variables of the program
+// body and of functions declaring other functions become globals. Functions and builtins can only
+// be called by name, and assigning to a function is a `Type error`.
+
+mod encode;
+mod runtime;
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::*;
+use crate::codegen::{constants, declares_func};
+use crate::data::{builtin, Arity, Builtin};
+use crate::error::{EvalError, ParseError};
+use crate::parser::parse;
+
+use encode::ValType::{I32, I64};
+use encode::*;
+use runtime::*;
+
+// Helper implementing each builtin in `data::BUILTINS`, or its step for the variadic ones
+fn builtin_helper(name: &str) -> Helper {
+    match name {
+        "len" => Helper::Len,
+        "push" => Helper::Push,
+        "pop" => Helper::Pop,
+        "print" => Helper::Spaced,
+        "input" => Helper::Input,
+        "abs" => Helper::Abs,
+        "min" => Helper::Min,
+        "max" => Helper::Max,
+        "pow" => Helper::Pow,
+        "sqrt" => Helper::Sqrt,
+        "gcd" => Helper::Gcd,
+        _ => unreachable!("unknown builtin `{}`", name),
+    }
+}
+
+const MAX_INT: u64 = i64::MAX as u64;
+
+pub fn to_wasm(source: &str) -> Result<Vec<u8>, ParseError> {
+    let program = parse(source)?;
+    let mut wasm = Wasm {
+        module: runtime::module(),
+        contexts: vec![],
+        strings: HashMap::new(),
+        hoisted: HashMap::new(),
+        constants: constants(source),
+    };
+    let main = wasm.reserve();
+    wasm.enter(0, true);
+    wasm.hoist(&program);
+    wasm.stmts(&program);
+    wasm.finish(main, &[]);
+
+    let heap = wasm.module.data.len().next_multiple_of(8);
+    wasm.module.globals[HEAP as usize].init = heap as i64;
+    wasm.module.memory_pages = (heap / 65536 + 1) as u32;
+    wasm.module.exports.push(("memory", 2, 0));
+    wasm.module.exports.push(("main", 0, main));
+    Ok(wasm.module.encode())
+}
+
+#[derive(Clone, Copy)]
+enum Binding {
+    Local(u32),  // Tag in this local and payload in the next one
+    Global(u32), // Likewise for globals
+    Func { index: u32, arity: Arity },
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum Label {
+    Break,
+    Continue,
+    Other,
+}
+
+// Function being compiled, or the program body
+struct Context<'a> {
+    code: Code,
+    params: u32,
+    locals: Vec<ValType>,
+    blocks: Vec<HashMap<&'a str, Binding>>,
+    globals: bool, // Variables are globals to be seen by nested functions
+    labels: Vec<(Label, Option<&'a str>)>, // With the label of the loop in Momonga, if any
+}
+
+struct Wasm<'a> {
+    module: Module,
+    contexts: Vec<Context<'a>>,
+    strings: HashMap<String, u32>,
+    hoisted: HashMap<usize, u32>, // Index of each top-level function by the start of its declaration
+    constants: HashSet<usize>,
+}
+
+impl<'a> Wasm<'a> {
+    fn c(&mut self) -> &mut Code {
+        &mut self.contexts.last_mut().unwrap().code
+    }
+
+    fn context(&mut self) -> &mut Context<'a> {
+        self.contexts.last_mut().unwrap()
+    }
+
+    // Index of a function to be compiled later
+    fn reserve(&mut self) -> u32 {
+        self.module.functions.push(Function {
+            type_index: 0,
+            locals: vec![],
+            code: Code::default(),
+        });
+        (IMPORTS.len() + self.module.functions.len() - 1) as u32
+    }
+
+    fn enter(&mut self, params: u32, globals: bool) {
+        self.contexts.push(Context {
+            code: Code::default(),
+            params: params * 2,
+            locals: vec![],
+            blocks: vec![HashMap::new()],
+            globals,
+            labels: vec![],
+        });
+    }
+
+    fn finish(&mut self, index: u32, results: &[ValType]) {
+        let context = self.contexts.pop().unwrap();
+        let params = VALUE.repeat(context.params as usize / 2);
+        let type_index = self.module.type_index(&params, results);
+        self.module.functions[index as usize - IMPORTS.len()] = Function {
+            type_index,
+            locals: context.locals,
+            code: context.code,
+        };
+    }
+
+    fn bind(&mut self, name: &'a str, binding: Binding) {
+        self.context()
+            .blocks
+            .last_mut()
+            .unwrap()
+            .insert(name, binding);
+    }
+
+    fn lookup(&self, name: &str) -> Option<Binding> {
+        self.contexts
+            .iter()
+            .rev()
+            .flat_map(|context| context.blocks.iter().rev())
+            .find_map(|block| block.get(name).copied())
+    }
+
+    fn local(&mut self, val_type: ValType) -> u32 {
+        let context = self.context();
+        context.locals.push(val_type);
+        context.params + context.locals.len() as u32 - 1
+    }
+
+    // New variable, which is undefined until assigned
+    fn variable(&mut self) -> Binding {
+        if self.context().globals {
+            for val_type in [I32, I64] {
+                self.module.globals.push(Global { val_type, init: 0 });
+            }
+            return Binding::Global(self.module.globals.len() as u32 - 2);
+        }
+        self.temporary()
+    }
+
+    // Local holding an intermediate value, which unlike a variable is never a global
+    fn temporary(&mut self) -> Binding {
+        let tag = self.local(I32);
+        self.local(I64);
+        Binding::Local(tag)
+    }
+
+    // Pop a value into the variable
+    fn set(&mut self, binding: Binding) {
+        match binding {
+            Binding::Local(tag) => self.c().local_set(tag + 1).local_set(tag),
+            Binding::Global(tag) => self.c().global_set(tag + 1).global_set(tag),
+            Binding::Func { .. } => unreachable!(),
+        };
+    }
+
+    fn get(&mut self, binding: Binding) {
+        match binding {
+            Binding::Local(tag) => self.c().local_get(tag).local_get(tag + 1),
+            Binding::Global(tag) => self.c().global_get(tag).global_get(tag + 1),
+            Binding::Func { .. } => unreachable!(),
+        };
+    }
+
+    fn fail(&mut self, error: EvalError) {
+        let message = error.to_string();
+        let address = self.string(&message);
+        fail_with(self.c(), address, &message);
+    }
+
+    // Address of a string in the data, interned once
+    fn string(&mut self, string: &str) -> u32 {
+        match self.strings.get(string) {
+            Some(address) => *address,
+            None => {
+                let address = intern(&mut self.module.data, string);
+                self.strings.insert(string.to_string(), address);
+                address
+            }
+        }
+    }
+
+    fn drop_value(&mut self) {
+        self.c().op(DROP).op(DROP);
+    }
+
+    fn pop_labels(&mut self, count: usize) {
+        let labels = &mut self.context().labels;
+        labels.truncate(labels.len() - count);
+    }
+
+    // Branch depth of the innermost label of `kind`, or of the one of the loop labelled `name`
+    fn depth(&self, kind: Label, name: &Option<Ident>) -> u32 {
+        let labels = &self.contexts.last().unwrap().labels;
+        let position = labels
+            .iter()
+            .rposition(|(label, loop_name)| {
+                *label == kind
+                    && (name.is_none()
+                        || *loop_name == name.as_ref().map(|name| name.name.as_str()))
+            })
+            .unwrap();
+        (labels.len() - 1 - position) as u32
+    }
+
+    fn push_labels(&mut self, kinds: &[Label], name: &'a Option<Ident>) {
+        let name = name.as_ref().map(|name| name.name.as_str());
+        let labels = kinds.iter().map(|&kind| (kind, name));
+        self.context().labels.extend(labels);
+    }
+
+    // Top-level functions and variables can be used by functions declared before them
+    fn hoist(&mut self, program: &'a [Stmt]) {
+        for stmt in program {
+            match stmt {
+                Stmt::VarStmt(VarStmt { ident, .. }, _) if self.lookup(&ident.name).is_none() => {
+                    let binding = self.variable();
+                    self.bind(&ident.name, binding);
+                }
+                Stmt::FuncDecl(func_decl, span)
+                    if self.lookup(&func_decl.ident_func.name).is_none() =>
+                {
+                    let index = self.reserve();
+                    self.hoisted.insert(span.start, index);
+                    let arity = Arity::params(&func_decl.default_param);
+                    self.bind(&func_decl.ident_func.name, Binding::Func { index, arity });
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn stmts(&mut self, stmts: &'a [Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn block(&mut self, stmts: &'a [Stmt]) {
+        self.context().blocks.push(HashMap::new());
+        self.stmts(stmts);
+        self.context().blocks.pop();
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::BlockStmt(stmts, _) => self.block(stmts),
+            Stmt::FuncDecl(func_decl, span) => self.func_decl(func_decl, *span),
+            Stmt::IfStmt(if_stmt, _) => self.if_stmt(if_stmt),
+            Stmt::ForStmt(for_stmt, _) => self.for_stmt(for_stmt),
+            Stmt::WhileStmt(WhileStmt { label, cond, block }, _) => {
+                self.c().block(BlockType::Empty).loop_(BlockType::Empty);
+                self.push_labels(&[Label::Break, Label::Continue], label);
+                self.expr(cond);
+                self.c().call(Helper::Test.index()).op(I32_EQZ);
+                let depth = self.depth(Label::Break, &None);
+                self.c().br_if(depth);
+                self.block(block);
+                self.c().br(0).op(END).op(END);
+                self.pop_labels(2);
+            }
+            Stmt::DoWhileStmt(DoWhileStmt { label, block, cond }, _) => {
+                // `continue` leaves the inner block to check the condition
+                self.c().block(BlockType::Empty).loop_(BlockType::Empty);
+                self.push_labels(&[Label::Break, Label::Other], label);
+                self.c().block(BlockType::Empty);
+                self.push_labels(&[Label::Continue], label);
+                self.block(block);
+                self.c().op(END);
+                self.context().labels.pop();
+                self.expr(cond);
+                self.c().call(Helper::Test.index()).br_if(0).op(END).op(END);
+                self.pop_labels(2);
+            }
+            Stmt::SwitchStmt(switch_stmt, _) => self.switch_stmt(switch_stmt),
+            Stmt::VarStmt(var_stmt, _) => self.var_stmt(var_stmt),
+            Stmt::ExprStmt(expr, _) => {
+                self.expr(expr);
+                if let Expr::Ident(_) = expr {
+                    self.c().call(Helper::Check.index()); // Reading an unassigned variable fails
+                }
+                self.drop_value();
+            }
+            Stmt::ContinueStmt(label, _) => {
+                let depth = self.depth(Label::Continue, label);
+                self.c().br(depth);
+            }
+            Stmt::BreakStmt(label, _) => {
+                let depth = self.depth(Label::Break, label);
+                self.c().br(depth);
+            }
+            Stmt::ReturnStmt(ReturnStmt { expr }, _) => {
+                match expr {
+                    Some(expr) => self.expr(expr),
+                    None => self.none(),
+                }
+                self.c().op(RETURN);
+            }
+            Stmt::Unreachable(..) => (),
+            Stmt::Error(_) => unreachable!(),
+        }
+    }
+
+    fn func_decl(&mut self, func_decl: &'a FuncDecl, span: Span) {
+        let FuncDecl {
+            ident_func,
+            ident_param,
+            default_param,
+            block,
+            ..
+        } = func_decl;
+        let index = match self.hoisted.remove(&span.start) {
+            Some(index) => index,
+            None => self.reserve(),
+        };
+        let arity = Arity::params(default_param);
+        self.bind(&ident_func.name, Binding::Func { index, arity });
+
+        // Parameters share the scope with the body in Momonga
+        self.enter(ident_param.len() as u32, declares_func(block));
+        for (i, (ident, default)) in ident_param.iter().zip(default_param).enumerate() {
+            let param = Binding::Local(i as u32 * 2);
+            // Missing arguments are undefined, and get their default values seeing the parameters before them
+            if let Some(default) = default {
+                self.c()
+                    .local_get(i as u32 * 2)
+                    .op(I32_EQZ)
+                    .if_(BlockType::Empty);
+                self.expr(default);
+                self.set(param);
+                self.c().op(END);
+            }
+            let binding = match self.context().globals {
+                true => {
+                    let binding = self.variable();
+                    self.get(param);
+                    self.set(binding);
+                    binding
+                }
+                false => param,
+            };
+            self.bind(&ident.name, binding);
+        }
+        self.stmts(block);
+        self.none();
+        self.finish(index, &VALUE);
+    }
+
+    fn if_stmt(&mut self, if_stmt: &'a IfStmt) {
+        let IfStmt {
+            condition,
+            block,
+            else_clause,
+        } = if_stmt;
+        self.expr(condition);
+        self.c().call(Helper::Test.index()).if_(BlockType::Empty);
+        self.context().labels.push((Label::Other, None));
+        self.block(block);
+        match else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => {
+                self.c().op(ELSE);
+                self.if_stmt(if_stmt);
+            }
+            Some(IfStmtElseClause::IfStmtBlock(block)) => {
+                self.c().op(ELSE);
+                self.block(block);
+            }
+            None => (),
+        }
+        self.c().op(END);
+        self.context().labels.pop();
+    }
+
+    // A chain of `if` comparing the subject, kept in a temporary, with `Helper::Eq`
+    fn switch_stmt(&mut self, switch_stmt: &'a SwitchStmt) {
+        let SwitchStmt {
+            subject,
+            cases,
+            default,
+        } = switch_stmt;
+        let temporary = self.temporary();
+        self.expr(subject);
+        if let Expr::Ident(_) = subject {
+            self.c().call(Helper::Check.index());
+        }
+        self.set(temporary);
+        for (i, SwitchStmtCase { value, block }) in cases.iter().enumerate() {
+            if i > 0 {
+                self.c().op(ELSE);
+            }
+            self.get(temporary);
+            self.expr(value);
+            self.c()
+                .call(Helper::Eq.index())
+                .call(Helper::Test.index())
+                .if_(BlockType::Empty);
+            self.context().labels.push((Label::Other, None));
+            self.block(block);
+        }
+        if let Some(block) = default {
+            if !cases.is_empty() {
+                self.c().op(ELSE);
+            }
+            self.block(block);
+        }
+        for _ in cases {
+            self.c().op(END);
+        }
+        self.pop_labels(cases.len());
+    }
+
+    fn for_stmt(&mut self, for_stmt: &'a ForStmt) {
+        let ForStmt {
+            label,
+            init,
+            cond,
+            afterthought,
+            block,
+        } = for_stmt;
+        // The loop variable shares the scope with the body in Momonga
+        self.context().blocks.push(HashMap::new());
+        match init {
+            Some(ForStmtInit::Var(var_stmt)) => self.var_stmt(var_stmt),
+            Some(ForStmtInit::Expr(expr)) => {
+                self.expr(expr);
+                self.drop_value();
+            }
+            None => (),
+        }
+        self.c().block(BlockType::Empty).loop_(BlockType::Empty);
+        self.push_labels(&[Label::Break, Label::Other], label);
+        if let Some(cond) = cond {
+            self.expr(cond);
+            self.c().call(Helper::Test.index()).op(I32_EQZ);
+            let depth = self.depth(Label::Break, &None);
+            self.c().br_if(depth);
+        }
+        self.c().block(BlockType::Empty);
+        self.push_labels(&[Label::Continue], label);
+        self.stmts(block);
+        self.c().op(END);
+        self.context().labels.pop();
+        if let Some(afterthought) = afterthought {
+            self.expr(afterthought);
+            self.drop_value();
+        }
+        self.c().br(0).op(END).op(END);
+        self.pop_labels(2);
+        self.context().blocks.pop();
+    }
+
+    fn var_stmt(&mut self, var_stmt: &'a VarStmt) {
+        let VarStmt { ident, expr, .. } = var_stmt;
+        match expr {
+            Some(expr) => self.expr(expr),
+            None => self.none(),
+        }
+        let redeclared = self
+            .context()
+            .blocks
+            .last()
+            .unwrap()
+            .get(ident.name.as_str())
+            .copied();
+        let binding = match redeclared {
+            Some(binding @ (Binding::Local(_) | Binding::Global(_))) => binding,
+            _ => self.variable(),
+        };
+        self.set(binding);
+        self.bind(&ident.name, binding);
+    }
+
+    fn none(&mut self) {
+        self.c().i32_const(TAG_NONE).i64_const(0);
+    }
+
+    // Push the value of `expr`
+    fn expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Ident(ident) => match self.lookup(&ident.name) {
+                Some(Binding::Func { .. }) => self.fail(EvalError::InvalidExpression),
+                Some(binding) => self.get(binding),
+                None => self.fail(EvalError::Name),
+            },
+            Expr::PrefixOp { kind, rhs } => match (kind, &**rhs) {
+                (PrefixOpKind::Neg, Expr::Literal(Literal::Int(int))) if *int <= MAX_INT + 1 => {
+                    self.c()
+                        .i32_const(TAG_INT)
+                        .i64_const((*int as i64).wrapping_neg());
+                }
+                _ => {
+                    self.expr(rhs);
+                    let helper = match kind {
+                        PrefixOpKind::Neg => Helper::Neg,
+                        PrefixOpKind::Pos => Helper::Pos,
+                        PrefixOpKind::Not => Helper::Not,
+                        PrefixOpKind::BitNot => Helper::BitNot,
+                    };
+                    self.c().call(helper.index());
+                }
+            },
+            Expr::InfixOp { kind, lhs, rhs } => self.infix(kind, lhs, rhs),
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs,
+            } => {
+                self.expr(lhs);
+                self.expr(index);
+                self.c().call(Helper::Index.index());
+            }
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Call(args),
+                lhs,
+            } => self.call(lhs, args),
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                // A value is two numbers, which a block cannot result in without a type of its own
+                let temporary = self.temporary();
+                self.expr(cond);
+                self.c().call(Helper::Test.index()).if_(BlockType::Empty);
+                self.expr(then);
+                self.set(temporary);
+                self.c().op(ELSE);
+                self.expr(otherwise);
+                self.set(temporary);
+                self.c().op(END);
+                self.get(temporary);
+            }
+        }
+    }
+
+    fn infix(&mut self, kind: &InfixOpKind, lhs: &'a Expr, rhs: &'a Expr) {
+        let helper = match kind {
+            InfixOpKind::Add
+            | InfixOpKind::Subtract
+            | InfixOpKind::Multiply
+            | InfixOpKind::Divide
+            | InfixOpKind::Modulo => arithmetic_helper(kind),
+            InfixOpKind::Power => Helper::Pow,
+            InfixOpKind::Eq => Helper::Eq,
+            InfixOpKind::NotEq => Helper::Ne,
+            InfixOpKind::Gt => Helper::Gt,
+            InfixOpKind::Ge => Helper::Ge,
+            InfixOpKind::Lt => Helper::Lt,
+            InfixOpKind::Le => Helper::Le,
+            InfixOpKind::BitAnd => Helper::BitAnd,
+            InfixOpKind::BitOr => Helper::BitOr,
+            InfixOpKind::BitXor => Helper::BitXor,
+            InfixOpKind::ShiftLeft => Helper::Shl,
+            InfixOpKind::ShiftRight => Helper::Shr,
+            InfixOpKind::And | InfixOpKind::Or => {
+                self.c().i32_const(TAG_BOOL);
+                self.expr(lhs);
+                self.c()
+                    .call(Helper::Test.index())
+                    .if_(BlockType::Value(I32));
+                if *kind == InfixOpKind::And {
+                    self.expr(rhs);
+                    self.c().call(Helper::Test.index()).op(ELSE).i32_const(0);
+                } else {
+                    self.c().i32_const(1).op(ELSE);
+                    self.expr(rhs);
+                    self.c().call(Helper::Test.index());
+                }
+                self.c().op(END).op(I64_EXTEND_I32_U);
+                return;
+            }
+            InfixOpKind::Assign
+            | InfixOpKind::AddAssign
+            | InfixOpKind::SubtractAssign
+            | InfixOpKind::MultiplyAssign
+            | InfixOpKind::DivideAssign
+            | InfixOpKind::ModuloAssign => return self.assign(kind, lhs, rhs),
+        };
+        self.expr(lhs);
+        self.expr(rhs);
+        self.c().call(helper.index());
+    }
+
+    // `x += v` as `x = x + v`, and assignments to items through `Helper::Store`
+    fn assign(&mut self, kind: &InfixOpKind, lhs: &'a Expr, rhs: &'a Expr) {
+        match lhs {
+            Expr::Ident(ident) => {
+                match kind.compound() {
+                    Some(op) => self.infix(&op, lhs, rhs),
+                    None => self.expr(rhs),
+                }
+                if self.constants.contains(&ident.span.start) {
+                    self.drop_value();
+                    return self.fail(EvalError::ConstAssign);
+                }
+                match self.lookup(&ident.name) {
+                    Some(Binding::Func { .. }) => {
+                        self.drop_value();
+                        self.fail(EvalError::Type);
+                    }
+                    Some(binding) => {
+                        self.set(binding);
+                        self.get(binding);
+                    }
+                    None => {
+                        self.drop_value();
+                        self.fail(EvalError::Name);
+                    }
+                }
+            }
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs: array,
+            } => {
+                let Some(op) = kind.compound() else {
+                    self.expr(array);
+                    self.expr(index);
+                    self.expr(rhs);
+                    self.c().call(Helper::Store.index());
+                    return;
+                };
+                // The array, the index and the value are evaluated before the item is read
+                let temporaries = [array, index, rhs].map(|expr| {
+                    self.expr(expr);
+                    let temporary = self.temporary();
+                    self.set(temporary);
+                    temporary
+                });
+                for temporary in [0, 1, 0, 1].map(|i| temporaries[i]) {
+                    self.get(temporary);
+                }
+                self.c().call(Helper::Index.index());
+                self.get(temporaries[2]);
+                self.c()
+                    .call(arithmetic_helper(&op).index())
+                    .call(Helper::Store.index());
+            }
+            _ => self.fail(EvalError::Type), // Only variables and items can be assigned
+        }
+    }
+
+    fn call(&mut self, callee: &'a Expr, args: &'a [Expr]) {
+        let Expr::Ident(ident) = callee else {
+            return self.fail(EvalError::Type); // Functions are only called by name
+        };
+        match self.lookup(&ident.name) {
+            Some(Binding::Func { arity, .. }) if !arity.accepts(args.len()) => {
+                // Checked before evaluating the arguments
+                self.fail(EvalError::Argument {
+                    name: ident.name.clone(),
+                    arity,
+                    count: args.len(),
+                });
+            }
+            // Missing arguments are undefined for the function to give them their default values
+            Some(Binding::Func { index, arity }) => {
+                for arg in args {
+                    self.expr(arg);
+                }
+                for _ in args.len()..arity.max.unwrap() {
+                    self.c().i32_const(TAG_UNDEFINED).i64_const(0);
+                }
+                self.c().call(index);
+            }
+            Some(_) => self.fail(EvalError::Type),
+            None => match builtin(&ident.name) {
+                Some(&Builtin { name, arity, .. }) if !arity.accepts(args.len()) => {
+                    // Checked before evaluating the arguments
+                    self.fail(EvalError::Argument {
+                        name: name.to_string(),
+                        arity,
+                        count: args.len(),
+                    });
+                }
+                Some(&Builtin { name, arity, .. }) if arity.max.is_none() => {
+                    let helper = builtin_helper(name);
+                    // Folded over the helper of two arguments once all of them are evaluated, which
+                    // for `print` and `push` writes each value but the last and appends each item
+                    let temporaries: Vec<Binding> = args
+                        .iter()
+                        .map(|arg| {
+                            let temporary = self.temporary();
+                            self.expr(arg);
+                            self.set(temporary);
+                            temporary
+                        })
+                        .collect();
+                    self.get(temporaries[0]);
+                    for temporary in &temporaries[1..] {
+                        self.get(*temporary);
+                        self.c().call(helper.index());
+                    }
+                    match name {
+                        "print" => self.c().call(Helper::Print.index()),
+                        "push" => self.c().call(Helper::ArrayCopy.index()),
+                        _ => self.c(),
+                    };
+                }
+                Some(builtin) => {
+                    for arg in args {
+                        self.expr(arg);
+                    }
+                    self.c().call(builtin_helper(builtin.name).index());
+                }
+                None => self.fail(EvalError::Name),
+            },
+        }
+    }
+
+    fn literal(&mut self, literal: &'a Literal) {
+        match literal {
+            Literal::Bool(bool) => {
+                self.c().i32_const(TAG_BOOL).i64_const(*bool as i64);
+            }
+            Literal::Int(int) if *int > MAX_INT => self.fail(EvalError::OutOfRange),
+            Literal::Int(int) => {
+                self.c().i32_const(TAG_INT).i64_const(*int as i64);
+            }
+            Literal::String(string) => {
+                let address = self.string(string);
+                self.c().i32_const(TAG_STRING).i64_const(address as i64);
+            }
+            Literal::Array(exprs) => {
+                let array = self.local(I32);
+                self.c()
+                    .i32_const(exprs.len() as i32)
+                    .call(Helper::ArrayNew.index())
+                    .local_set(array);
+                for expr in exprs {
+                    self.c().local_get(array);
+                    self.expr(expr);
+                    self.c().call(Helper::Append.index());
+                }
+                self.c()
+                    .i32_const(TAG_ARRAY)
+                    .local_get(array)
+                    .op(I64_EXTEND_I32_U);
+            }
+            Literal::None => self.none(),
+        }
+    }
+}
+
+fn arithmetic_helper(kind: &InfixOpKind) -> Helper {
+    match kind {
+        InfixOpKind::Add => Helper::Add,
+        InfixOpKind::Subtract => Helper::Sub,
+        InfixOpKind::Multiply => Helper::Mul,
+        InfixOpKind::Divide => Helper::Div,
+        _ => Helper::Mod,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::codegen::wasm::*;
+
+    #[test]
+    fn parse_errors_are_returned() {
+        assert!(to_wasm("var x = ;").is_err());
+        assert!(to_wasm("print(1);")
+            .unwrap()
+            .starts_with(b"\0asm\x01\0\0\0"));
+    }
+}
@@ -0,0 +1,1252 @@
+// Runtime helpers of compiled modules, written in WebAssembly instructions.
+//
+// A value is a pair of an `i32` tag and an `i64` payload, which holds ints and bools directly and
+// the address of strings and arrays in the linear memory. Nothing is ever freed.
+//
+//   string {len: i32, bytes: [u8; len]}
+//   array  {len: i32, cap: i32, items: i32}, where items point to `cap` slots of 16 bytes
+//          {tag: i32, _: i32, payload: i64}
+
+use crate::codegen::wasm::encode::ValType::{I32, I64};
+use crate::codegen::wasm::encode::*;
+use crate::error::EvalError;
+
+pub const TAG_UNDEFINED: i32 = 0; // Variables not assigned yet and missing arguments
+pub const TAG_NONE: i32 = 1;
+pub const TAG_BOOL: i32 = 2;
+pub const TAG_INT: i32 = 3;
+pub const TAG_STRING: i32 = 4;
+pub const TAG_ARRAY: i32 = 5;
+
+pub const VALUE: [ValType; 2] = [I32, I64];
+
+// Functions imported from the module "env", which come first in the function index space
+pub const IMPORTS: [(&str, &[ValType], &[ValType]); 3] = [
+    ("print", &[I32, I32], &[]), // Address and length of a line to print
+    ("input", &[], &[I32]),      // Next byte of the standard input, or -1 at the end
+    ("error", &[I32, I32], &[]), // Address and length of an error message, followed by a trap
+];
+const PRINT: u32 = 0;
+const INPUT: u32 = 1;
+const ERROR: u32 = 2;
+
+// Globals, followed by the variables of the program
+pub const HEAP: u32 = 0; // Next free address
+const OUT_PTR: u32 = 1; // Buffer the line to print or the input line is built in
+const OUT_LEN: u32 = 2;
+const OUT_CAP: u32 = 3;
+pub const GLOBALS: u32 = 4;
+
+// Scratch memory for formatting ints, followed by the strings of the runtime
+const SCRATCH_END: i32 = 24;
+const STRINGS: [&str; 7] = ["true", "false", "none", "[", ", ", "]", " "];
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Helper {
+    Alloc,
+    Copy,
+    Concat,
+    StrEq,
+    Check,
+    Test,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    BitAnd,
+    BitOr,
+    BitXor,
+    Shl,
+    Shr,
+    Pow,
+    Same,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Not,
+    Pos,
+    Neg,
+    BitNot,
+    Index,
+    Store,
+    ArrayNew,
+    Append,
+    Len,
+    Push,
+    ArrayCopy,
+    Pop,
+    Abs,
+    Min,
+    Max,
+    Sqrt,
+    Gcd,
+    Emit,
+    EmitStr,
+    EmitInt,
+    Display,
+    Spaced,
+    Print,
+    Input,
+}
+
+// All helpers in the order of declaration, which is their order in the function index space
+const HELPERS: [Helper; 48] = [
+    Helper::Alloc,
+    Helper::Copy,
+    Helper::Concat,
+    Helper::StrEq,
+    Helper::Check,
+    Helper::Test,
+    Helper::Add,
+    Helper::Sub,
+    Helper::Mul,
+    Helper::Div,
+    Helper::Mod,
+    Helper::BitAnd,
+    Helper::BitOr,
+    Helper::BitXor,
+    Helper::Shl,
+    Helper::Shr,
+    Helper::Pow,
+    Helper::Same,
+    Helper::Eq,
+    Helper::Ne,
+    Helper::Lt,
+    Helper::Le,
+    Helper::Gt,
+    Helper::Ge,
+    Helper::Not,
+    Helper::Pos,
+    Helper::Neg,
+    Helper::BitNot,
+    Helper::Index,
+    Helper::Store,
+    Helper::ArrayNew,
+    Helper::Append,
+    Helper::Len,
+    Helper::Push,
+    Helper::ArrayCopy,
+    Helper::Pop,
+    Helper::Abs,
+    Helper::Min,
+    Helper::Max,
+    Helper::Sqrt,
+    Helper::Gcd,
+    Helper::Emit,
+    Helper::EmitStr,
+    Helper::EmitInt,
+    Helper::Display,
+    Helper::Spaced,
+    Helper::Print,
+    Helper::Input,
+];
+
+impl Helper {
+    pub fn index(self) -> u32 {
+        IMPORTS.len() as u32 + self as u32
+    }
+}
+
+// Errors the helpers can fail with, whose messages follow `STRINGS` in the data
+const ERRORS: [EvalError; 5] = [
+    EvalError::Index,
+    EvalError::Name,
+    EvalError::OutOfRange,
+    EvalError::Type,
+    EvalError::ZeroDivision,
+];
+
+// Fail with the message interned at `address`, leaving the stack polymorphic like `unreachable`
+pub fn fail_with(c: &mut Code, address: u32, message: &str) {
+    c.i32_const(address as i32 + 4)
+        .i32_const(message.len() as i32)
+        .call(ERROR)
+        .op(UNREACHABLE);
+}
+
+// Fail with `error` from a helper, whose message is a string of the runtime
+fn fail(c: &mut Code, error: EvalError) {
+    let message = error.to_string();
+    fail_with(c, string(&message) as u32, &message);
+}
+
+// Strings of the runtime in the order they are interned
+fn strings() -> impl Iterator<Item = String> {
+    let messages = ERRORS.iter().map(|error| error.to_string());
+    STRINGS
+        .iter()
+        .map(|string| string.to_string())
+        .chain(messages)
+}
+
+// Imports, runtime globals, strings and helpers of an empty module
+pub fn module() -> Module {
+    let mut module = Module::default();
+    for (name, params, results) in IMPORTS {
+        let type_index = module.type_index(params, results);
+        module.imports.push(Import { name, type_index });
+    }
+    for _ in 0..GLOBALS {
+        module.globals.push(Global {
+            val_type: I32,
+            init: 0,
+        });
+    }
+    module.data = vec![0; SCRATCH_END as usize];
+    for string in strings() {
+        intern(&mut module.data, &string);
+    }
+    for helper in HELPERS {
+        let (params, results, locals, code) = build(helper);
+        let type_index = module.type_index(params, results);
+        module.functions.push(Function {
+            type_index,
+            locals: locals.to_vec(),
+            code,
+        });
+    }
+    module
+}
+
+// Append a string to the data, returning its address
+pub fn intern(data: &mut Vec<u8>, string: &str) -> u32 {
+    data.resize(data.len().next_multiple_of(4), 0);
+    let address = data.len() as u32;
+    data.extend((string.len() as u32).to_le_bytes());
+    data.extend(string.as_bytes());
+    address
+}
+
+// Address of a string of the runtime
+fn string(string: &str) -> i32 {
+    let mut data = vec![0; SCRATCH_END as usize];
+    let mut address = 0;
+    for s in strings() {
+        address = intern(&mut data, &s);
+        if s == string {
+            break;
+        }
+    }
+    address as i32
+}
+
+fn defined(c: &mut Code, local: u32) {
+    c.local_get(local).op(I32_EQZ).if_(BlockType::Empty);
+    fail(c, EvalError::Name);
+    c.op(END);
+}
+
+fn tag_is(c: &mut Code, local: u32, tag: i32) {
+    c.local_get(local)
+        .i32_const(tag)
+        .op(I32_NE)
+        .if_(BlockType::Empty);
+    fail(c, EvalError::Type);
+    c.op(END);
+}
+
+// Both operands of a binary helper are ints
+fn ints(c: &mut Code) {
+    defined(c, 0);
+    defined(c, 2);
+    tag_is(c, 0, TAG_INT);
+    tag_is(c, 2, TAG_INT);
+}
+
+// Address of the `i`th item of the array at `array`, both in locals
+fn item(c: &mut Code, array: u32, i: u32) {
+    c.local_get(array)
+        .mem(I32_LOAD, 8)
+        .local_get(i)
+        .i32_const(16)
+        .op(I32_MUL)
+        .op(I32_ADD);
+}
+
+fn increment(c: &mut Code, local: u32) {
+    c.local_get(local).i32_const(1).op(I32_ADD).local_set(local);
+}
+
+type Signature = (
+    &'static [ValType],
+    &'static [ValType],
+    &'static [ValType],
+    Code,
+);
+
+// Parameters, results, locals and code of each helper
+fn build(helper: Helper) -> Signature {
+    const BINARY: &[ValType] = &[I32, I64, I32, I64];
+    let mut c = Code::default();
+    match helper {
+        Helper::Alloc => {
+            c.global_get(HEAP).local_set(1);
+            c.global_get(HEAP)
+                .local_get(0)
+                .op(I32_ADD)
+                .i32_const(7)
+                .op(I32_ADD)
+                .i32_const(-8)
+                .op(I32_AND)
+                .global_set(HEAP);
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.global_get(HEAP)
+                .memory_size()
+                .i32_const(16)
+                .op(I32_SHL)
+                .op(I32_LE_U)
+                .br_if(1);
+            c.i32_const(1)
+                .memory_grow()
+                .i32_const(-1)
+                .op(I32_EQ)
+                .if_(BlockType::Empty)
+                .op(UNREACHABLE)
+                .op(END);
+            c.br(0).op(END).op(END);
+            c.local_get(1);
+            (&[I32], &[I32], &[I32], c)
+        }
+        Helper::Copy => {
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(2).op(I32_EQZ).br_if(1);
+            c.local_get(0)
+                .local_get(1)
+                .mem(I32_LOAD8_U, 0)
+                .mem(I32_STORE8, 0);
+            increment(&mut c, 0);
+            increment(&mut c, 1);
+            c.local_get(2).i32_const(1).op(I32_SUB).local_set(2);
+            c.br(0).op(END).op(END);
+            (&[I32, I32, I32], &[], &[], c)
+        }
+        Helper::Concat => {
+            c.local_get(0).mem(I32_LOAD, 0).local_set(2);
+            c.local_get(1).mem(I32_LOAD, 0).local_set(3);
+            c.local_get(2)
+                .local_get(3)
+                .op(I32_ADD)
+                .i32_const(4)
+                .op(I32_ADD)
+                .call(Helper::Alloc.index())
+                .local_set(4);
+            c.local_get(4)
+                .local_get(2)
+                .local_get(3)
+                .op(I32_ADD)
+                .mem(I32_STORE, 0);
+            c.local_get(4)
+                .i32_const(4)
+                .op(I32_ADD)
+                .local_get(0)
+                .i32_const(4)
+                .op(I32_ADD)
+                .local_get(2)
+                .call(Helper::Copy.index());
+            c.local_get(4)
+                .i32_const(4)
+                .op(I32_ADD)
+                .local_get(2)
+                .op(I32_ADD)
+                .local_get(1)
+                .i32_const(4)
+                .op(I32_ADD)
+                .local_get(3)
+                .call(Helper::Copy.index());
+            c.local_get(4);
+            (&[I32, I32], &[I32], &[I32, I32, I32], c)
+        }
+        Helper::StrEq => {
+            c.local_get(0)
+                .mem(I32_LOAD, 0)
+                .local_tee(2)
+                .local_get(1)
+                .mem(I32_LOAD, 0)
+                .op(I32_NE)
+                .if_(BlockType::Empty)
+                .i32_const(0)
+                .op(RETURN)
+                .op(END);
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(3).local_get(2).op(I32_GE_S).br_if(1);
+            c.local_get(0)
+                .local_get(3)
+                .op(I32_ADD)
+                .mem(I32_LOAD8_U, 4)
+                .local_get(1)
+                .local_get(3)
+                .op(I32_ADD)
+                .mem(I32_LOAD8_U, 4)
+                .op(I32_NE)
+                .if_(BlockType::Empty)
+                .i32_const(0)
+                .op(RETURN)
+                .op(END);
+            increment(&mut c, 3);
+            c.br(0).op(END).op(END);
+            c.i32_const(1);
+            (&[I32, I32], &[I32], &[I32, I32], c)
+        }
+        Helper::Check => {
+            defined(&mut c, 0);
+            c.local_get(0).local_get(1);
+            (&VALUE, &VALUE, &[], c)
+        }
+        Helper::Test => {
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_BOOL);
+            c.local_get(1).op(I32_WRAP_I64);
+            (&VALUE, &[I32], &[], c)
+        }
+        Helper::Add => {
+            defined(&mut c, 0);
+            defined(&mut c, 2);
+            for tag in [TAG_INT, TAG_STRING] {
+                c.local_get(0)
+                    .i32_const(tag)
+                    .op(I32_EQ)
+                    .local_get(2)
+                    .i32_const(tag)
+                    .op(I32_EQ)
+                    .op(I32_AND)
+                    .if_(BlockType::Empty)
+                    .i32_const(tag);
+                match tag {
+                    TAG_INT => {
+                        // Overflowed if the sum has another sign than both operands
+                        c.local_get(1).local_get(3).op(I64_ADD).local_set(4);
+                        c.local_get(1)
+                            .local_get(4)
+                            .op(I64_XOR)
+                            .local_get(3)
+                            .local_get(4)
+                            .op(I64_XOR)
+                            .op(I64_AND)
+                            .i64_const(0)
+                            .op(I64_LT_S)
+                            .if_(BlockType::Empty);
+                        fail(&mut c, EvalError::OutOfRange);
+                        c.op(END).local_get(4)
+                    }
+                    _ => c
+                        .local_get(1)
+                        .op(I32_WRAP_I64)
+                        .local_get(3)
+                        .op(I32_WRAP_I64)
+                        .call(Helper::Concat.index())
+                        .op(I64_EXTEND_I32_U),
+                };
+                c.op(RETURN).op(END);
+            }
+            fail(&mut c, EvalError::Type);
+            (BINARY, &VALUE, &[I64], c)
+        }
+        Helper::Sub => {
+            ints(&mut c);
+            // Overflowed if the operands differ in sign and the difference has the sign of the
+            // right one
+            c.local_get(1).local_get(3).op(I64_SUB).local_set(4);
+            c.local_get(1)
+                .local_get(3)
+                .op(I64_XOR)
+                .local_get(1)
+                .local_get(4)
+                .op(I64_XOR)
+                .op(I64_AND)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            c.i32_const(TAG_INT).local_get(4);
+            (BINARY, &VALUE, &[I64], c)
+        }
+        Helper::Mul => {
+            ints(&mut c);
+            // Dividing the product back detects an overflow, except for -1 times the minimum
+            c.local_get(1).local_get(3).op(I64_MUL).local_set(4);
+            c.local_get(1)
+                .i64_const(1)
+                .op(I64_ADD)
+                .i64_const(2)
+                .op(I64_GT_U)
+                .if_(BlockType::Value(I32))
+                .local_get(4)
+                .local_get(1)
+                .op(I64_DIV_S)
+                .local_get(3)
+                .op(I64_NE)
+                .op(ELSE)
+                .local_get(1)
+                .i64_const(-1)
+                .op(I64_EQ)
+                .local_get(3)
+                .i64_const(i64::MIN)
+                .op(I64_EQ)
+                .op(I32_AND)
+                .op(END)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            c.i32_const(TAG_INT).local_get(4);
+            (BINARY, &VALUE, &[I64], c)
+        }
+        Helper::BitAnd | Helper::BitOr | Helper::BitXor => {
+            ints(&mut c);
+            let op = match helper {
+                Helper::BitAnd => I64_AND,
+                Helper::BitOr => I64_OR,
+                _ => I64_XOR,
+            };
+            c.i32_const(TAG_INT).local_get(1).local_get(3).op(op);
+            (BINARY, &VALUE, &[], c)
+        }
+        Helper::Shl | Helper::Shr => {
+            ints(&mut c);
+            // Negative amounts are above 63 as well when unsigned
+            c.local_get(3)
+                .i64_const(63)
+                .op(I64_GT_U)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            let op = if helper == Helper::Shl {
+                I64_SHL
+            } else {
+                I64_SHR_S
+            };
+            c.i32_const(TAG_INT).local_get(1).local_get(3).op(op);
+            (BINARY, &VALUE, &[], c)
+        }
+        Helper::Div | Helper::Mod => {
+            ints(&mut c);
+            c.local_get(3).op(I64_EQZ).if_(BlockType::Empty);
+            fail(&mut c, EvalError::ZeroDivision);
+            c.op(END);
+            // Dividing the minimum by -1 overflows, and the remainder fails like the quotient
+            c.local_get(1)
+                .i64_const(i64::MIN)
+                .op(I64_EQ)
+                .local_get(3)
+                .i64_const(-1)
+                .op(I64_EQ)
+                .op(I32_AND)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            let op = if helper == Helper::Div {
+                I64_DIV_S
+            } else {
+                I64_REM_S
+            };
+            c.i32_const(TAG_INT).local_get(1).local_get(3).op(op);
+            (BINARY, &VALUE, &[], c)
+        }
+        Helper::Pow => {
+            ints(&mut c);
+            c.local_get(3)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            // Exponents beyond 64 as in `data::int_pow`
+            c.i64_const(64)
+                .local_get(3)
+                .i64_const(2)
+                .op(I64_REM_S)
+                .op(I64_ADD)
+                .local_get(3)
+                .local_get(3)
+                .i64_const(64)
+                .op(I64_GT_S)
+                .op(SELECT)
+                .local_set(5);
+            c.i64_const(1).local_set(4);
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(5).op(I64_EQZ).br_if(1);
+            c.local_get(4).local_get(1).op(I64_MUL).local_set(6);
+            // Only bases other than -1, 0 and 1 can overflow, and dividing by them never traps
+            c.local_get(1)
+                .i64_const(1)
+                .op(I64_ADD)
+                .i64_const(2)
+                .op(I64_GT_U)
+                .if_(BlockType::Empty)
+                .local_get(6)
+                .local_get(1)
+                .op(I64_DIV_S)
+                .local_get(4)
+                .op(I64_NE)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END).op(END);
+            c.local_get(6).local_set(4);
+            c.local_get(5).i64_const(1).op(I64_SUB).local_set(5);
+            c.br(0).op(END).op(END);
+            c.i32_const(TAG_INT).local_get(4);
+            (BINARY, &VALUE, &[I64, I64, I64], c)
+        }
+        Helper::Same => {
+            c.local_get(0)
+                .local_get(2)
+                .op(I32_NE)
+                .if_(BlockType::Empty)
+                .i32_const(0)
+                .op(RETURN)
+                .op(END);
+            c.local_get(0)
+                .i32_const(TAG_STRING)
+                .op(I32_EQ)
+                .if_(BlockType::Empty)
+                .local_get(1)
+                .op(I32_WRAP_I64)
+                .local_get(3)
+                .op(I32_WRAP_I64)
+                .call(Helper::StrEq.index())
+                .op(RETURN)
+                .op(END);
+            c.local_get(0)
+                .i32_const(TAG_ARRAY)
+                .op(I32_NE)
+                .if_(BlockType::Empty)
+                .local_get(1)
+                .local_get(3)
+                .op(I64_EQ)
+                .op(RETURN)
+                .op(END);
+            c.local_get(1).op(I32_WRAP_I64).local_set(4);
+            c.local_get(3).op(I32_WRAP_I64).local_set(5);
+            c.local_get(4)
+                .mem(I32_LOAD, 0)
+                .local_tee(6)
+                .local_get(5)
+                .mem(I32_LOAD, 0)
+                .op(I32_NE)
+                .if_(BlockType::Empty)
+                .i32_const(0)
+                .op(RETURN)
+                .op(END);
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(7).local_get(6).op(I32_GE_S).br_if(1);
+            item(&mut c, 4, 7);
+            c.local_set(8);
+            item(&mut c, 5, 7);
+            c.local_set(9);
+            c.local_get(8)
+                .mem(I32_LOAD, 0)
+                .local_get(8)
+                .mem(I64_LOAD, 8)
+                .local_get(9)
+                .mem(I32_LOAD, 0)
+                .local_get(9)
+                .mem(I64_LOAD, 8)
+                .call(Helper::Same.index())
+                .op(I32_EQZ)
+                .if_(BlockType::Empty)
+                .i32_const(0)
+                .op(RETURN)
+                .op(END);
+            increment(&mut c, 7);
+            c.br(0).op(END).op(END);
+            c.i32_const(1);
+            (BINARY, &[I32], &[I32, I32, I32, I32, I32, I32], c)
+        }
+        Helper::Eq => {
+            defined(&mut c, 0);
+            defined(&mut c, 2);
+            c.local_get(0)
+                .local_get(2)
+                .op(I32_NE)
+                .local_get(0)
+                .i32_const(TAG_NONE)
+                .op(I32_EQ)
+                .op(I32_OR)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::Type);
+            c.op(END);
+            c.i32_const(TAG_BOOL)
+                .local_get(0)
+                .local_get(1)
+                .local_get(2)
+                .local_get(3)
+                .call(Helper::Same.index())
+                .op(I64_EXTEND_I32_U);
+            (BINARY, &VALUE, &[], c)
+        }
+        Helper::Ne => {
+            c.local_get(0)
+                .local_get(1)
+                .local_get(2)
+                .local_get(3)
+                .call(Helper::Eq.index())
+                .op(I64_EQZ)
+                .op(I64_EXTEND_I32_U);
+            (BINARY, &VALUE, &[], c)
+        }
+        Helper::Lt | Helper::Le | Helper::Gt | Helper::Ge => {
+            ints(&mut c);
+            let op = match helper {
+                Helper::Lt => I64_LT_S,
+                Helper::Le => I64_LE_S,
+                Helper::Gt => I64_GT_S,
+                _ => I64_GE_S,
+            };
+            c.i32_const(TAG_BOOL)
+                .local_get(1)
+                .local_get(3)
+                .op(op)
+                .op(I64_EXTEND_I32_U);
+            (BINARY, &VALUE, &[], c)
+        }
+        Helper::Not => {
+            c.i32_const(TAG_BOOL)
+                .local_get(0)
+                .local_get(1)
+                .call(Helper::Test.index())
+                .op(I32_EQZ)
+                .op(I64_EXTEND_I32_U);
+            (&VALUE, &VALUE, &[], c)
+        }
+        Helper::Pos => {
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_INT);
+            c.local_get(0).local_get(1);
+            (&VALUE, &VALUE, &[], c)
+        }
+        Helper::Neg => {
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_INT);
+            c.local_get(1)
+                .i64_const(i64::MIN)
+                .op(I64_EQ)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            c.i32_const(TAG_INT).i64_const(0).local_get(1).op(I64_SUB);
+            (&VALUE, &VALUE, &[], c)
+        }
+        Helper::BitNot => {
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_INT);
+            c.i32_const(TAG_INT).local_get(1).i64_const(-1).op(I64_XOR);
+            (&VALUE, &VALUE, &[], c)
+        }
+        Helper::Index => {
+            defined(&mut c, 2);
+            tag_is(&mut c, 2, TAG_INT);
+            c.local_get(3)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::Index);
+            c.op(END);
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_ARRAY);
+            c.local_get(1).op(I32_WRAP_I64).local_set(4);
+            c.local_get(3)
+                .local_get(4)
+                .mem(I32_LOAD, 0)
+                .op(I64_EXTEND_I32_U)
+                .op(I64_GE_U)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::Index);
+            c.op(END);
+            c.local_get(3).op(I32_WRAP_I64).local_set(5);
+            item(&mut c, 4, 5);
+            c.local_tee(5)
+                .mem(I32_LOAD, 0)
+                .local_get(5)
+                .mem(I64_LOAD, 8);
+            (BINARY, &VALUE, &[I32, I32], c)
+        }
+        Helper::Store => {
+            defined(&mut c, 4);
+            // Checked like reading the item
+            c.local_get(0)
+                .local_get(1)
+                .local_get(2)
+                .local_get(3)
+                .call(Helper::Index.index())
+                .op(DROP)
+                .op(DROP);
+            c.local_get(1).op(I32_WRAP_I64).local_set(6);
+            c.local_get(3).op(I32_WRAP_I64).local_set(7);
+            item(&mut c, 6, 7);
+            c.local_tee(7).local_get(4).mem(I32_STORE, 0);
+            c.local_get(7).local_get(5).mem(I64_STORE, 8);
+            c.local_get(4).local_get(5);
+            (&[I32, I64, I32, I64, I32, I64], &VALUE, &[I32, I32], c)
+        }
+        Helper::ArrayNew => {
+            c.i32_const(12).call(Helper::Alloc.index()).local_set(1);
+            c.local_get(1).i32_const(0).mem(I32_STORE, 0);
+            c.local_get(1).local_get(0).mem(I32_STORE, 4);
+            c.local_get(1)
+                .local_get(0)
+                .i32_const(16)
+                .op(I32_MUL)
+                .call(Helper::Alloc.index())
+                .mem(I32_STORE, 8);
+            c.local_get(1);
+            (&[I32], &[I32], &[I32], c)
+        }
+        Helper::Append => {
+            defined(&mut c, 1);
+            c.local_get(0).mem(I32_LOAD, 0).local_set(3);
+            c.local_get(3)
+                .local_get(0)
+                .mem(I32_LOAD, 4)
+                .op(I32_EQ)
+                .if_(BlockType::Empty);
+            c.local_get(3)
+                .i32_const(2)
+                .op(I32_MUL)
+                .i32_const(1)
+                .op(I32_ADD)
+                .local_set(4);
+            c.local_get(4)
+                .i32_const(16)
+                .op(I32_MUL)
+                .call(Helper::Alloc.index())
+                .local_tee(5)
+                .local_get(0)
+                .mem(I32_LOAD, 8)
+                .local_get(3)
+                .i32_const(16)
+                .op(I32_MUL)
+                .call(Helper::Copy.index());
+            c.local_get(0).local_get(5).mem(I32_STORE, 8);
+            c.local_get(0).local_get(4).mem(I32_STORE, 4);
+            c.op(END);
+            item(&mut c, 0, 3);
+            c.local_tee(5).local_get(1).mem(I32_STORE, 0);
+            c.local_get(5).local_get(2).mem(I64_STORE, 8);
+            c.local_get(0)
+                .local_get(3)
+                .i32_const(1)
+                .op(I32_ADD)
+                .mem(I32_STORE, 0);
+            (&[I32, I32, I64], &[], &[I32, I32, I32], c)
+        }
+        Helper::Len => {
+            defined(&mut c, 0);
+            c.local_get(0)
+                .i32_const(TAG_STRING)
+                .op(I32_NE)
+                .local_get(0)
+                .i32_const(TAG_ARRAY)
+                .op(I32_NE)
+                .op(I32_AND)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::Type);
+            c.op(END);
+            // Strings and arrays both start with the length
+            c.i32_const(TAG_INT)
+                .local_get(1)
+                .op(I32_WRAP_I64)
+                .mem(I32_LOAD, 0)
+                .op(I64_EXTEND_I32_U);
+            (&VALUE, &VALUE, &[], c)
+        }
+        // Item by item of `push`, returning the array itself
+        Helper::Push => {
+            defined(&mut c, 0);
+            defined(&mut c, 2);
+            tag_is(&mut c, 0, TAG_ARRAY);
+            c.local_get(1)
+                .op(I32_WRAP_I64)
+                .local_get(2)
+                .local_get(3)
+                .call(Helper::Append.index());
+            c.local_get(0).local_get(1);
+            (BINARY, &VALUE, &[], c)
+        }
+        // What `push` returns like `data::momonga_push`
+        Helper::ArrayCopy => {
+            c.local_get(1).op(I32_WRAP_I64).local_set(2);
+            c.local_get(2)
+                .mem(I32_LOAD, 0)
+                .call(Helper::ArrayNew.index())
+                .local_set(3);
+            c.local_get(3)
+                .mem(I32_LOAD, 8)
+                .local_get(2)
+                .mem(I32_LOAD, 8)
+                .local_get(2)
+                .mem(I32_LOAD, 0)
+                .i32_const(16)
+                .op(I32_MUL)
+                .call(Helper::Copy.index());
+            c.local_get(3)
+                .local_get(2)
+                .mem(I32_LOAD, 0)
+                .mem(I32_STORE, 0);
+            c.i32_const(TAG_ARRAY).local_get(3).op(I64_EXTEND_I32_U);
+            (&VALUE, &VALUE, &[I32, I32], c)
+        }
+        Helper::Pop => {
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_ARRAY);
+            c.local_get(1).op(I32_WRAP_I64).local_set(2);
+            c.local_get(2)
+                .mem(I32_LOAD, 0)
+                .local_tee(3)
+                .op(I32_EQZ)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::Index);
+            c.op(END);
+            c.local_get(2)
+                .local_get(3)
+                .i32_const(1)
+                .op(I32_SUB)
+                .local_tee(3)
+                .mem(I32_STORE, 0);
+            item(&mut c, 2, 3);
+            c.local_tee(3)
+                .mem(I32_LOAD, 0)
+                .local_get(3)
+                .mem(I64_LOAD, 8);
+            (&VALUE, &VALUE, &[I32, I32], c)
+        }
+        Helper::Abs => {
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_INT);
+            c.local_get(1)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty)
+                .local_get(0)
+                .local_get(1)
+                .call(Helper::Neg.index())
+                .op(RETURN)
+                .op(END);
+            c.local_get(0).local_get(1);
+            (&VALUE, &VALUE, &[], c)
+        }
+        Helper::Min | Helper::Max => {
+            ints(&mut c);
+            let op = if helper == Helper::Min {
+                I64_LT_S
+            } else {
+                I64_GT_S
+            };
+            c.i32_const(TAG_INT)
+                .local_get(3)
+                .local_get(1)
+                .local_get(3)
+                .local_get(1)
+                .op(op)
+                .op(SELECT);
+            (BINARY, &VALUE, &[], c)
+        }
+        Helper::Sqrt => {
+            defined(&mut c, 0);
+            tag_is(&mut c, 0, TAG_INT);
+            c.local_get(1)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            c.local_get(1)
+                .i64_const(2)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty)
+                .local_get(0)
+                .local_get(1)
+                .op(RETURN)
+                .op(END);
+            // Newton's method from above as in `data::momonga_sqrt`
+            let next = |c: &mut Code| {
+                c.local_get(2)
+                    .local_get(1)
+                    .local_get(2)
+                    .op(I64_DIV_S)
+                    .op(I64_ADD)
+                    .i64_const(2)
+                    .op(I64_DIV_S)
+                    .local_set(3);
+            };
+            c.local_get(1)
+                .i64_const(2)
+                .op(I64_DIV_S)
+                .i64_const(1)
+                .op(I64_ADD)
+                .local_set(2);
+            next(&mut c);
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(3).local_get(2).op(I64_GE_S).br_if(1);
+            c.local_get(3).local_set(2);
+            next(&mut c);
+            c.br(0).op(END).op(END);
+            c.i32_const(TAG_INT).local_get(2);
+            (&VALUE, &VALUE, &[I64, I64], c)
+        }
+        Helper::Gcd => {
+            ints(&mut c);
+            // Absolute values, of which only that of the minimum stays negative
+            for (param, local) in [(1, 4), (3, 5)] {
+                c.i64_const(0)
+                    .local_get(param)
+                    .op(I64_SUB)
+                    .local_get(param)
+                    .local_get(param)
+                    .i64_const(0)
+                    .op(I64_LT_S)
+                    .op(SELECT)
+                    .local_set(local);
+            }
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(5).op(I64_EQZ).br_if(1);
+            c.local_get(4).local_get(5).op(I64_REM_U).local_set(6);
+            c.local_get(5).local_set(4);
+            c.local_get(6).local_set(5);
+            c.br(0).op(END).op(END);
+            c.local_get(4)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty);
+            fail(&mut c, EvalError::OutOfRange);
+            c.op(END);
+            c.i32_const(TAG_INT).local_get(4);
+            (BINARY, &VALUE, &[I64, I64, I64], c)
+        }
+        Helper::Emit => {
+            c.global_get(OUT_LEN)
+                .local_get(1)
+                .op(I32_ADD)
+                .global_get(OUT_CAP)
+                .op(I32_GT_U)
+                .if_(BlockType::Empty);
+            c.global_get(OUT_LEN)
+                .local_get(1)
+                .op(I32_ADD)
+                .i32_const(2)
+                .op(I32_MUL)
+                .local_tee(3)
+                .call(Helper::Alloc.index())
+                .local_tee(2)
+                .global_get(OUT_PTR)
+                .global_get(OUT_LEN)
+                .call(Helper::Copy.index());
+            c.local_get(2).global_set(OUT_PTR);
+            c.local_get(3).global_set(OUT_CAP);
+            c.op(END);
+            c.global_get(OUT_PTR)
+                .global_get(OUT_LEN)
+                .op(I32_ADD)
+                .local_get(0)
+                .local_get(1)
+                .call(Helper::Copy.index());
+            c.global_get(OUT_LEN)
+                .local_get(1)
+                .op(I32_ADD)
+                .global_set(OUT_LEN);
+            (&[I32, I32], &[], &[I32, I32], c)
+        }
+        Helper::EmitStr => {
+            c.local_get(0)
+                .i32_const(4)
+                .op(I32_ADD)
+                .local_get(0)
+                .mem(I32_LOAD, 0)
+                .call(Helper::Emit.index());
+            (&[I32], &[], &[], c)
+        }
+        Helper::EmitInt => {
+            // Digits are written backwards from the end of the scratch memory
+            c.local_get(0)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Value(I64))
+                .i64_const(0)
+                .local_get(0)
+                .op(I64_SUB)
+                .op(ELSE)
+                .local_get(0)
+                .op(END)
+                .local_set(1);
+            c.i32_const(SCRATCH_END).local_set(2);
+            c.loop_(BlockType::Empty);
+            c.local_get(2)
+                .i32_const(1)
+                .op(I32_SUB)
+                .local_tee(2)
+                .local_get(1)
+                .i64_const(10)
+                .op(I64_REM_U)
+                .op(I32_WRAP_I64)
+                .i32_const(b'0' as i32)
+                .op(I32_ADD)
+                .mem(I32_STORE8, 0);
+            c.local_get(1)
+                .i64_const(10)
+                .op(I64_DIV_U)
+                .local_tee(1)
+                .i64_const(0)
+                .op(I64_NE)
+                .br_if(0)
+                .op(END);
+            c.local_get(0)
+                .i64_const(0)
+                .op(I64_LT_S)
+                .if_(BlockType::Empty);
+            c.local_get(2)
+                .i32_const(1)
+                .op(I32_SUB)
+                .local_tee(2)
+                .i32_const(b'-' as i32)
+                .mem(I32_STORE8, 0);
+            c.op(END);
+            c.local_get(2)
+                .i32_const(SCRATCH_END)
+                .local_get(2)
+                .op(I32_SUB)
+                .call(Helper::Emit.index());
+            (&[I64], &[], &[I64, I32], c)
+        }
+        Helper::Display => {
+            let emit_str = Helper::EmitStr.index();
+            c.local_get(0)
+                .i32_const(TAG_BOOL)
+                .op(I32_EQ)
+                .if_(BlockType::Empty)
+                .i32_const(string("true"))
+                .i32_const(string("false"))
+                .local_get(1)
+                .op(I32_WRAP_I64)
+                .op(SELECT)
+                .call(emit_str)
+                .op(RETURN)
+                .op(END);
+            c.local_get(0)
+                .i32_const(TAG_INT)
+                .op(I32_EQ)
+                .if_(BlockType::Empty)
+                .local_get(1)
+                .call(Helper::EmitInt.index())
+                .op(RETURN)
+                .op(END);
+            c.local_get(0)
+                .i32_const(TAG_STRING)
+                .op(I32_EQ)
+                .if_(BlockType::Empty)
+                .local_get(1)
+                .op(I32_WRAP_I64)
+                .call(emit_str)
+                .op(RETURN)
+                .op(END);
+            c.local_get(0)
+                .i32_const(TAG_ARRAY)
+                .op(I32_EQ)
+                .if_(BlockType::Empty);
+            c.i32_const(string("[")).call(emit_str);
+            c.local_get(1).op(I32_WRAP_I64).local_set(2);
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(3)
+                .local_get(2)
+                .mem(I32_LOAD, 0)
+                .op(I32_GE_S)
+                .br_if(1);
+            c.local_get(3)
+                .if_(BlockType::Empty)
+                .i32_const(string(", "))
+                .call(emit_str)
+                .op(END);
+            item(&mut c, 2, 3);
+            c.local_tee(4)
+                .mem(I32_LOAD, 0)
+                .local_get(4)
+                .mem(I64_LOAD, 8)
+                .call(Helper::Display.index());
+            increment(&mut c, 3);
+            c.br(0).op(END).op(END);
+            c.i32_const(string("]")).call(emit_str).op(RETURN);
+            c.op(END);
+            c.i32_const(string("none")).call(emit_str);
+            (&VALUE, &[], &[I32, I32, I32], c)
+        }
+        // Value by value of `print` but the last, returning the next one
+        Helper::Spaced => {
+            defined(&mut c, 2);
+            defined(&mut c, 0);
+            c.local_get(0).local_get(1).call(Helper::Display.index());
+            c.i32_const(string(" ")).call(Helper::EmitStr.index());
+            c.local_get(2).local_get(3);
+            (BINARY, &VALUE, &[], c)
+        }
+        // The output buffer is left empty, holding what `Spaced` writes before the line is printed
+        Helper::Print => {
+            defined(&mut c, 0);
+            c.local_get(0).local_get(1).call(Helper::Display.index());
+            c.global_get(OUT_PTR).global_get(OUT_LEN).call(PRINT);
+            c.i32_const(0).global_set(OUT_LEN);
+            c.i32_const(TAG_NONE).i64_const(0);
+            (&VALUE, &VALUE, &[], c)
+        }
+        Helper::Input => {
+            c.call(INPUT)
+                .local_tee(0)
+                .i32_const(-1)
+                .op(I32_EQ)
+                .if_(BlockType::Empty)
+                .i32_const(TAG_NONE)
+                .i64_const(0)
+                .op(RETURN)
+                .op(END);
+            c.i32_const(0).global_set(OUT_LEN);
+            c.block(BlockType::Empty).loop_(BlockType::Empty);
+            c.local_get(0)
+                .i32_const(-1)
+                .op(I32_EQ)
+                .local_get(0)
+                .i32_const(b'\n' as i32)
+                .op(I32_EQ)
+                .op(I32_OR)
+                .br_if(1);
+            c.i32_const(0).local_get(0).mem(I32_STORE8, 0);
+            c.i32_const(0).i32_const(1).call(Helper::Emit.index());
+            c.call(INPUT).local_set(0);
+            c.br(0).op(END).op(END);
+            // Without the line break of Windows
+            c.global_get(OUT_LEN).local_set(2);
+            c.local_get(2).if_(BlockType::Empty);
+            c.global_get(OUT_PTR)
+                .local_get(2)
+                .op(I32_ADD)
+                .i32_const(1)
+                .op(I32_SUB)
+                .mem(I32_LOAD8_U, 0)
+                .i32_const(b'\r' as i32)
+                .op(I32_EQ)
+                .if_(BlockType::Empty)
+                .local_get(2)
+                .i32_const(1)
+                .op(I32_SUB)
+                .local_set(2)
+                .op(END);
+            c.op(END);
+            c.local_get(2)
+                .i32_const(4)
+                .op(I32_ADD)
+                .call(Helper::Alloc.index())
+                .local_tee(1)
+                .local_get(2)
+                .mem(I32_STORE, 0);
+            c.local_get(1)
+                .i32_const(4)
+                .op(I32_ADD)
+                .global_get(OUT_PTR)
+                .local_get(2)
+                .call(Helper::Copy.index());
+            c.i32_const(0).global_set(OUT_LEN);
+            c.i32_const(TAG_STRING).local_get(1).op(I64_EXTEND_I32_U);
+            (&[], &VALUE, &[I32, I32, I32], c)
+        }
+    }
+}
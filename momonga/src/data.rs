@@ -1,10 +1,12 @@
 use core::fmt;
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
+use crate::ast::{Expr, TypeAnnotation};
 use crate::env::Store;
 use crate::error::EvalError;
-use crate::{emit_output_event, OutputEvent};
+use crate::stdio;
 
+#[allow(unknown_lints, unpredictable_function_pointer_comparisons)]
 #[derive(Debug, PartialEq, Clone)]
 pub enum Value<'a> {
     Bool(bool),
@@ -14,14 +16,68 @@ pub enum Value<'a> {
     None,
     Func {
         params: &'a Vec<crate::ast::Ident>,
+        annotation_param: &'a Vec<Option<TypeAnnotation>>,
+        default_param: &'a Vec<Option<Expr>>,
+        annotation_return: Option<TypeAnnotation>,
         block: &'a crate::ast::BlockStmt,
     },
     Builtin(
-        i64,                                  // Number of arguments
-        fn(BuiltinArgs<'a>) -> BuiltinReturn, // Function itself
+        Arity,         // Numbers of arguments it can be called with
+        BuiltinFn<'a>, // Function itself
     ),
 }
 
+// Least number of arguments of a function, and the most unless it takes any number of them
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Arity {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    pub const fn exact(count: usize) -> Self {
+        Arity {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    pub const fn at_least(min: usize) -> Self {
+        Arity { min, max: None }
+    }
+
+    // Of a user function, whose parameters with default values can be left out
+    pub fn params(default_param: &[Option<Expr>]) -> Self {
+        Arity {
+            min: default_param
+                .iter()
+                .take_while(|default| default.is_none())
+                .count(),
+            max: Some(default_param.len()),
+        }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        match self.max {
+            Some(max) => (self.min..=max).contains(&count),
+            None => count >= self.min,
+        }
+    }
+}
+
+// As in "`print` expects at least 1 argument"
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (count, last) = match self.max {
+            Some(max) if max == self.min => (format!("{}", max), max),
+            Some(max) => (format!("{} to {}", self.min, max), max),
+            None => (format!("at least {}", self.min), self.min),
+        };
+        let noun = if last == 1 { "argument" } else { "arguments" };
+        write!(f, "{} {}", count, noun)
+    }
+}
+
 impl<'a> fmt::Display for Value<'a> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -46,26 +102,129 @@ impl<'a> fmt::Display for Value<'a> {
     }
 }
 
+impl<'a> Value<'a> {
+    // A value of another type than the annotation of the name it is bound to is a type error
+    pub fn check_annotation(&self, annotation: Option<TypeAnnotation>) -> Result<(), EvalError> {
+        match (annotation, self) {
+            (None, _)
+            | (Some(TypeAnnotation::Int), Value::Int(_))
+            | (Some(TypeAnnotation::Bool), Value::Bool(_))
+            | (Some(TypeAnnotation::String), Value::String(_))
+            | (Some(TypeAnnotation::Array), Value::Array(_))
+            | (Some(TypeAnnotation::None), Value::None) => Ok(()),
+            _ => Err(EvalError::Type),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Array<'a>(pub RefCell<Vec<Rc<RefCell<Value<'a>>>>>);
 
+// Function predefined in the global scope, which the backends and the resolver look up by name
+pub struct Builtin {
+    pub name: &'static str,
+    pub arity: Arity, // Numbers of arguments it can be called with
+    pub params: &'static [&'static str], // Parameters, the last one repeated if variadic
+    pub doc: &'static str, // Description shown by editor features
+    pub function: for<'a> fn(BuiltinArgs<'a>) -> BuiltinReturn<'a>,
+}
+
+pub static BUILTINS: [Builtin; 11] = [
+    Builtin {
+        name: "len",
+        arity: Arity::exact(1),
+        params: &["value"],
+        doc: "Returns the number of characters of a string or items of an array.",
+        function: momonga_len,
+    },
+    Builtin {
+        name: "push",
+        arity: Arity::at_least(2),
+        params: &["array", "item"],
+        doc: "Appends items to an array and returns a copy of the array.",
+        function: momonga_push,
+    },
+    Builtin {
+        name: "pop",
+        arity: Arity::exact(1),
+        params: &["array"],
+        doc: "Removes the last item of an array and returns it.",
+        function: momonga_pop,
+    },
+    Builtin {
+        name: "print",
+        arity: Arity::at_least(1),
+        params: &["value"],
+        doc: "Prints values separated by spaces, followed by a line break.",
+        function: momonga_print,
+    },
+    Builtin {
+        name: "input",
+        arity: Arity::exact(0),
+        params: &[],
+        doc: "Reads a line from the standard input, or returns `none` at its end.",
+        function: momonga_input,
+    },
+    Builtin {
+        name: "abs",
+        arity: Arity::exact(1),
+        params: &["n"],
+        doc: "Returns the absolute value of an int.",
+        function: momonga_abs,
+    },
+    Builtin {
+        name: "min",
+        arity: Arity::at_least(2),
+        params: &["a", "b"],
+        doc: "Returns the smallest of two or more ints.",
+        function: momonga_min,
+    },
+    Builtin {
+        name: "max",
+        arity: Arity::at_least(2),
+        params: &["a", "b"],
+        doc: "Returns the largest of two or more ints.",
+        function: momonga_max,
+    },
+    Builtin {
+        name: "pow",
+        arity: Arity::exact(2),
+        params: &["base", "exponent"],
+        doc: "Returns an int raised to the power of another, which must not be negative.",
+        function: momonga_pow,
+    },
+    Builtin {
+        name: "sqrt",
+        arity: Arity::exact(1),
+        params: &["n"],
+        doc: "Returns the square root of an int rounded down.",
+        function: momonga_sqrt,
+    },
+    Builtin {
+        name: "gcd",
+        arity: Arity::exact(2),
+        params: &["a", "b"],
+        doc: "Returns the greatest common divisor of two ints.",
+        function: momonga_gcd,
+    },
+];
+
+pub fn builtin(name: &str) -> Option<&'static Builtin> {
+    BUILTINS.iter().find(|builtin| builtin.name == name)
+}
+
 pub fn new_builtins<'a>() -> Store<'a> {
     let mut builtins = HashMap::new();
-    builtins.insert("len", Rc::new(RefCell::new(Value::Builtin(1, momonga_len))));
-    builtins.insert(
-        "push",
-        Rc::new(RefCell::new(Value::Builtin(2, momonga_push))),
-    );
-    builtins.insert("pop", Rc::new(RefCell::new(Value::Builtin(1, momonga_pop))));
-    builtins.insert(
-        "print",
-        Rc::new(RefCell::new(Value::Builtin(1, momonga_print))),
-    );
+    for builtin in &BUILTINS {
+        let value = Value::Builtin(builtin.arity, builtin.function);
+        builtins.insert(builtin.name, Rc::new(RefCell::new(value)));
+    }
     builtins
 }
 
 type BuiltinArgs<'a> = Vec<Rc<RefCell<Value<'a>>>>;
 type BuiltinReturn<'a> = Result<Rc<RefCell<Value<'a>>>, EvalError>;
+type BuiltinFn<'a> = fn(BuiltinArgs<'a>) -> BuiltinReturn<'a>;
 
 pub fn momonga_len(args: BuiltinArgs) -> BuiltinReturn {
     match *args[0].borrow() {
@@ -82,7 +241,7 @@ pub fn momonga_len(args: BuiltinArgs) -> BuiltinReturn {
 pub fn momonga_push(args: BuiltinArgs) -> BuiltinReturn {
     match *args[0].borrow() {
         Value::Array(Array(ref vals)) => {
-            vals.borrow_mut().push(Rc::clone(&args[1]));
+            vals.borrow_mut().extend(args[1..].iter().map(Rc::clone));
             Ok(Rc::new(RefCell::new(Value::Array(Array(vals.clone())))))
         }
         _ => Err(EvalError::Type),
@@ -100,6 +259,101 @@ pub fn momonga_pop(args: BuiltinArgs) -> BuiltinReturn {
 }
 
 pub fn momonga_print(args: BuiltinArgs) -> BuiltinReturn {
-    emit_output_event(OutputEvent::Stdout, &(*args[0].borrow()).to_string());
+    let vals: Vec<String> = args.iter().map(|arg| arg.borrow().to_string()).collect();
+    stdio::write_line(&vals.join(" "));
     Ok(Rc::new(RefCell::new(Value::None)))
 }
+
+pub fn momonga_input(_args: BuiltinArgs) -> BuiltinReturn {
+    match stdio::read_line() {
+        Some(line) => Ok(Rc::new(RefCell::new(Value::String(RefCell::new(line))))),
+        None => Ok(Rc::new(RefCell::new(Value::None))), // No more input
+    }
+}
+
+pub fn momonga_abs(args: BuiltinArgs) -> BuiltinReturn {
+    match *args[0].borrow() {
+        Value::Int(int) => match int.checked_abs() {
+            Some(abs) => Ok(Rc::new(RefCell::new(Value::Int(abs)))),
+            None => Err(EvalError::OutOfRange), // The minimum has no positive counterpart
+        },
+        _ => Err(EvalError::Type),
+    }
+}
+
+pub fn momonga_min(args: BuiltinArgs) -> BuiltinReturn {
+    let ints = ints(&args)?;
+    Ok(Rc::new(RefCell::new(Value::Int(
+        *ints.iter().min().unwrap(),
+    ))))
+}
+
+pub fn momonga_max(args: BuiltinArgs) -> BuiltinReturn {
+    let ints = ints(&args)?;
+    Ok(Rc::new(RefCell::new(Value::Int(
+        *ints.iter().max().unwrap(),
+    ))))
+}
+
+pub fn momonga_pow(args: BuiltinArgs) -> BuiltinReturn {
+    match *ints(&args)? {
+        [base, exp] => Ok(Rc::new(RefCell::new(Value::Int(int_pow(base, exp)?)))),
+        _ => unreachable!(),
+    }
+}
+
+// Square root rounded down
+pub fn momonga_sqrt(args: BuiltinArgs) -> BuiltinReturn {
+    match *args[0].borrow() {
+        Value::Int(int) if int < 0 => Err(EvalError::OutOfRange),
+        Value::Int(int) if int < 2 => Ok(Rc::new(RefCell::new(Value::Int(int)))),
+        Value::Int(int) => {
+            // Newton's method from above, which stops at the floor
+            let mut root = int / 2 + 1;
+            let mut next = (root + int / root) / 2;
+            while next < root {
+                root = next;
+                next = (root + int / root) / 2;
+            }
+            Ok(Rc::new(RefCell::new(Value::Int(root))))
+        }
+        _ => Err(EvalError::Type),
+    }
+}
+
+// Greatest common divisor of the absolute values, which is 0 only for 0 and 0
+pub fn momonga_gcd(args: BuiltinArgs) -> BuiltinReturn {
+    match *ints(&args)? {
+        [a, b] => {
+            let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+            while b != 0 {
+                (a, b) = (b, a % b);
+            }
+            match i64::try_from(a) {
+                Ok(gcd) => Ok(Rc::new(RefCell::new(Value::Int(gcd)))),
+                Err(_) => Err(EvalError::OutOfRange), // 2 to the 63rd, of the minimum and 0
+            }
+        }
+        _ => unreachable!(),
+    }
+}
+
+// `base ** exp`, which is out of range for a negative exponent as well as on overflow
+pub fn int_pow(base: i64, exp: i64) -> Result<i64, EvalError> {
+    // Only -1, 0 and 1 have powers beyond the 64th in range, which depend on the parity alone
+    let exp = if exp > 64 { 64 + exp % 2 } else { exp };
+    u32::try_from(exp)
+        .ok()
+        .and_then(|exp| base.checked_pow(exp))
+        .ok_or(EvalError::OutOfRange)
+}
+
+// Arguments of a builtin taking only ints
+fn ints(args: &BuiltinArgs) -> Result<Vec<i64>, EvalError> {
+    args.iter()
+        .map(|arg| match *arg.borrow() {
+            Value::Int(int) => Ok(int),
+            _ => Err(EvalError::Type),
+        })
+        .collect()
+}
@@ -0,0 +1,320 @@
+use std::{cell::RefCell, collections::BTreeSet, rc::Rc};
+
+use serde::Serialize;
+
+use crate::ast::{Span, Stmt};
+use crate::data::Value;
+use crate::env::Env;
+use crate::error::{EvalError, ParseError};
+use crate::eval::{eval, with_step_limit, with_stmt_hook, StmtHook};
+use crate::parser::parse;
+use crate::stdio;
+
+// Every step is recorded with all the variables in scope, so the limit also bounds the memory used
+pub const DEFAULT_DEBUG_STEP_LIMIT: u64 = 10_000;
+
+// State of the program right before a statement is executed
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Stop {
+    pub line: usize, // 1-based
+    #[serde(skip)]
+    pub span: Span,
+    pub call_stack: Vec<String>,
+    pub scopes: Vec<Vec<Variable>>, // Innermost scope first
+    #[serde(skip)]
+    pub stdout_len: usize, // Number of lines printed before this stop
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Variable {
+    pub name: String,
+    pub value: String,
+}
+
+// The whole execution is recorded first and then replayed, since the evaluator cannot be suspended
+pub struct Recording {
+    pub stops: Vec<Stop>,
+    pub stdout: Vec<String>,
+    pub result: Result<(), EvalError>,
+}
+
+pub fn record(source: &str, step_limit: u64) -> Result<Recording, ParseError> {
+    let program = parse(source)?;
+    let stops = Rc::new(RefCell::new(vec![]));
+    let recorder = Recorder {
+        line_starts: line_starts(source),
+        stops: Rc::clone(&stops),
+    };
+
+    let (result, stdout) = stdio::with_captured_stdio("", || {
+        with_stmt_hook(Box::new(recorder), || {
+            with_step_limit(step_limit, || {
+                eval(&program, Rc::new(RefCell::new(Env::new_with_builtins()))).map(|_| ())
+            })
+        })
+    });
+    let stops = stops.take();
+    Ok(Recording {
+        stops,
+        stdout,
+        result,
+    })
+}
+
+struct Recorder {
+    line_starts: Vec<usize>,
+    stops: Rc<RefCell<Vec<Stop>>>,
+}
+
+impl StmtHook for Recorder {
+    fn before_stmt(&mut self, stmt: &Stmt, env: &Env, call_stack: &[String]) {
+        let span = stmt.span();
+        self.stops.borrow_mut().push(Stop {
+            line: self
+                .line_starts
+                .partition_point(|&start| start <= span.start),
+            span,
+            call_stack: call_stack.to_vec(),
+            scopes: scopes(env),
+            stdout_len: stdio::captured_len(),
+        });
+    }
+}
+
+pub fn line_starts(source: &str) -> Vec<usize> {
+    let mut line_starts = vec![0];
+    line_starts.extend(source.match_indices('\n').map(|(i, _)| i + 1));
+    line_starts
+}
+
+fn scopes(env: &Env) -> Vec<Vec<Variable>> {
+    let mut scopes = vec![variables(env)];
+    let mut outer = env.outer();
+    while let Some(env) = outer {
+        scopes.push(variables(&env.borrow()));
+        outer = env.borrow().outer();
+    }
+    scopes
+}
+
+fn variables(env: &Env) -> Vec<Variable> {
+    env.vars()
+        .into_iter()
+        .map(|(name, val)| Variable {
+            name: name.to_string(),
+            value: render(&val.borrow()),
+        })
+        .collect()
+}
+
+pub fn render(value: &Value) -> String {
+    match value {
+        Value::Func { .. } => "func".to_string(),
+        Value::Builtin(..) => "builtin".to_string(),
+        Value::String(string) => format!("\"{}\"", string.borrow()),
+        value => value.to_string(),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Position {
+    NotStarted,
+    At(usize), // Index of the stop
+    Finished,
+}
+
+pub struct Debugger {
+    recording: Recording,
+    step_limit: u64,
+    breakpoints: BTreeSet<usize>,
+    position: Position,
+}
+
+impl Debugger {
+    pub fn new(source: &str, step_limit: u64) -> Result<Self, ParseError> {
+        Ok(Self {
+            recording: record(source, step_limit)?,
+            step_limit,
+            breakpoints: BTreeSet::new(),
+            position: Position::NotStarted,
+        })
+    }
+
+    pub fn set_breakpoints(&mut self, lines: &[usize]) {
+        self.breakpoints = lines.iter().copied().collect();
+    }
+
+    // Run until the next breakpoint
+    pub fn resume(&mut self) -> Option<&Stop> {
+        let breakpoints = self.breakpoints.clone();
+        self.move_to(|stop, _| breakpoints.contains(&stop.line))
+    }
+
+    // Stop at the next statement, including ones in called functions
+    pub fn step_in(&mut self) -> Option<&Stop> {
+        self.move_to(|_, _| true)
+    }
+
+    // Stop at the next statement without going into called functions
+    pub fn step_over(&mut self) -> Option<&Stop> {
+        self.move_to(|stop, depth| stop.call_stack.len() <= depth)
+    }
+
+    // Stop at the next statement after returning from the current function
+    pub fn step_out(&mut self) -> Option<&Stop> {
+        self.move_to(|stop, depth| stop.call_stack.len() < depth)
+    }
+
+    // Move to the next stop matching `f`, which takes the stop and the current call depth
+    fn move_to(&mut self, f: impl Fn(&Stop, usize) -> bool) -> Option<&Stop> {
+        let (from, depth) = match self.position {
+            Position::NotStarted => (0, usize::MAX), // Any statement is deeper than nothing
+            Position::At(idx) => (idx + 1, self.recording.stops[idx].call_stack.len()),
+            Position::Finished => return None,
+        };
+        let next =
+            (from..self.recording.stops.len()).find(|&idx| f(&self.recording.stops[idx], depth));
+        self.position = match next {
+            Some(idx) => Position::At(idx),
+            None => Position::Finished,
+        };
+        self.current()
+    }
+
+    pub fn current(&self) -> Option<&Stop> {
+        match self.position {
+            Position::At(idx) => Some(&self.recording.stops[idx]),
+            _ => None,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == Position::Finished
+    }
+
+    // Lines printed before the current stop
+    pub fn stdout(&self) -> &[String] {
+        match self.position {
+            Position::NotStarted => &[],
+            Position::At(idx) => &self.recording.stdout[..self.recording.stops[idx].stdout_len],
+            Position::Finished => &self.recording.stdout,
+        }
+    }
+
+    // Error which terminated the program, available once finished
+    pub fn error(&self) -> Option<&EvalError> {
+        match (&self.position, &self.recording.result) {
+            (Position::Finished, Err(eval_err)) => Some(eval_err),
+            _ => None,
+        }
+    }
+
+    // Message of `error`, telling that the program was stopped by the step limit if so
+    pub fn error_message(&self) -> Option<String> {
+        self.error().map(|eval_err| match eval_err {
+            EvalError::StepLimit => format!(
+                "Program too long to debug: only the first {} steps are recorded",
+                self.step_limit
+            ),
+            eval_err => eval_err.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::debugger::*;
+
+    const SRC: &str = r#"func add(a, b) {
+    var sum = a + b;
+    return sum;
+}
+var x = 1;
+var y = add(x, 2);
+print(y);
+"#;
+
+    fn lines(debugger: &mut Debugger, f: fn(&mut Debugger) -> Option<&Stop>) -> Vec<usize> {
+        let mut lines = vec![];
+        while let Some(stop) = f(debugger) {
+            lines.push(stop.line);
+        }
+        lines
+    }
+
+    #[test]
+    fn steps_follow_function_calls() {
+        let mut debugger = Debugger::new(SRC, DEFAULT_DEBUG_STEP_LIMIT).unwrap();
+        assert_eq!(
+            lines(&mut debugger, Debugger::step_in),
+            vec![1, 5, 6, 2, 3, 7]
+        );
+
+        let mut debugger = Debugger::new(SRC, DEFAULT_DEBUG_STEP_LIMIT).unwrap();
+        assert_eq!(lines(&mut debugger, Debugger::step_over), vec![1, 5, 6, 7]);
+
+        let mut debugger = Debugger::new(SRC, DEFAULT_DEBUG_STEP_LIMIT).unwrap();
+        debugger.set_breakpoints(&[2]);
+        assert_eq!(debugger.resume().map(|stop| stop.line), Some(2));
+        assert_eq!(debugger.step_out().map(|stop| stop.line), Some(7));
+        assert_eq!(debugger.step_out(), None);
+        assert!(debugger.is_finished());
+        assert_eq!(debugger.stdout(), &["3".to_string()]);
+        assert_eq!(debugger.error(), None);
+    }
+
+    #[test]
+    fn variables_are_inspected() {
+        let mut debugger = Debugger::new(SRC, DEFAULT_DEBUG_STEP_LIMIT).unwrap();
+        debugger.set_breakpoints(&[3, 7]);
+
+        let stop = debugger.resume().unwrap();
+        assert_eq!(stop.call_stack, vec!["add".to_string()]);
+        let variable = |name: &str, value: &str| Variable {
+            name: name.to_string(),
+            value: value.to_string(),
+        };
+        assert_eq!(
+            stop.scopes,
+            vec![
+                vec![variable("a", "1"), variable("b", "2"), variable("sum", "3")],
+                vec![variable("add", "func"), variable("x", "1")],
+            ]
+        );
+
+        let stop = debugger.resume().unwrap();
+        assert_eq!(
+            stop.scopes,
+            vec![vec![
+                variable("add", "func"),
+                variable("x", "1"),
+                variable("y", "3")
+            ]]
+        );
+        assert!(debugger.stdout().is_empty());
+    }
+
+    #[test]
+    fn runtime_error_is_reported_at_the_end() {
+        let src = "print(1);\nprint(1 + true);\n";
+        let mut debugger = Debugger::new(src, DEFAULT_DEBUG_STEP_LIMIT).unwrap();
+        assert_eq!(lines(&mut debugger, Debugger::step_in), vec![1, 2]);
+        assert_eq!(debugger.stdout(), &["1".to_string()]);
+        assert_eq!(debugger.error(), Some(&EvalError::Type));
+        assert_eq!(debugger.error_message(), Some("Type error".to_string()));
+    }
+
+    #[test]
+    fn step_limit_is_reported_as_too_long() {
+        let src = "var i = 0;\nwhile (true) {\n    i += 1;\n}\n";
+        let mut debugger = Debugger::new(src, 10).unwrap();
+        debugger.set_breakpoints(&[3]);
+        assert_eq!(debugger.resume().map(|stop| stop.line), Some(3)); // Steps before the limit are kept
+        while debugger.resume().is_some() {}
+        assert_eq!(debugger.error(), Some(&EvalError::StepLimit));
+        assert_eq!(
+            debugger.error_message(),
+            Some("Program too long to debug: only the first 10 steps are recorded".to_string())
+        );
+    }
+}
@@ -1,6 +1,11 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use crate::{
+    ast::TypeAnnotation,
     data::{new_builtins, Value},
     error::EvalError,
 };
@@ -10,6 +15,8 @@ pub type Store<'a> = HashMap<&'a str, Rc<RefCell<Value<'a>>>>;
 #[derive(Debug)]
 pub struct Env<'a> {
     store: Store<'a>,
+    annotations: HashMap<&'a str, TypeAnnotation>, // Of variables declared with a type
+    consts: HashSet<&'a str>,                      // Variables declared with `const`
     outer: Option<Rc<RefCell<Env<'a>>>>,
 }
 
@@ -17,12 +24,16 @@ impl<'a> Env<'a> {
     pub fn new(outer: Option<Rc<RefCell<Env<'a>>>>) -> Self {
         Self {
             store: HashMap::new(),
+            annotations: HashMap::new(),
+            consts: HashSet::new(),
             outer,
         }
     }
     pub fn new_with_builtins() -> Self {
         Self {
             store: new_builtins(),
+            annotations: HashMap::new(),
+            consts: HashSet::new(),
             outer: None,
         }
     }
@@ -31,17 +42,43 @@ impl<'a> Env<'a> {
         self.store.insert(name, value);
     }
 
-    pub fn set_var(&mut self, name: &'a str, value: Rc<RefCell<Value<'a>>>) {
+    // Values later assigned to the variable are checked against `annotation`
+    pub fn set_var(
+        &mut self,
+        name: &'a str,
+        value: Rc<RefCell<Value<'a>>>,
+        annotation: Option<TypeAnnotation>,
+    ) {
+        match annotation {
+            Some(annotation) => self.annotations.insert(name, annotation),
+            None => self.annotations.remove(name),
+        };
+        self.consts.remove(name);
         self.store.insert(name, value);
     }
 
+    // Same as `set_var`, but the constant cannot be assigned to later
+    pub fn set_const(
+        &mut self,
+        name: &'a str,
+        value: Rc<RefCell<Value<'a>>>,
+        annotation: Option<TypeAnnotation>,
+    ) {
+        self.set_var(name, value, annotation);
+        self.consts.insert(name);
+    }
+
     pub fn set_assign(
         &mut self,
         name: &'a str,
         value: Rc<RefCell<Value<'a>>>,
     ) -> Result<(), EvalError> {
         match self.store.get(name) {
+            Some(_) if self.consts.contains(name) => Err(EvalError::ConstAssign),
             Some(_) => {
+                value
+                    .borrow()
+                    .check_annotation(self.annotations.get(name).copied())?;
                 self.store.insert(name, value);
                 Ok(())
             }
@@ -57,6 +94,22 @@ impl<'a> Env<'a> {
         }
     }
 
+    // Variables in this scope ordered by name, excluding builtins
+    pub fn vars(&self) -> Vec<(&'a str, Rc<RefCell<Value<'a>>>)> {
+        let mut vars: Vec<_> = self
+            .store
+            .iter()
+            .filter(|(_, val)| !matches!(*val.borrow(), Value::Builtin(..)))
+            .map(|(name, val)| (*name, Rc::clone(val)))
+            .collect();
+        vars.sort_by_key(|(name, _)| *name);
+        vars
+    }
+
+    pub fn outer(&self) -> Option<Rc<RefCell<Env<'a>>>> {
+        self.outer.as_ref().map(Rc::clone)
+    }
+
     pub fn get(&self, name: &str) -> Result<Rc<RefCell<Value<'a>>>, EvalError> {
         match self.store.get(name) {
             Some(val) => Ok(Rc::clone(val)),
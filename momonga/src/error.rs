@@ -1,5 +1,7 @@
 use core::fmt;
 
+use crate::data::Arity;
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     Parser(ParseError),
@@ -31,6 +33,7 @@ impl From<EvalError> for Error {
 pub enum ParseError {
     PestParser,
     BuildAst,
+    DuplicateCase(String), // Source of the literal of a `case` equal to an earlier one
 }
 
 impl fmt::Display for ParseError {
@@ -38,17 +41,28 @@ impl fmt::Display for ParseError {
         match self {
             ParseError::PestParser => write!(f, "Syntax error"),
             ParseError::BuildAst => write!(f, "Syntax error"),
+            ParseError::DuplicateCase(literal) => {
+                write!(f, "Syntax error: duplicate case `{}`", literal)
+            }
         }
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum EvalError {
-    Argument,
+    // Call of a function with a number of arguments its arity does not accept
+    Argument {
+        name: String,
+        arity: Arity,
+        count: usize,
+    },
+    CallDepthLimit,
+    ConstAssign,
     Index,
     InvalidExpression,
     Name,
     OutOfRange,
+    StepLimit,
     Type,
     ZeroDivision,
 }
@@ -56,11 +70,16 @@ pub enum EvalError {
 impl fmt::Display for EvalError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            EvalError::Argument => write!(f, "Argument error"),
+            EvalError::Argument { name, arity, count } => {
+                write!(f, "`{}` expects {}, got {}", name, arity, count)
+            }
+            EvalError::CallDepthLimit => write!(f, "Call depth limit error"),
+            EvalError::ConstAssign => write!(f, "Constant assignment error"),
             EvalError::Index => write!(f, "Index error"),
             EvalError::InvalidExpression => write!(f, "Invalid expression error"),
             EvalError::Name => write!(f, "Name error"),
             EvalError::OutOfRange => write!(f, "Out of range error"),
+            EvalError::StepLimit => write!(f, "Step limit error"),
             EvalError::Type => write!(f, "Type error"),
             EvalError::ZeroDivision => write!(f, "Zero division error"),
         }
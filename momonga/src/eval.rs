@@ -1,4 +1,4 @@
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 use crate::ast::*;
@@ -11,14 +11,70 @@ type EvalExprResult<'a> = Result<Rc<RefCell<Value<'a>>>, JumpStmt<'a>>;
 
 #[derive(Debug, PartialEq)]
 pub enum JumpStmt<'a> {
-    Continue,
-    Break,
+    Continue(Option<&'a str>), // With the label of the loop to continue, if any
+    Break(Option<&'a str>),
     Return(Rc<RefCell<Value<'a>>>),
     Error(EvalError),
 }
 
 const MAX_ABS_INT: u64 = i64::MIN.unsigned_abs(); // TODO: Reconsider how to handle value overflow
 
+// Observer called before each statement is executed, e.g. by a debugger
+pub trait StmtHook {
+    fn before_stmt(&mut self, stmt: &Stmt, env: &Env, call_stack: &[String]);
+}
+
+thread_local! {
+    static STEPS_LEFT: Cell<Option<u64>> = const { Cell::new(None) }; // No limit by default
+    static CALL_DEPTH_LIMIT: Cell<Option<usize>> = const { Cell::new(None) };
+    static STMT_HOOK: RefCell<Option<Box<dyn StmtHook>>> = const { RefCell::new(None) };
+    static CALL_STACK: RefCell<Vec<String>> = const { RefCell::new(vec![]) }; // Names of the functions being called
+}
+
+pub fn with_stmt_hook<R>(hook: Box<dyn StmtHook>, f: impl FnOnce() -> R) -> R {
+    STMT_HOOK.with(|stmt_hook| *stmt_hook.borrow_mut() = Some(hook));
+    let res = f();
+    STMT_HOOK.with(|stmt_hook| *stmt_hook.borrow_mut() = None);
+    CALL_STACK.with(|call_stack| call_stack.borrow_mut().clear()); // Left as is when an error occurs
+    res
+}
+
+fn call_hook(stmt: &Stmt, env: &Env) {
+    STMT_HOOK.with(|stmt_hook| {
+        if let Some(hook) = stmt_hook.borrow_mut().as_mut() {
+            CALL_STACK.with(|call_stack| hook.before_stmt(stmt, env, &call_stack.borrow()));
+        }
+    });
+}
+
+// Limit the number of statements and loop iterations `eval` may execute
+pub fn with_step_limit<R>(limit: u64, f: impl FnOnce() -> R) -> R {
+    STEPS_LEFT.with(|steps| steps.set(Some(limit)));
+    let res = f();
+    STEPS_LEFT.with(|steps| steps.set(None));
+    res
+}
+
+// Calls nested deeper than the limit fail instead of overflowing the native stack, which aborts the
+// whole process
+pub fn with_call_depth_limit<R>(limit: usize, f: impl FnOnce() -> R) -> R {
+    CALL_DEPTH_LIMIT.with(|depth| depth.set(Some(limit)));
+    let res = f();
+    CALL_DEPTH_LIMIT.with(|depth| depth.set(None));
+    res
+}
+
+fn step<'a>() -> Result<(), JumpStmt<'a>> {
+    STEPS_LEFT.with(|steps| match steps.get() {
+        Some(0) => Err(JumpStmt::Error(EvalError::StepLimit)),
+        Some(left) => {
+            steps.set(Some(left - 1));
+            Ok(())
+        }
+        None => Ok(()),
+    })
+}
+
 pub fn eval<'a>(
     program: &'a Program,
     env: Rc<RefCell<Env<'a>>>,
@@ -34,30 +90,38 @@ fn eval_block_stmt<'a>(block_stmt: &'a BlockStmt, env: Rc<RefCell<Env<'a>>>) ->
     let mut result = Ok(None);
 
     for stmt in block_stmt {
+        step()?;
+        call_hook(stmt, &env.borrow());
         result = match stmt {
-            Stmt::BlockStmt(block_stmt) => {
+            Stmt::BlockStmt(block_stmt, _) => {
                 let env_block = Rc::new(RefCell::new(Env::new(Some(Rc::clone(&env)))));
                 eval_block_stmt(block_stmt, Rc::clone(&env_block))
-            },
-            Stmt::FuncDecl(func_decl) => eval_func_decl(func_decl, Rc::clone(&env)),
-            Stmt::IfStmt(if_stmt) => eval_if_stmt(if_stmt, Rc::clone(&env)),
-            Stmt::ForStmt(for_stmt) => eval_for_stmt(for_stmt, Rc::clone(&env)),
-            Stmt::WhileStmt(while_stmt) => eval_while_stmt(while_stmt, Rc::clone(&env)),
-            Stmt::VarStmt(var_stmt) => eval_var_stmt(var_stmt, Rc::clone(&env)),
-            Stmt::ExprStmt(expr_stmt) => eval_expr_stmt(expr_stmt, Rc::clone(&env)),
-            Stmt::ContinueStmt => Err(JumpStmt::Continue),
-            Stmt::BreakStmt => Err(JumpStmt::Break),
-            Stmt::ReturnStmt(return_stmt) => {
+            }
+            Stmt::FuncDecl(func_decl, _) => eval_func_decl(func_decl, Rc::clone(&env)),
+            Stmt::IfStmt(if_stmt, _) => eval_if_stmt(if_stmt, Rc::clone(&env)),
+            Stmt::ForStmt(for_stmt, _) => eval_for_stmt(for_stmt, Rc::clone(&env)),
+            Stmt::WhileStmt(while_stmt, _) => eval_while_stmt(while_stmt, Rc::clone(&env)),
+            Stmt::DoWhileStmt(do_while_stmt, _) => {
+                eval_do_while_stmt(do_while_stmt, Rc::clone(&env))
+            }
+            Stmt::SwitchStmt(switch_stmt, _) => eval_switch_stmt(switch_stmt, Rc::clone(&env)),
+            Stmt::VarStmt(var_stmt, _) => eval_var_stmt(var_stmt, Rc::clone(&env)),
+            Stmt::ExprStmt(expr_stmt, _) => eval_expr_stmt(expr_stmt, Rc::clone(&env)),
+            Stmt::ContinueStmt(label, _) => Err(JumpStmt::Continue(label_name(label))),
+            Stmt::BreakStmt(label, _) => Err(JumpStmt::Break(label_name(label))),
+            Stmt::ReturnStmt(return_stmt, _) => {
                 let ReturnStmt { expr } = return_stmt;
                 match expr {
                     Some(expr) => Err(JumpStmt::Return(eval_expr(expr, Rc::clone(&env))?)),
                     None => Err(JumpStmt::Return(Rc::new(RefCell::new(Value::None)))),
                 }
             }
+            Stmt::Unreachable(..) => Ok(None), // A jump statement always comes first
+            Stmt::Error(_) => unreachable!(),
         };
 
-        if let Err(JumpStmt::Continue)
-        | Err(JumpStmt::Break)
+        if let Err(JumpStmt::Continue(_))
+        | Err(JumpStmt::Break(_))
         | Err(JumpStmt::Return(_))
         | Err(JumpStmt::Error(_)) = &result
         {
@@ -72,14 +136,20 @@ fn eval_func_decl<'a>(func_decl: &'a FuncDecl, env: Rc<RefCell<Env<'a>>>) -> Eva
     let FuncDecl {
         ident_func,
         ident_param,
+        annotation_param,
+        default_param,
+        annotation_return,
         block,
     } = func_decl;
-    let Ident { name } = ident_func;
+    let Ident { name, .. } = ident_func;
 
     env.borrow_mut().set(
         name,
         Rc::new(RefCell::new(Value::Func {
             params: ident_param,
+            annotation_param,
+            default_param,
+            annotation_return: *annotation_return,
             block,
         })),
     );
@@ -124,6 +194,7 @@ fn eval_if_stmt_else_clause<'a>(
 
 fn eval_for_stmt<'a>(for_stmt: &'a ForStmt, env: Rc<RefCell<Env<'a>>>) -> EvalStmtResult<'a> {
     let ForStmt {
+        label,
         init,
         cond,
         afterthought,
@@ -134,15 +205,16 @@ fn eval_for_stmt<'a>(for_stmt: &'a ForStmt, env: Rc<RefCell<Env<'a>>>) -> EvalSt
     match init {
         Some(ForStmtInit::Var(var_stmt)) => eval_var_stmt(var_stmt, Rc::clone(&env_block))?,
         Some(ForStmtInit::Expr(expr_stmt)) => eval_expr_stmt(expr_stmt, env)?,
-        _ => todo!(), // TODO: Define how to handle this case
+        None => None, // Nothing to initialize
     };
 
     let mut result = Ok(None);
     loop {
+        step()?;
         let cond = match cond {
             Some(cond) => match *eval_expr(cond, Rc::clone(&env_block))?.borrow() {
                 Value::Bool(bool) => bool,
-                _ => todo!(), // TODO: Define how to handle this case
+                _ => return Err(JumpStmt::Error(EvalError::Type)),
             },
             None => false,
         };
@@ -152,15 +224,16 @@ fn eval_for_stmt<'a>(for_stmt: &'a ForStmt, env: Rc<RefCell<Env<'a>>>) -> EvalSt
         }
 
         result = match eval_block_stmt(block, Rc::clone(&env_block)) {
-            Err(JumpStmt::Continue) => {
+            Err(JumpStmt::Continue(target)) if targets(target, label) => {
                 eval_for_stmt_afterthought(afterthought, Rc::clone(&env_block))?;
                 result = Ok(None);
                 continue;
             }
-            Err(JumpStmt::Break) => {
+            Err(JumpStmt::Break(target)) if targets(target, label) => {
                 result = Ok(None);
                 break;
             }
+            Err(jump) => return Err(jump), // Out of an outer loop or the function
             default => default,
         };
 
@@ -180,47 +253,130 @@ fn eval_for_stmt_afterthought<'a>(
 }
 
 fn eval_while_stmt<'a>(while_stmt: &'a WhileStmt, env: Rc<RefCell<Env<'a>>>) -> EvalStmtResult<'a> {
-    let WhileStmt { cond, block } = while_stmt;
+    let WhileStmt { label, cond, block } = while_stmt;
 
     let mut result = Ok(None);
     let env_block = Rc::new(RefCell::new(Env::new(Some(Rc::clone(&env)))));
 
     loop {
+        step()?;
         let cond = match *eval_expr(cond, Rc::clone(&env_block))?.borrow() {
             Value::Bool(bool) => bool,
             _ => return Err(JumpStmt::Error(EvalError::Type)),
         };
 
-        if !cond { break; }
+        if !cond {
+            break;
+        }
 
         result = match eval_block_stmt(block, Rc::clone(&env_block)) {
-            Err(JumpStmt::Continue) => {
+            Err(JumpStmt::Continue(target)) if targets(target, label) => {
                 result = Ok(None);
                 continue;
-            },
-            Err(JumpStmt::Break) => {
+            }
+            Err(JumpStmt::Break(target)) if targets(target, label) => {
                 result = Ok(None);
                 break;
-            },
+            }
+            Err(jump) => return Err(jump), // Out of an outer loop or the function
             default => default,
         }
     }
 
     result
+}
 
+fn eval_do_while_stmt<'a>(
+    do_while_stmt: &'a DoWhileStmt,
+    env: Rc<RefCell<Env<'a>>>,
+) -> EvalStmtResult<'a> {
+    let DoWhileStmt { label, block, cond } = do_while_stmt;
+
+    let mut result;
+    let env_block = Rc::new(RefCell::new(Env::new(Some(Rc::clone(&env)))));
+
+    loop {
+        step()?;
+        result = match eval_block_stmt(block, Rc::clone(&env_block)) {
+            Err(JumpStmt::Continue(target)) if targets(target, label) => Ok(None),
+            Err(JumpStmt::Break(target)) if targets(target, label) => {
+                result = Ok(None);
+                break;
+            }
+            Err(jump) => return Err(jump), // Out of an outer loop or the function
+            default => default,
+        };
+
+        // The variables declared in the block are out of the scope of the condition
+        let cond = match *eval_expr(cond, Rc::clone(&env))?.borrow() {
+            Value::Bool(bool) => bool,
+            _ => return Err(JumpStmt::Error(EvalError::Type)),
+        };
+
+        if !cond {
+            break;
+        }
+    }
+
+    result
+}
+
+fn label_name(label: &Option<Ident>) -> Option<&str> {
+    label.as_ref().map(|label| label.name.as_str())
+}
+
+// Whether a `break` or `continue` with the label `target` is for the loop labelled `label`
+fn targets(target: Option<&str>, label: &Option<Ident>) -> bool {
+    target.is_none() || target == label_name(label)
+}
+
+fn eval_switch_stmt<'a>(
+    switch_stmt: &'a SwitchStmt,
+    env: Rc<RefCell<Env<'a>>>,
+) -> EvalStmtResult<'a> {
+    let SwitchStmt {
+        subject,
+        cases,
+        default,
+    } = switch_stmt;
+
+    let env_block = Rc::new(RefCell::new(Env::new(Some(Rc::clone(&env)))));
+
+    let subject = eval_expr(subject, Rc::clone(&env))?;
+    for SwitchStmtCase { value, block } in cases {
+        let value = eval_expr(value, Rc::clone(&env))?;
+        if equals(&subject.borrow(), &value.borrow())? {
+            return eval_block_stmt(block, env_block);
+        }
+    }
+    match default {
+        Some(block) => eval_block_stmt(block, env_block),
+        None => Ok(None),
+    }
 }
 
 fn eval_var_stmt<'a>(var_stmt: &'a VarStmt, env: Rc<RefCell<Env<'a>>>) -> EvalStmtResult<'a> {
     let VarStmt {
-        ident: Ident { name },
+        is_const,
+        ident: Ident { name, .. },
+        annotation,
         expr,
     } = var_stmt;
+    // A variable declared with a type holds `none` until a value of the type is assigned
     let value = if let Some(expr) = expr {
-        eval_expr(expr, Rc::clone(&env))?
+        let value = eval_expr(expr, Rc::clone(&env))?;
+        if let Err(err) = value.borrow().check_annotation(*annotation) {
+            return Err(JumpStmt::Error(err));
+        }
+        value
     } else {
         Rc::new(RefCell::new(Value::None))
     };
-    env.borrow_mut().set_var(name, value);
+    if *is_const {
+        env.borrow_mut().set_const(name, value, *annotation);
+    } else {
+        env.borrow_mut().set_var(name, value, *annotation);
+    }
 
     Ok(None)
 }
@@ -270,118 +426,43 @@ fn eval_expr<'a>(expr: &'a Expr, env: Rc<RefCell<Env<'a>>>) -> EvalExprResult<'a
                         _ => Err(JumpStmt::Error(EvalError::Type)), // Incorrect operand
                     }
                 }
+                PrefixOpKind::BitNot => {
+                    match *eval_expr(rhs, env)?.borrow() {
+                        Value::Int(int) => Ok(Rc::new(RefCell::new(Value::Int(!int)))),
+                        _ => Err(JumpStmt::Error(EvalError::Type)), // Incorrect operand
+                    }
+                }
             }
         }
         Expr::InfixOp { kind, lhs, rhs } => {
             match kind {
-                InfixOpKind::Add => {
-                    match (
-                        &*eval_expr(lhs, Rc::clone(&env))?.borrow(),
-                        &*eval_expr(rhs, Rc::clone(&env))?.borrow(),
-                    ) {
-                        (Value::Int(lhs), Value::Int(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Int(lhs + rhs))))
-                        }
-                        (Value::String(lhs), Value::String(rhs)) => {
-                            let mut lhs = lhs.borrow_mut();
-                            let rhs = rhs.borrow();
-                            lhs.push_str(&rhs);
-                            Ok(Rc::new(RefCell::new(Value::String(RefCell::new(
-                                lhs.clone(),
-                            )))))
-                        }
-                        _ => Err(JumpStmt::Error(EvalError::Type)),
-                    }
-                }
-                InfixOpKind::Subtract => {
-                    match (
-                        &*eval_expr(lhs, Rc::clone(&env))?.borrow(),
-                        &*eval_expr(rhs, Rc::clone(&env))?.borrow(),
-                    ) {
-                        (Value::Int(lhs), Value::Int(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Int(lhs - rhs))))
-                        }
-                        _ => Err(JumpStmt::Error(EvalError::Type)),
-                    }
-                }
-                InfixOpKind::Multiply => {
-                    match (
-                        &*eval_expr(lhs, Rc::clone(&env))?.borrow(),
-                        &*eval_expr(rhs, Rc::clone(&env))?.borrow(),
-                    ) {
-                        (Value::Int(lhs), Value::Int(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Int(lhs * rhs))))
-                        }
-                        _ => Err(JumpStmt::Error(EvalError::Type)),
-                    }
-                }
-                InfixOpKind::Divide => {
-                    match (
-                        &*eval_expr(lhs, Rc::clone(&env))?.borrow(),
-                        &*eval_expr(rhs, Rc::clone(&env))?.borrow(),
-                    ) {
-                        (Value::Int(lhs), Value::Int(rhs)) => {
-                            if *rhs == 0 {
-                                return Err(JumpStmt::Error(EvalError::ZeroDivision));
-                            }
-                            Ok(Rc::new(RefCell::new(Value::Int(lhs / rhs))))
-                        }
-                        _ => Err(JumpStmt::Error(EvalError::Type)),
-                    }
-                }
-                InfixOpKind::Modulo => {
-                    match (
-                        &*eval_expr(lhs, Rc::clone(&env))?.borrow(),
-                        &*eval_expr(rhs, Rc::clone(&env))?.borrow(),
-                    ) {
-                        (Value::Int(lhs), Value::Int(rhs)) => {
-                            if *rhs == 0 {
-                                return Err(JumpStmt::Error(EvalError::ZeroDivision));
-                            }
-                            Ok(Rc::new(RefCell::new(Value::Int(lhs % rhs))))
-                        }
-                        _ => Err(JumpStmt::Error(EvalError::Type)),
-                    }
+                InfixOpKind::Add
+                | InfixOpKind::Subtract
+                | InfixOpKind::Multiply
+                | InfixOpKind::Divide
+                | InfixOpKind::Modulo
+                | InfixOpKind::Power
+                | InfixOpKind::BitAnd
+                | InfixOpKind::BitOr
+                | InfixOpKind::BitXor
+                | InfixOpKind::ShiftLeft
+                | InfixOpKind::ShiftRight => {
+                    let lhs = eval_expr(lhs, Rc::clone(&env))?;
+                    let rhs = eval_expr(rhs, Rc::clone(&env))?;
+                    let value = eval_arithmetic(kind, &lhs.borrow(), &rhs.borrow());
+                    value
                 }
                 InfixOpKind::Eq => {
-                    match (
-                        &*eval_expr(lhs, Rc::clone(&env))?.borrow(),
-                        &*eval_expr(rhs, Rc::clone(&env))?.borrow(),
-                    ) {
-                        (Value::Bool(lhs), Value::Bool(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs == rhs))))
-                        }
-                        (Value::Int(lhs), Value::Int(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs == rhs))))
-                        }
-                        (Value::String(lhs), Value::String(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs == rhs))))
-                        }
-                        (Value::Array(lhs), Value::Array(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs == rhs))))
-                        }
-                        _ => Err(JumpStmt::Error(EvalError::Type)),
-                    }
+                    let lhs = eval_expr(lhs, Rc::clone(&env))?;
+                    let rhs = eval_expr(rhs, Rc::clone(&env))?;
+                    let value = equals(&lhs.borrow(), &rhs.borrow())?;
+                    Ok(Rc::new(RefCell::new(Value::Bool(value))))
                 }
                 InfixOpKind::NotEq => {
-                    match (
-                        &*eval_expr(lhs, Rc::clone(&env))?.borrow(),
-                        &*eval_expr(rhs, Rc::clone(&env))?.borrow(),
-                    ) {
-                        (Value::Bool(lhs), Value::Bool(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs != rhs))))
-                        }
-                        (Value::Int(lhs), Value::Int(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs != rhs))))
-                        }
-                        (Value::String(lhs), Value::String(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs != rhs))))
-                        }
-                        (Value::Array(lhs), Value::Array(rhs)) => {
-                            Ok(Rc::new(RefCell::new(Value::Bool(lhs != rhs))))
-                        }
-                        _ => Err(JumpStmt::Error(EvalError::Type)),
-                    }
+                    let lhs = eval_expr(lhs, Rc::clone(&env))?;
+                    let rhs = eval_expr(rhs, Rc::clone(&env))?;
+                    let value = !equals(&lhs.borrow(), &rhs.borrow())?;
+                    Ok(Rc::new(RefCell::new(Value::Bool(value))))
                 }
                 InfixOpKind::Gt => {
                     match (
@@ -445,19 +526,12 @@ fn eval_expr<'a>(expr: &'a Expr, env: Rc<RefCell<Env<'a>>>) -> EvalExprResult<'a
                     },
                     _ => Err(JumpStmt::Error(EvalError::Type)),
                 },
-                InfixOpKind::Assign => {
-                    match **lhs {
-                        Expr::Ident(Ident { ref name }) => {
-                            let value = eval_expr(rhs, Rc::clone(&env))?;
-                            if let Err(err) = env.borrow_mut().set_assign(name, Rc::clone(&value)) {
-                                return Err(JumpStmt::Error(err));
-                            };
-                            Ok(value)
-                        }
-                        // Expr::PostfixOp { kind: _, lhs: _ } => todo!(), // TODO: Assign value to an element of array directly
-                        _ => Err(JumpStmt::Error(EvalError::Type)), // TODO: Generate syntax error before reaching here
-                    }
-                }
+                InfixOpKind::Assign
+                | InfixOpKind::AddAssign
+                | InfixOpKind::SubtractAssign
+                | InfixOpKind::MultiplyAssign
+                | InfixOpKind::DivideAssign
+                | InfixOpKind::ModuloAssign => eval_assign(kind, lhs, rhs, env),
             }
         }
         Expr::PostfixOp { kind, lhs } => {
@@ -497,27 +571,81 @@ fn eval_expr<'a>(expr: &'a Expr, env: Rc<RefCell<Env<'a>>>) -> EvalExprResult<'a
                         return Err(JumpStmt::Error(EvalError::Type)); // Operand is not callable
                     };
                     let res = match *eval_ident(ident, Rc::clone(&env))?.borrow() {
-                        Value::Func { params, block } => {
+                        Value::Func {
+                            params,
+                            annotation_param,
+                            default_param,
+                            annotation_return,
+                            block,
+                        } => {
+                            // Incorrect number of arguments, checked before evaluating them like for builtins
+                            let arity = Arity::params(default_param);
+                            if !arity.accepts(args.len()) {
+                                return Err(JumpStmt::Error(EvalError::Argument {
+                                    name: ident.name.clone(),
+                                    arity,
+                                    count: args.len(),
+                                }));
+                            }
+
                             let evaluated_args = args
                                 .iter()
                                 .map(|arg| eval_expr(arg, Rc::clone(&env)))
                                 .collect::<Result<Vec<Rc<RefCell<Value<'a>>>>, JumpStmt>>()?;
                             let env_block = Rc::new(RefCell::new(Env::new(Some(Rc::clone(&env)))));
 
-                            for (ident, val) in params.iter().zip(evaluated_args.iter()) {
-                                env_block.borrow_mut().set_var(&ident.name, Rc::clone(val))
+                            for (i, ((ident, annotation), default)) in params
+                                .iter()
+                                .zip(annotation_param)
+                                .zip(default_param)
+                                .enumerate()
+                            {
+                                // Default values are evaluated at each call, seeing the parameters before them
+                                let val = match (evaluated_args.get(i), default) {
+                                    (Some(val), _) => Rc::clone(val),
+                                    (None, Some(default)) => {
+                                        eval_expr(default, Rc::clone(&env_block))?
+                                    }
+                                    (None, None) => unreachable!(),
+                                };
+                                if let Err(err) = val.borrow().check_annotation(*annotation) {
+                                    return Err(JumpStmt::Error(err));
+                                }
+                                env_block
+                                    .borrow_mut()
+                                    .set_var(&ident.name, val, *annotation);
                             }
 
-                            match eval_block_stmt(block, env_block) {
-                                Ok(_) => Ok(Rc::new(RefCell::new(Value::None))),
-                                Err(JumpStmt::Return(val)) => Ok(val),
-                                Err(default) => Err(default),
+                            let depth = CALL_STACK.with(|call_stack| call_stack.borrow().len());
+                            if CALL_DEPTH_LIMIT
+                                .with(Cell::get)
+                                .is_some_and(|limit| depth >= limit)
+                            {
+                                return Err(JumpStmt::Error(EvalError::CallDepthLimit));
+                            }
+                            CALL_STACK.with(|call_stack| {
+                                call_stack.borrow_mut().push(ident.name.clone())
+                            });
+                            let res = eval_block_stmt(block, env_block);
+                            CALL_STACK.with(|call_stack| call_stack.borrow_mut().pop());
+                            let val = match res {
+                                Ok(_) => Rc::new(RefCell::new(Value::None)),
+                                Err(JumpStmt::Return(val)) => val,
+                                Err(default) => return Err(default),
+                            };
+                            if let Err(err) = val.borrow().check_annotation(annotation_return) {
+                                return Err(JumpStmt::Error(err));
                             }
+                            Ok(val)
                         }
-                        Value::Builtin(args_cnt, builin_func) => {
+                        Value::Builtin(arity, builin_func) => {
                             // Incorrect number of arguments
-                            if args.len() as i64 != args_cnt {
-                                return Err(JumpStmt::Error(EvalError::Argument));
+                            if !arity.accepts(args.len()) {
+                                return Err(JumpStmt::Error(EvalError::Argument {
+                                    name: ident.name.clone(),
+                                    arity,
+                                    count: args.len(),
+                                }));
                             };
 
                             let mut evaluated_args = vec![];
@@ -535,6 +663,138 @@ fn eval_expr<'a>(expr: &'a Expr, env: Rc<RefCell<Env<'a>>>) -> EvalExprResult<'a
                 }
             }
         }
+        Expr::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => {
+            let cond = match *eval_expr(cond, Rc::clone(&env))?.borrow() {
+                Value::Bool(bool) => bool,
+                _ => return Err(JumpStmt::Error(EvalError::Type)), // Condition type must be bool
+            };
+            eval_expr(if cond { then } else { otherwise }, env)
+        }
+    }
+}
+
+// `==`, which is only defined between values of the same type, as are `!=` and the cases of
+// switch statements
+fn equals<'a>(lhs: &Value<'a>, rhs: &Value<'a>) -> Result<bool, JumpStmt<'a>> {
+    match (lhs, rhs) {
+        (Value::Bool(lhs), Value::Bool(rhs)) => Ok(lhs == rhs),
+        (Value::Int(lhs), Value::Int(rhs)) => Ok(lhs == rhs),
+        (Value::String(lhs), Value::String(rhs)) => Ok(lhs == rhs),
+        (Value::Array(lhs), Value::Array(rhs)) => Ok(lhs == rhs),
+        _ => Err(JumpStmt::Error(EvalError::Type)),
+    }
+}
+
+// `+`, `-`, `*`, `/`, `%`, `**` and the bitwise operators, which compound assignments such as `+=` apply
+// as well
+fn eval_arithmetic<'a>(kind: &InfixOpKind, lhs: &Value<'a>, rhs: &Value<'a>) -> EvalExprResult<'a> {
+    // Results beyond 64 bits, including the minimum divided by -1, are out of range
+    let checked = |int: Option<i64>| match int {
+        Some(int) => Ok(Value::Int(int)),
+        None => Err(JumpStmt::Error(EvalError::OutOfRange)),
+    };
+    let value = match (kind, lhs, rhs) {
+        (InfixOpKind::Add, Value::Int(lhs), Value::Int(rhs)) => checked(lhs.checked_add(*rhs))?,
+        // A new string, leaving the operands and the variables sharing them unchanged
+        (InfixOpKind::Add, Value::String(lhs), Value::String(rhs)) => {
+            Value::String(RefCell::new(format!("{}{}", lhs.borrow(), rhs.borrow())))
+        }
+        (InfixOpKind::Subtract, Value::Int(lhs), Value::Int(rhs)) => {
+            checked(lhs.checked_sub(*rhs))?
+        }
+        (InfixOpKind::Multiply, Value::Int(lhs), Value::Int(rhs)) => {
+            checked(lhs.checked_mul(*rhs))?
+        }
+        (InfixOpKind::Divide | InfixOpKind::Modulo, Value::Int(_), Value::Int(0)) => {
+            return Err(JumpStmt::Error(EvalError::ZeroDivision));
+        }
+        (InfixOpKind::Divide, Value::Int(lhs), Value::Int(rhs)) => checked(lhs.checked_div(*rhs))?,
+        (InfixOpKind::Modulo, Value::Int(lhs), Value::Int(rhs)) => checked(lhs.checked_rem(*rhs))?,
+        (InfixOpKind::Power, Value::Int(lhs), Value::Int(rhs)) => match int_pow(*lhs, *rhs) {
+            Ok(int) => Value::Int(int),
+            Err(err) => return Err(JumpStmt::Error(err)),
+        },
+        (InfixOpKind::BitAnd, Value::Int(lhs), Value::Int(rhs)) => Value::Int(lhs & rhs),
+        (InfixOpKind::BitOr, Value::Int(lhs), Value::Int(rhs)) => Value::Int(lhs | rhs),
+        (InfixOpKind::BitXor, Value::Int(lhs), Value::Int(rhs)) => Value::Int(lhs ^ rhs),
+        (InfixOpKind::ShiftLeft | InfixOpKind::ShiftRight, Value::Int(lhs), Value::Int(rhs)) => {
+            // Bits shifted out of the left are lost, and the sign is kept shifting to the right
+            let shifted = match u32::try_from(*rhs) {
+                Ok(rhs) if *kind == InfixOpKind::ShiftLeft => lhs.checked_shl(rhs),
+                Ok(rhs) => lhs.checked_shr(rhs),
+                Err(_) => None,
+            };
+            match shifted {
+                Some(shifted) => Value::Int(shifted),
+                None => return Err(JumpStmt::Error(EvalError::OutOfRange)), // Shift by less than 0 or more than 63
+            }
+        }
+        _ => return Err(JumpStmt::Error(EvalError::Type)),
+    };
+    Ok(Rc::new(RefCell::new(value)))
+}
+
+// `=` or a compound assignment to a variable or an item of an array
+fn eval_assign<'a>(
+    kind: &InfixOpKind,
+    target: &'a Expr,
+    rhs: &'a Expr,
+    env: Rc<RefCell<Env<'a>>>,
+) -> EvalExprResult<'a> {
+    match target {
+        Expr::Ident(ident) => {
+            // The variable is read before the value is evaluated, as in `x = x + value`
+            let current = match kind.compound() {
+                Some(_) => Some(eval_ident(ident, Rc::clone(&env))?),
+                None => None,
+            };
+            let value = eval_expr(rhs, Rc::clone(&env))?;
+            let value = match (kind.compound(), current) {
+                (Some(op), Some(current)) => {
+                    let result = eval_arithmetic(&op, &current.borrow(), &value.borrow());
+                    result?
+                }
+                _ => value,
+            };
+            if let Err(err) = env.borrow_mut().set_assign(&ident.name, Rc::clone(&value)) {
+                return Err(JumpStmt::Error(err));
+            };
+            Ok(value)
+        }
+        Expr::PostfixOp {
+            kind: PostfixOpKind::Index(index),
+            lhs,
+        } => {
+            let array = eval_expr(lhs, Rc::clone(&env))?;
+            let index = eval_expr(index, Rc::clone(&env))?;
+            let value = eval_expr(rhs, Rc::clone(&env))?;
+            // Checked in the same order as indexing
+            let index = match *index.borrow() {
+                Value::Int(index) if index < 0 => return Err(JumpStmt::Error(EvalError::Index)),
+                Value::Int(index) => index as usize,
+                _ => return Err(JumpStmt::Error(EvalError::Type)),
+            };
+            let Value::Array(Array(ref vals)) = *array.borrow() else {
+                return Err(JumpStmt::Error(EvalError::Type));
+            };
+            let Some(current) = vals.borrow().get(index).map(Rc::clone) else {
+                return Err(JumpStmt::Error(EvalError::Index));
+            };
+            let value = match kind.compound() {
+                Some(op) => {
+                    let result = eval_arithmetic(&op, &current.borrow(), &value.borrow());
+                    result?
+                }
+                None => value,
+            };
+            vals.borrow_mut()[index] = Rc::clone(&value);
+            Ok(value)
+        }
+        _ => Err(JumpStmt::Error(EvalError::Type)), // Only variables and items can be assigned
     }
 }
 
@@ -588,7 +848,7 @@ mod tests {
         assert_eq!(
             eval(
                 // {}
-                &vec![Stmt::BlockStmt(vec![])],
+                &vec![Stmt::BlockStmt(vec![], Span::default())],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(None)
@@ -597,13 +857,20 @@ mod tests {
         assert_eq!(
             eval(
                 // func foo() {}
-                &vec![Stmt::FuncDecl(FuncDecl {
-                    ident_func: Ident {
-                        name: "foo".to_string(),
+                &vec![Stmt::FuncDecl(
+                    FuncDecl {
+                        ident_func: Ident {
+                            name: "foo".to_string(),
+                            span: Span::default(),
+                        },
+                        ident_param: vec![],
+                        annotation_param: vec![],
+                        default_param: vec![],
+                        annotation_return: None,
+                        block: vec![],
                     },
-                    ident_param: vec![],
-                    block: vec![],
-                })],
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(None)
@@ -612,15 +879,21 @@ mod tests {
         assert_eq!(
             eval(
                 // if (true) {}
-                &vec![Stmt::IfStmt(IfStmt {
-                    condition: Expr::literal_bool(true),
-                    block: vec![Stmt::IfStmt(IfStmt {
+                &vec![Stmt::IfStmt(
+                    IfStmt {
                         condition: Expr::literal_bool(true),
-                        block: vec![],
+                        block: vec![Stmt::IfStmt(
+                            IfStmt {
+                                condition: Expr::literal_bool(true),
+                                block: vec![],
+                                else_clause: None,
+                            },
+                            Span::default()
+                        )],
                         else_clause: None,
-                    })],
-                    else_clause: None,
-                })],
+                    },
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(None)
@@ -628,15 +901,21 @@ mod tests {
         assert_eq!(
             eval(
                 // if (true) { none; }
-                &vec![Stmt::IfStmt(IfStmt {
-                    condition: Expr::literal_bool(true),
-                    block: vec![Stmt::IfStmt(IfStmt {
+                &vec![Stmt::IfStmt(
+                    IfStmt {
                         condition: Expr::literal_bool(true),
-                        block: vec![Stmt::ExprStmt(Expr::literal_none())],
+                        block: vec![Stmt::IfStmt(
+                            IfStmt {
+                                condition: Expr::literal_bool(true),
+                                block: vec![Stmt::ExprStmt(Expr::literal_none(), Span::default())],
+                                else_clause: None,
+                            },
+                            Span::default()
+                        )],
                         else_clause: None,
-                    })],
-                    else_clause: None,
-                })],
+                    },
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::None))))
@@ -645,35 +924,45 @@ mod tests {
         assert_eq!(
             eval(
                 // for (var i = 0; i < 10; i = i + 1) {}
-                &vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Var(VarStmt {
-                        ident: Ident {
-                            name: "i".to_string(),
-                        },
-                        expr: Some(Expr::literal_int(1)),
-                    })),
-                    cond: Some(Expr::InfixOp {
-                        kind: InfixOpKind::Lt,
-                        lhs: Box::new(Expr::Ident(Ident {
-                            name: "i".to_string()
-                        })),
-                        rhs: Box::new(Expr::literal_int(10))
-                    }),
-                    afterthought: Some(Expr::InfixOp {
-                        kind: InfixOpKind::Assign,
-                        lhs: Box::new(Expr::Ident(Ident {
-                            name: "i".to_string()
+                &vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Var(VarStmt {
+                            is_const: false,
+                            ident: Ident {
+                                name: "i".to_string(),
+                                span: Span::default(),
+                            },
+                            annotation: None,
+                            expr: Some(Expr::literal_int(1)),
                         })),
-                        rhs: Box::new(Expr::InfixOp {
-                            kind: InfixOpKind::Add,
+                        cond: Some(Expr::InfixOp {
+                            kind: InfixOpKind::Lt,
                             lhs: Box::new(Expr::Ident(Ident {
-                                name: "i".to_string()
+                                name: "i".to_string(),
+                                span: Span::default(),
                             })),
-                            rhs: Box::new(Expr::Literal(Literal::Int(1)))
-                        })
-                    }),
-                    block: vec![],
-                })],
+                            rhs: Box::new(Expr::literal_int(10))
+                        }),
+                        afterthought: Some(Expr::InfixOp {
+                            kind: InfixOpKind::Assign,
+                            lhs: Box::new(Expr::Ident(Ident {
+                                name: "i".to_string(),
+                                span: Span::default(),
+                            })),
+                            rhs: Box::new(Expr::InfixOp {
+                                kind: InfixOpKind::Add,
+                                lhs: Box::new(Expr::Ident(Ident {
+                                    name: "i".to_string(),
+                                    span: Span::default(),
+                                })),
+                                rhs: Box::new(Expr::Literal(Literal::Int(1)))
+                            })
+                        }),
+                        block: vec![],
+                    },
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(None)
@@ -681,37 +970,51 @@ mod tests {
         assert_eq!(
             eval(
                 // for (var i = 0; i < 10; i = i + 1) { i; }
-                &vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Var(VarStmt {
-                        ident: Ident {
-                            name: "i".to_string(),
-                        },
-                        expr: Some(Expr::literal_int(1)),
-                    })),
-                    cond: Some(Expr::InfixOp {
-                        kind: InfixOpKind::Lt,
-                        lhs: Box::new(Expr::Ident(Ident {
-                            name: "i".to_string()
+                &vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Var(VarStmt {
+                            is_const: false,
+                            ident: Ident {
+                                name: "i".to_string(),
+                                span: Span::default(),
+                            },
+                            annotation: None,
+                            expr: Some(Expr::literal_int(1)),
                         })),
-                        rhs: Box::new(Expr::literal_int(10))
-                    }),
-                    afterthought: Some(Expr::InfixOp {
-                        kind: InfixOpKind::Assign,
-                        lhs: Box::new(Expr::Ident(Ident {
-                            name: "i".to_string()
-                        })),
-                        rhs: Box::new(Expr::InfixOp {
-                            kind: InfixOpKind::Add,
+                        cond: Some(Expr::InfixOp {
+                            kind: InfixOpKind::Lt,
+                            lhs: Box::new(Expr::Ident(Ident {
+                                name: "i".to_string(),
+                                span: Span::default(),
+                            })),
+                            rhs: Box::new(Expr::literal_int(10))
+                        }),
+                        afterthought: Some(Expr::InfixOp {
+                            kind: InfixOpKind::Assign,
                             lhs: Box::new(Expr::Ident(Ident {
-                                name: "i".to_string()
+                                name: "i".to_string(),
+                                span: Span::default(),
                             })),
-                            rhs: Box::new(Expr::Literal(Literal::Int(1)))
-                        })
-                    }),
-                    block: vec![Stmt::ExprStmt(Expr::Ident(Ident {
-                        name: "i".to_string()
-                    }))],
-                })],
+                            rhs: Box::new(Expr::InfixOp {
+                                kind: InfixOpKind::Add,
+                                lhs: Box::new(Expr::Ident(Ident {
+                                    name: "i".to_string(),
+                                    span: Span::default(),
+                                })),
+                                rhs: Box::new(Expr::Literal(Literal::Int(1)))
+                            })
+                        }),
+                        block: vec![Stmt::ExprStmt(
+                            Expr::Ident(Ident {
+                                name: "i".to_string(),
+                                span: Span::default(),
+                            }),
+                            Span::default()
+                        )],
+                    },
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::Int(9)))))
@@ -720,10 +1023,14 @@ mod tests {
         assert_eq!(
             eval(
                 // while(true) {break;}
-                &vec![Stmt::WhileStmt(WhileStmt {
-                    cond: Expr::literal_bool(true),
-                    block: vec![Stmt::BreakStmt]
-                })],
+                &vec![Stmt::WhileStmt(
+                    WhileStmt {
+                        label: None,
+                        cond: Expr::literal_bool(true),
+                        block: vec![Stmt::BreakStmt(None, Span::default())]
+                    },
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(None)
@@ -732,12 +1039,18 @@ mod tests {
         assert_eq!(
             eval(
                 // var x;
-                &vec![Stmt::VarStmt(VarStmt {
-                    ident: Ident {
-                        name: "x".to_string(),
+                &vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: false,
+                        ident: Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: None,
+                        expr: None,
                     },
-                    expr: None,
-                })],
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(None)
@@ -745,12 +1058,18 @@ mod tests {
         assert_eq!(
             eval(
                 // var x = 1;
-                &vec![Stmt::VarStmt(VarStmt {
-                    ident: Ident {
-                        name: "x".to_string(),
+                &vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: false,
+                        ident: Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: None,
+                        expr: Some(Expr::literal_int(1)),
                     },
-                    expr: Some(Expr::literal_int(1)),
-                })],
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(None)
@@ -764,15 +1083,25 @@ mod tests {
                 // var x; // No initialization
                 // x;
                 &vec![
-                    Stmt::VarStmt(VarStmt {
-                        ident: Ident {
-                            name: "x".to_string(),
+                    Stmt::VarStmt(
+                        VarStmt {
+                            is_const: false,
+                            ident: Ident {
+                                name: "x".to_string(),
+                                span: Span::default(),
+                            },
+                            annotation: None,
+                            expr: None,
                         },
-                        expr: None,
-                    }),
-                    Stmt::ExprStmt(Expr::Ident(Ident {
-                        name: "x".to_string()
-                    }))
+                        Span::default()
+                    ),
+                    Stmt::ExprStmt(
+                        Expr::Ident(Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        }),
+                        Span::default()
+                    )
                 ],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
@@ -783,15 +1112,25 @@ mod tests {
                 // var x = 1;
                 // x;
                 &vec![
-                    Stmt::VarStmt(VarStmt {
-                        ident: Ident {
-                            name: "x".to_string(),
+                    Stmt::VarStmt(
+                        VarStmt {
+                            is_const: false,
+                            ident: Ident {
+                                name: "x".to_string(),
+                                span: Span::default(),
+                            },
+                            annotation: None,
+                            expr: Some(Expr::literal_int(1)),
                         },
-                        expr: Some(Expr::literal_int(1)),
-                    }),
-                    Stmt::ExprStmt(Expr::Ident(Ident {
-                        name: "x".to_string()
-                    }))
+                        Span::default()
+                    ),
+                    Stmt::ExprStmt(
+                        Expr::Ident(Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        }),
+                        Span::default()
+                    )
                 ],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
@@ -804,14 +1143,14 @@ mod tests {
         // Boolean
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_bool(true))], // true
+                &vec![Stmt::ExprStmt(Expr::literal_bool(true), Span::default())], // true
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::Bool(true)))))
         );
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_bool(false))], // false
+                &vec![Stmt::ExprStmt(Expr::literal_bool(false), Span::default())], // false
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::Bool(false)))))
@@ -819,29 +1158,35 @@ mod tests {
         // Integer
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_int(0))], // 0
+                &vec![Stmt::ExprStmt(Expr::literal_int(0), Span::default())], // 0
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::Int(0)))))
         );
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_int(1))], // 1
+                &vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())], // 1
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::Int(1)))))
         );
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_int(9223372036854775807))], // 9223372036854775807
+                &vec![Stmt::ExprStmt(
+                    Expr::literal_int(9223372036854775807),
+                    Span::default()
+                )], // 9223372036854775807
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
-            Ok(Some(Rc::new(RefCell::new(Value::Int(std::i64::MAX)))))
+            Ok(Some(Rc::new(RefCell::new(Value::Int(i64::MAX)))))
         );
         // String
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_string("foo".to_string()))], // "foo"
+                &vec![Stmt::ExprStmt(
+                    Expr::literal_string("foo".to_string()),
+                    Span::default()
+                )], // "foo"
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::String(RefCell::new(
@@ -851,7 +1196,7 @@ mod tests {
         // Array
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_array(vec![]))], // []
+                &vec![Stmt::ExprStmt(Expr::literal_array(vec![]), Span::default())], // []
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::Array(Array(
@@ -860,12 +1205,15 @@ mod tests {
         );
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_array(vec![
-                    // [1, 2, 3]
-                    Expr::literal_int(1),
-                    Expr::literal_int(2),
-                    Expr::literal_int(3),
-                ]))],
+                &vec![Stmt::ExprStmt(
+                    Expr::literal_array(vec![
+                        // [1, 2, 3]
+                        Expr::literal_int(1),
+                        Expr::literal_int(2),
+                        Expr::literal_int(3),
+                    ]),
+                    Span::default()
+                )],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::Array(Array(
@@ -879,7 +1227,7 @@ mod tests {
         // None
         assert_eq!(
             eval(
-                &vec![Stmt::ExprStmt(Expr::literal_none())],
+                &vec![Stmt::ExprStmt(Expr::literal_none(), Span::default())],
                 Rc::new(RefCell::new(Env::new_with_builtins()))
             ),
             Ok(Some(Rc::new(RefCell::new(Value::None))))
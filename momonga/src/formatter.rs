@@ -0,0 +1,743 @@
+use pest::Parser;
+
+use crate::ast::*;
+use crate::error::ParseError;
+use crate::parser::{parse, PestMomongaParser, Rule};
+
+const INDENT: &str = "    ";
+
+pub fn format(source: &str) -> Result<String, ParseError> {
+    let program = parse(source)?;
+    let mut formatter = Formatter::new(source, comments(source)?);
+    formatter.stmts(&program, 0);
+    formatter.comments_before(source.len(), 0);
+    if !formatter.out.is_empty() {
+        formatter.out.push('\n');
+    }
+    Ok(formatter.out)
+}
+
+// Comments are not part of the AST, so they are collected separately and put back by their positions
+fn comments(source: &str) -> Result<Vec<Span>, ParseError> {
+    let trivia_pair = PestMomongaParser::parse(Rule::trivia, source)
+        .map_err(|_| ParseError::PestParser)?
+        .next()
+        .unwrap();
+    Ok(trivia_pair
+        .into_inner()
+        .filter(|pair| pair.as_rule() == Rule::comment)
+        .map(|pair| Span::new(pair.as_span().start(), pair.as_span().end()))
+        .collect())
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    comments: Vec<Span>,
+    next_comment: usize,     // Index of the first comment not yet written
+    last_end: Option<usize>, // End of the last statement, comment or brace written
+    blank_line: bool,        // Whether to put a blank line before the next line
+    out: String,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(source: &'a str, comments: Vec<Span>) -> Self {
+        Self {
+            source,
+            comments,
+            next_comment: 0,
+            last_end: None,
+            blank_line: false,
+            out: String::new(),
+        }
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt], depth: usize) {
+        for stmt in stmts {
+            self.stmt(stmt, depth);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt, depth: usize) {
+        if let Stmt::Unreachable(stmts, _) = stmt {
+            self.stmts(stmts, depth);
+            return;
+        }
+        let span = stmt.span();
+        self.comments_before(span.start, depth);
+        self.blank_line_before(span.start);
+
+        match stmt {
+            Stmt::BlockStmt(block, _) => {
+                self.line_start(depth);
+                self.block(block, span.start, depth);
+            }
+            Stmt::FuncDecl(func_decl, _) => {
+                let open = self.open_brace(span.start);
+                self.comments_before(open, depth);
+                self.line_start(depth);
+                self.out.push_str(&format!("{} ", signature(func_decl)));
+                self.block(&func_decl.block, open, depth);
+            }
+            Stmt::IfStmt(if_stmt, _) => {
+                let open = self.open_brace(span.start);
+                self.comments_before(open, depth);
+                self.line_start(depth);
+                self.if_stmt(if_stmt, open, depth);
+            }
+            Stmt::ForStmt(
+                ForStmt {
+                    label,
+                    init,
+                    cond,
+                    afterthought,
+                    block,
+                },
+                _,
+            ) => {
+                let open = self.open_brace(span.start);
+                self.comments_before(open, depth);
+                self.line_start(depth);
+                let mut header = match init {
+                    Some(ForStmtInit::Var(var_stmt)) => var(var_stmt),
+                    Some(ForStmtInit::Expr(expr)) => self::expr(expr),
+                    None => String::new(),
+                };
+                header.push(';');
+                if let Some(cond) = cond {
+                    header.push_str(&format!(" {}", self::expr(cond)));
+                }
+                header.push(';');
+                if let Some(afterthought) = afterthought {
+                    header.push_str(&format!(" {}", self::expr(afterthought)));
+                }
+                self.out
+                    .push_str(&format!("{}for ({}) ", self::label(label), header));
+                self.block(block, open, depth);
+            }
+            Stmt::WhileStmt(WhileStmt { label, cond, block }, _) => {
+                let open = self.open_brace(span.start);
+                self.comments_before(open, depth);
+                self.line_start(depth);
+                self.out
+                    .push_str(&format!("{}while ({}) ", self::label(label), expr(cond)));
+                self.block(block, open, depth);
+            }
+            Stmt::DoWhileStmt(DoWhileStmt { label, block, cond }, _) => {
+                let open = self.open_brace(span.start);
+                self.comments_before(open, depth);
+                self.line_start(depth);
+                self.out.push_str(&format!("{}do ", self::label(label)));
+                // Comments up to the end stay in the block so that `while` follows `}`
+                self.block_until(block, open, span.end, depth);
+                self.out.push_str(&format!(" while ({});", expr(cond)));
+            }
+            Stmt::SwitchStmt(switch_stmt, _) => {
+                let open = self.open_brace(span.start);
+                self.comments_before(open, depth);
+                self.line_start(depth);
+                self.switch_stmt(switch_stmt, open, depth);
+            }
+            Stmt::VarStmt(var_stmt, _) => {
+                self.simple_stmt(&format!("{};", var(var_stmt)), span, depth)
+            }
+            Stmt::ExprStmt(expr_stmt, _) => {
+                self.simple_stmt(&format!("{};", expr(expr_stmt)), span, depth)
+            }
+            Stmt::ContinueStmt(label, _) => {
+                self.simple_stmt(&format!("continue{};", jump_label(label)), span, depth)
+            }
+            Stmt::BreakStmt(label, _) => {
+                self.simple_stmt(&format!("break{};", jump_label(label)), span, depth)
+            }
+            Stmt::ReturnStmt(ReturnStmt { expr: Some(expr) }, _) => {
+                self.simple_stmt(&format!("return {};", self::expr(expr)), span, depth)
+            }
+            Stmt::ReturnStmt(ReturnStmt { expr: None }, _) => {
+                self.simple_stmt("return;", span, depth)
+            }
+            Stmt::Unreachable(..) | Stmt::Error(_) => unreachable!(),
+        }
+
+        self.last_end = Some(span.end);
+        self.trailing_comments();
+    }
+
+    fn simple_stmt(&mut self, text: &str, span: Span, depth: usize) {
+        self.comments_before(span.end, depth); // Comments inside of the statement are moved above it
+        self.line_start(depth);
+        self.out.push_str(text);
+    }
+
+    // Write `if (...) {...} else ...` whose block starts at the brace `open`
+    fn if_stmt(&mut self, if_stmt: &IfStmt, open: usize, depth: usize) {
+        let IfStmt {
+            condition,
+            block,
+            else_clause,
+        } = if_stmt;
+        self.out.push_str(&format!("if ({}) ", expr(condition)));
+        let close = self.close_brace(open);
+        match else_clause {
+            Some(else_clause) => {
+                // Comments up to the next block stay in this block so that `else` follows `}`
+                let else_open = self.open_brace(close + 1);
+                self.block_until(block, open, else_open, depth);
+                self.out.push_str(" else ");
+                match else_clause {
+                    IfStmtElseClause::IfStmtBlock(block) => self.block(block, else_open, depth),
+                    IfStmtElseClause::IfStmt(if_stmt) => self.if_stmt(if_stmt, else_open, depth),
+                }
+            }
+            None => self.block(block, open, depth),
+        }
+    }
+
+    // Write `switch (...) {...}` whose cases start after the brace `open`
+    fn switch_stmt(&mut self, switch_stmt: &SwitchStmt, open: usize, depth: usize) {
+        let SwitchStmt {
+            subject,
+            cases,
+            default,
+        } = switch_stmt;
+        self.out.push_str(&format!("switch ({}) ", expr(subject)));
+        let close = self.close_brace(open);
+        let has_comments = self
+            .comments
+            .get(self.next_comment)
+            .is_some_and(|comment| comment.start < close);
+        if cases.is_empty() && default.is_none() && !has_comments {
+            self.out.push_str("{}");
+            return;
+        }
+
+        self.out.push('{');
+        self.last_end = Some(open + 1);
+        self.trailing_comments();
+        let clauses = cases
+            .iter()
+            .map(|case| (format!("case {}: ", expr(&case.value)), &case.block))
+            .chain(default.iter().map(|block| ("default: ".to_string(), block)));
+        let mut from = open + 1;
+        for (clause, block) in clauses {
+            // Each case is only found by the brace of its block
+            let block_open = self.open_brace(from);
+            self.comments_before(block_open, depth + 1);
+            self.blank_line_before(block_open);
+            self.line_start(depth + 1);
+            self.out.push_str(&clause);
+            self.block(block, block_open, depth + 1);
+            from = self.close_brace(block_open) + 1;
+            self.trailing_comments();
+        }
+        self.comments_before(close, depth + 1);
+        self.blank_line = false;
+        self.line_start(depth);
+        self.out.push('}');
+        self.last_end = Some(close + 1);
+    }
+
+    // Write `{...}` which starts at the brace `open`
+    fn block(&mut self, block: &BlockStmt, open: usize, depth: usize) {
+        let close = self.close_brace(open);
+        self.block_until(block, open, close, depth)
+    }
+
+    // Write `{...}` including the comments before `until`
+    fn block_until(&mut self, block: &BlockStmt, open: usize, until: usize, depth: usize) {
+        let has_comments = self
+            .comments
+            .get(self.next_comment)
+            .is_some_and(|comment| comment.start < until);
+        if block.is_empty() && !has_comments {
+            self.out.push_str("{}");
+            return;
+        }
+
+        self.out.push('{');
+        self.last_end = Some(open + 1);
+        self.trailing_comments();
+        self.stmts(block, depth + 1);
+        self.comments_before(until, depth + 1);
+        self.blank_line = false;
+        self.line_start(depth);
+        self.out.push('}');
+        self.last_end = Some(self.close_brace(open) + 1);
+    }
+
+    // Write comments starting before `pos` on their own lines
+    fn comments_before(&mut self, pos: usize, depth: usize) {
+        while let Some(&comment) = self.comments.get(self.next_comment) {
+            if comment.start >= pos {
+                break;
+            }
+            self.blank_line_before(comment.start);
+            self.line_start(depth);
+            self.out.push_str(&self.source[comment.start..comment.end]);
+            self.next_comment += 1;
+            self.last_end = Some(comment.end);
+        }
+    }
+
+    // Write comments starting on the same line where the last thing written ends
+    fn trailing_comments(&mut self) {
+        while let (Some(&comment), Some(last_end)) =
+            (self.comments.get(self.next_comment), self.last_end)
+        {
+            if comment.start < last_end || self.source[last_end..comment.start].contains('\n') {
+                break;
+            }
+            self.out.push(' ');
+            self.out.push_str(&self.source[comment.start..comment.end]);
+            self.next_comment += 1;
+            self.last_end = Some(comment.end);
+        }
+    }
+
+    // Keep one blank line where the source has one or more
+    fn blank_line_before(&mut self, pos: usize) {
+        if let Some(last_end) = self.last_end {
+            if last_end <= pos && self.source[last_end..pos].matches('\n').count() > 1 {
+                self.blank_line = true;
+            }
+        }
+    }
+
+    fn line_start(&mut self, depth: usize) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        if self.blank_line && !self.out.is_empty() && !self.out.trim_end().ends_with('{') {
+            self.out.push('\n');
+        }
+        self.blank_line = false;
+        self.out.push_str(&INDENT.repeat(depth));
+    }
+
+    fn open_brace(&self, from: usize) -> usize {
+        self.scan(from, |c| c == '{').unwrap()
+    }
+
+    fn close_brace(&self, open: usize) -> usize {
+        let mut depth = 0;
+        self.scan(open, |c| {
+            match c {
+                '{' => depth += 1,
+                '}' => depth -= 1,
+                _ => (),
+            };
+            depth == 0
+        })
+        .unwrap()
+    }
+
+    // Find the first character from `from` matching `f`, skipping strings and comments
+    fn scan(&self, from: usize, mut f: impl FnMut(char) -> bool) -> Option<usize> {
+        let mut skip_until = from;
+        let mut in_string = false;
+        let mut escaped = false;
+        for (i, c) in self.source[from..]
+            .char_indices()
+            .map(|(i, c)| (from + i, c))
+        {
+            if i < skip_until {
+                continue;
+            }
+            if in_string {
+                match c {
+                    '"' if !escaped => in_string = false,
+                    '\\' => escaped = !escaped,
+                    _ => escaped = false,
+                }
+                continue;
+            }
+            if let Ok(idx) = self
+                .comments
+                .binary_search_by_key(&i, |comment| comment.start)
+            {
+                skip_until = self.comments[idx].end;
+                continue;
+            }
+            if c == '"' {
+                in_string = true;
+                continue;
+            }
+            if f(c) {
+                return Some(i);
+            }
+        }
+        None
+    }
+}
+
+fn var(var_stmt: &VarStmt) -> String {
+    let keyword = if var_stmt.is_const { "const" } else { "var" };
+    let annotation = self::annotation(&var_stmt.annotation);
+    match &var_stmt.expr {
+        Some(expr) => format!(
+            "{} {}{} = {}",
+            keyword,
+            var_stmt.ident.name,
+            annotation,
+            self::expr(expr)
+        ),
+        None => format!("{} {}{}", keyword, var_stmt.ident.name, annotation),
+    }
+}
+
+// `func` keyword, name, parameters with annotations and default values of a function declaration
+pub fn signature(func_decl: &FuncDecl) -> String {
+    let params: Vec<String> = func_decl
+        .ident_param
+        .iter()
+        .zip(&func_decl.annotation_param)
+        .zip(&func_decl.default_param)
+        .map(|((ident, annotation), default)| match default {
+            Some(default) => {
+                let annotation = self::annotation(annotation);
+                format!("{}{} = {}", ident.name, annotation, expr(default))
+            }
+            None => format!("{}{}", ident.name, self::annotation(annotation)),
+        })
+        .collect();
+    format!(
+        "func {}({}){}",
+        func_decl.ident_func.name,
+        params.join(", "),
+        annotation(&func_decl.annotation_return)
+    )
+}
+
+fn label(label: &Option<Ident>) -> String {
+    match label {
+        Some(label) => format!("{}: ", label.name),
+        None => String::new(),
+    }
+}
+
+fn jump_label(label: &Option<Ident>) -> String {
+    match label {
+        Some(label) => format!(" {}", label.name),
+        None => String::new(),
+    }
+}
+
+fn annotation(annotation: &Option<TypeAnnotation>) -> String {
+    match annotation {
+        Some(annotation) => format!(": {}", annotation),
+        None => String::new(),
+    }
+}
+
+// Binding power of each operator, following `PRATT_PARSER`
+const PREC_ASSIGN: u8 = 1;
+const PREC_CONDITIONAL: u8 = 2;
+const PREC_OR: u8 = 3;
+const PREC_AND: u8 = 4;
+const PREC_BIT_OR: u8 = 5;
+const PREC_BIT_XOR: u8 = 6;
+const PREC_BIT_AND: u8 = 7;
+const PREC_COMPARISON: u8 = 8;
+const PREC_SHIFT: u8 = 9;
+const PREC_SUM: u8 = 10;
+const PREC_PRODUCT: u8 = 11;
+const PREC_PREFIX: u8 = 12;
+const PREC_POWER: u8 = 13;
+const PREC_POSTFIX: u8 = 14;
+const PREC_PRIMARY: u8 = 15;
+
+fn prec(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Literal(_) | Expr::Ident(_) => PREC_PRIMARY,
+        Expr::PrefixOp { .. } => PREC_PREFIX,
+        Expr::PostfixOp { .. } => PREC_POSTFIX,
+        Expr::InfixOp { kind, .. } => infix_prec(kind),
+        Expr::Conditional { .. } => PREC_CONDITIONAL,
+    }
+}
+
+fn infix_prec(kind: &InfixOpKind) -> u8 {
+    match kind {
+        InfixOpKind::Assign
+        | InfixOpKind::AddAssign
+        | InfixOpKind::SubtractAssign
+        | InfixOpKind::MultiplyAssign
+        | InfixOpKind::DivideAssign
+        | InfixOpKind::ModuloAssign => PREC_ASSIGN,
+        InfixOpKind::Or => PREC_OR,
+        InfixOpKind::And => PREC_AND,
+        InfixOpKind::BitOr => PREC_BIT_OR,
+        InfixOpKind::BitXor => PREC_BIT_XOR,
+        InfixOpKind::BitAnd => PREC_BIT_AND,
+        InfixOpKind::Eq
+        | InfixOpKind::NotEq
+        | InfixOpKind::Gt
+        | InfixOpKind::Ge
+        | InfixOpKind::Lt
+        | InfixOpKind::Le => PREC_COMPARISON,
+        InfixOpKind::ShiftLeft | InfixOpKind::ShiftRight => PREC_SHIFT,
+        InfixOpKind::Add | InfixOpKind::Subtract => PREC_SUM,
+        InfixOpKind::Multiply | InfixOpKind::Divide | InfixOpKind::Modulo => PREC_PRODUCT,
+        InfixOpKind::Power => PREC_POWER,
+    }
+}
+
+pub fn infix_symbol(kind: &InfixOpKind) -> &'static str {
+    match kind {
+        InfixOpKind::Add => "+",
+        InfixOpKind::Subtract => "-",
+        InfixOpKind::Multiply => "*",
+        InfixOpKind::Divide => "/",
+        InfixOpKind::Modulo => "%",
+        InfixOpKind::Power => "**",
+        InfixOpKind::Eq => "==",
+        InfixOpKind::NotEq => "!=",
+        InfixOpKind::Gt => ">",
+        InfixOpKind::Ge => ">=",
+        InfixOpKind::Lt => "<",
+        InfixOpKind::Le => "<=",
+        InfixOpKind::And => "&&",
+        InfixOpKind::Or => "||",
+        InfixOpKind::BitAnd => "&",
+        InfixOpKind::BitOr => "|",
+        InfixOpKind::BitXor => "^",
+        InfixOpKind::ShiftLeft => "<<",
+        InfixOpKind::ShiftRight => ">>",
+        InfixOpKind::Assign => "=",
+        InfixOpKind::AddAssign => "+=",
+        InfixOpKind::SubtractAssign => "-=",
+        InfixOpKind::MultiplyAssign => "*=",
+        InfixOpKind::DivideAssign => "/=",
+        InfixOpKind::ModuloAssign => "%=",
+    }
+}
+
+// Wrap `expr` in parentheses if it binds weaker than `min_prec`
+fn operand(expr: &Expr, min_prec: u8) -> String {
+    if prec(expr) < min_prec {
+        format!("({})", self::expr(expr))
+    } else {
+        self::expr(expr)
+    }
+}
+
+pub fn expr(expr: &Expr) -> String {
+    match expr {
+        Expr::Literal(literal) => self::literal(literal),
+        Expr::Ident(ident) => ident.name.clone(),
+        Expr::PrefixOp { kind, rhs } => {
+            let symbol = match kind {
+                PrefixOpKind::Pos => "+",
+                PrefixOpKind::Neg => "-",
+                PrefixOpKind::Not => "!",
+                PrefixOpKind::BitNot => "~",
+            };
+            format!("{}{}", symbol, operand(rhs, PREC_PREFIX))
+        }
+        Expr::InfixOp { kind, lhs, rhs } => {
+            let prec = infix_prec(kind);
+            let (lhs_prec, rhs_prec) = match kind {
+                InfixOpKind::Power => (prec + 1, PREC_PREFIX), // As in `2 ** -1`
+                _ if kind.is_assign() => (prec + 1, prec),     // Right associative
+                _ => (prec, prec + 1),
+            };
+            format!(
+                "{} {} {}",
+                operand(lhs, lhs_prec),
+                infix_symbol(kind),
+                operand(rhs, rhs_prec)
+            )
+        }
+        Expr::PostfixOp { kind, lhs } => match kind {
+            PostfixOpKind::Index(index) => {
+                format!("{}[{}]", operand(lhs, PREC_POSTFIX), self::expr(index))
+            }
+            PostfixOpKind::Call(args) => {
+                let args: Vec<String> = args.iter().map(self::expr).collect();
+                format!("{}({})", operand(lhs, PREC_POSTFIX), args.join(", "))
+            }
+        },
+        Expr::Conditional {
+            cond,
+            then,
+            otherwise,
+        } => format!(
+            "{} ? {} : {}",
+            operand(cond, PREC_CONDITIONAL + 1),
+            self::expr(then),
+            operand(otherwise, PREC_CONDITIONAL) // Right associative
+        ),
+    }
+}
+
+fn literal(literal: &Literal) -> String {
+    match literal {
+        Literal::Bool(bool) => bool.to_string(),
+        Literal::Int(int) => int.to_string(),
+        Literal::String(string) => format!("\"{}\"", string),
+        Literal::Array(exprs) => {
+            let exprs: Vec<String> = exprs.iter().map(expr).collect();
+            format!("[{}]", exprs.join(", "))
+        }
+        Literal::None => "none".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::formatter::*;
+
+    #[test]
+    fn source_is_formatted_correctly() {
+        let tests = [
+            ("", ""),
+            ("1;", "1;\n"),
+            (
+                "var x=1;x=x+1*2;",
+                "var x = 1;\nx = x + 1 * 2;\n",
+            ),
+            (
+                "func add(a,b){return a+b;}  add( 1 ,2 ) ;",
+                "func add(a, b) {\n    return a + b;\n}\nadd(1, 2);\n",
+            ),
+            (
+                "func add(a:int,b :int):int{var s:int=a+b;return s;}for(var i:int=0;;){}",
+                "func add(a: int, b: int): int {\n    var s: int = a + b;\n    return s;\n}\nfor (var i: int = 0;;) {}\n",
+            ),
+            (
+                "func f(a,b:int=a*2,c=[ ]){}",
+                "func f(a, b: int = a * 2, c = []) {}\n",
+            ),
+            ("const  x:string=\"a\";", "const x: string = \"a\";\n"),
+            (
+                "if(x){}else if(y){1;}else{ 2; }",
+                "if (x) {} else if (y) {\n    1;\n} else {\n    2;\n}\n",
+            ),
+            (
+                "for(var i=0;i<10;i=i+1){if(i==3){continue;}}",
+                "for (var i = 0; i < 10; i = i + 1) {\n    if (i == 3) {\n        continue;\n    }\n}\n",
+            ),
+            ("for(;;){break;}", "for (;;) {\n    break;\n}\n"),
+            (
+                "outer :for(;;){while(x){continue   outer;}}",
+                "outer: for (;;) {\n    while (x) {\n        continue outer;\n    }\n}\n",
+            ),
+            (
+                "do{x=x+1;}while(x<3) ;\nloop:do {break loop;\n// once\n}\nwhile (true);",
+                "do {\n    x = x + 1;\n} while (x < 3);\nloop: do {\n    break loop;\n    // once\n} while (true);\n",
+            ),
+            (
+                "switch(x){case -1:{print(1);}case \"a\" :{}\n\n// otherwise\ndefault:{}}switch (y) {}",
+                "switch (x) {\n    case -1: {\n        print(1);\n    }\n    case \"a\": {}\n\n    // otherwise\n    default: {}\n}\nswitch (y) {}\n",
+            ),
+            (
+                "func f(){\nreturn;\nprint(1); // never\nprint(2);}",
+                "func f() {\n    return;\n    print(1); // never\n    print(2);\n}\n",
+            ),
+            (
+                "while(true){{var x;}}",
+                "while (true) {\n    {\n        var x;\n    }\n}\n",
+            ),
+            ("[1,[2 ,3],\"a\"][1][0];", "[1, [2, 3], \"a\"][1][0];\n"),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                format(src),
+                Ok(expected.to_string()),
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn parentheses_are_kept_only_where_needed() {
+        let tests = [
+            ("((1 + 2)) * 3;", "(1 + 2) * 3;\n"),
+            ("1 + (2 * 3);", "1 + 2 * 3;\n"),
+            ("1 - (2 - 3);", "1 - (2 - 3);\n"),
+            ("(1 - 2) - 3;", "1 - 2 - 3;\n"),
+            ("x = (y = 1);", "x = y = 1;\n"),
+            ("x += (y -= 1);", "x += y -= 1;\n"),
+            ("(x *= 2) + 1;", "(x *= 2) + 1;\n"),
+            ("-(1 + 2);", "-(1 + 2);\n"),
+            ("!(a && b) || c;", "!(a && b) || c;\n"),
+            ("(a || b) && c;", "(a || b) && c;\n"),
+            ("-a[0];", "-a[0];\n"),
+            (
+                "x = (a || b) ? (c ? 1 : 2) : (d ? 3 : 4);",
+                "x = a || b ? c ? 1 : 2 : d ? 3 : 4;\n",
+            ),
+            ("(a ? b : c) ? d : e;", "(a ? b : c) ? d : e;\n"),
+            ("(a ? b : c) + 1;", "(a ? b : c) + 1;\n"),
+            ("a ? (x = 1) : (y = 2);", "a ? x = 1 : (y = 2);\n"),
+            ("(a & b) == (c | d);", "(a & b) == (c | d);\n"),
+            ("a | (b ^ (c & d));", "a | b ^ c & d;\n"),
+            ("((a | b) ^ c) & d;", "((a | b) ^ c) & d;\n"),
+            ("(1 << 2) + (~x >> (y + 1));", "(1 << 2) + (~x >> y + 1);\n"),
+            ("2 ** (3 ** 2);", "2 ** 3 ** 2;\n"),
+            ("(2 ** 3) ** 2;", "(2 ** 3) ** 2;\n"),
+            (
+                "-(2 ** 2) + (-2) ** 2 + 2 ** (-1) * a[0] ** 2;",
+                "-2 ** 2 + (-2) ** 2 + 2 ** -1 * a[0] ** 2;\n",
+            ),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                format(src),
+                Ok(expected.to_string()),
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn comments_are_preserved() {
+        let tests = [
+            ("// only comment", "// only comment\n"),
+            (
+                "// leading\nvar x = 1; // trailing\n\n\n/* block */ x;",
+                "// leading\nvar x = 1; // trailing\n\n/* block */\nx;\n",
+            ),
+            (
+                "if (true) { // open\n  x = 1;\n  // last\n} // close",
+                "if (true) { // open\n    x = 1;\n    // last\n} // close\n",
+            ),
+            ("func f() {\n// empty\n}", "func f() {\n    // empty\n}\n"),
+            ("x /* inside */ = 1;", "/* inside */\nx = 1;\n"),
+            (
+                "var s = \"// not a comment\";",
+                "var s = \"// not a comment\";\n",
+            ),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                format(src),
+                Ok(expected.to_string()),
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        let src = r#"
+            // Comment
+            func fib(n) { if (n < 2) { return n; } return fib(n - 1) + fib(n - 2); }
+
+            for (var i = 0; i < 10; i = i + 1) { print(fib(i)); /* block */ }
+            "#;
+        let formatted = format(src).unwrap();
+        assert_eq!(format(&formatted), Ok(formatted.clone()));
+        assert_eq!(
+            parse(&formatted).map(without_spans),
+            parse(src).map(without_spans)
+        );
+    }
+
+    #[test]
+    fn syntax_error_is_returned() {
+        assert_eq!(format("var;"), Err(ParseError::PestParser));
+    }
+}
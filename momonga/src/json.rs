@@ -0,0 +1,278 @@
+// JSON representation of the AST for external tools such as syntax tree visualizers.
+//
+// The schema is versioned by `SCHEMA_VERSION` and only changes with it:
+//
+//   {"version": 10, "program": [Stmt, ...]}
+//
+//   Stmt       {"node": "<Stmt variant>", "span": Span, ...fields of the variant}
+//              "BlockStmt"    {"stmts": [Stmt]}
+//              "FuncDecl"     {"ident_func": Ident, "ident_param": [Ident], "annotation_param": [Type | null], "default_param": [Expr | null], "annotation_return": Type | null, "block": [Stmt]}
+//              "IfStmt"       {"condition": Expr, "block": [Stmt], "else_clause": ElseClause | null}
+//              "ForStmt"      {"label": Ident | null, "init": ForInit | null, "cond": Expr | null, "afterthought": Expr | null, "block": [Stmt]}
+//              "WhileStmt"    {"label": Ident | null, "cond": Expr, "block": [Stmt]}
+//              "DoWhileStmt"  {"label": Ident | null, "block": [Stmt], "cond": Expr}
+//              "SwitchStmt"   {"subject": Expr, "cases": [{"value": Expr, "block": [Stmt]}], "default": [Stmt] | null}
+//              "VarStmt"      {"is_const": bool, "ident": Ident, "annotation": Type | null, "expr": Expr | null}
+//              "ExprStmt"     {"expr": Expr}
+//              "ContinueStmt", "BreakStmt" {"label": Ident | null}
+//              "ReturnStmt"   {"expr": Expr | null}
+//              "Unreachable"  {"stmts": [Stmt]} (after "ContinueStmt", "BreakStmt" or "ReturnStmt")
+//              "Error"        {} (only in ASTs of sources with syntax errors)
+//   ElseClause {"type": "IfStmtBlock", "value": [Stmt]} | {"type": "IfStmt", "value": <IfStmt fields>}
+//   ForInit    {"type": "Var", "value": <VarStmt fields>} | {"type": "Expr", "value": Expr}
+//   Expr       {"node": "Literal", "type": "Bool" | "Int" | "String" | "Array" | "None", "value": bool | int | string | [Expr]}
+//              {"node": "Ident", "name": string, "span": Span}
+//              {"node": "PrefixOp", "kind": "Pos" | "Neg" | "Not" | "BitNot", "rhs": Expr}
+//              {"node": "InfixOp", "kind": <InfixOpKind variant>, "lhs": Expr, "rhs": Expr}
+//              {"node": "PostfixOp", "kind": {"type": "Index", "value": Expr} | {"type": "Call", "value": [Expr]}, "lhs": Expr}
+//              {"node": "Conditional", "cond": Expr, "then": Expr, "otherwise": Expr}
+//   Ident      {"name": string, "span": Span}
+//   Type       "Int" | "Bool" | "String" | "Array" | "None" (annotated after `:`)
+//   Span       {"start": int, "end": int} (byte offsets in the source)
+
+use serde::{Deserialize, Serialize};
+
+use crate::ast::*;
+use crate::error::ParseError;
+use crate::parser::parse;
+
+pub const SCHEMA_VERSION: u64 = 10;
+
+#[derive(Serialize, Deserialize)]
+struct ProgramJson {
+    version: u64,
+    program: Program,
+}
+
+pub fn parse_to_json(source: &str) -> Result<String, ParseError> {
+    let program = parse(source)?;
+    Ok(to_json(&program))
+}
+
+pub fn to_json(program: &Program) -> String {
+    serde_json::to_string(&ProgramJson {
+        version: SCHEMA_VERSION,
+        program: program.clone(),
+    })
+    .unwrap() // The AST always consists of serializable values
+}
+
+// `Stmt` pairs each node with its span, which is flattened into the node here
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "node")]
+pub enum StmtJson {
+    BlockStmt {
+        stmts: BlockStmt,
+        span: Span,
+    },
+    FuncDecl {
+        #[serde(flatten)]
+        func_decl: FuncDecl,
+        span: Span,
+    },
+    IfStmt {
+        #[serde(flatten)]
+        if_stmt: IfStmt,
+        span: Span,
+    },
+    ForStmt {
+        #[serde(flatten)]
+        for_stmt: ForStmt,
+        span: Span,
+    },
+    WhileStmt {
+        #[serde(flatten)]
+        while_stmt: WhileStmt,
+        span: Span,
+    },
+    DoWhileStmt {
+        #[serde(flatten)]
+        do_while_stmt: DoWhileStmt,
+        span: Span,
+    },
+    SwitchStmt {
+        #[serde(flatten)]
+        switch_stmt: SwitchStmt,
+        span: Span,
+    },
+    VarStmt {
+        #[serde(flatten)]
+        var_stmt: VarStmt,
+        span: Span,
+    },
+    ExprStmt {
+        expr: ExprStmt,
+        span: Span,
+    },
+    ContinueStmt {
+        label: Option<Ident>,
+        span: Span,
+    },
+    BreakStmt {
+        label: Option<Ident>,
+        span: Span,
+    },
+    ReturnStmt {
+        #[serde(flatten)]
+        return_stmt: ReturnStmt,
+        span: Span,
+    },
+    Unreachable {
+        stmts: BlockStmt,
+        span: Span,
+    },
+    Error {
+        span: Span,
+    },
+}
+
+impl From<Stmt> for StmtJson {
+    fn from(stmt: Stmt) -> Self {
+        match stmt {
+            Stmt::BlockStmt(stmts, span) => StmtJson::BlockStmt { stmts, span },
+            Stmt::FuncDecl(func_decl, span) => StmtJson::FuncDecl { func_decl, span },
+            Stmt::IfStmt(if_stmt, span) => StmtJson::IfStmt { if_stmt, span },
+            Stmt::ForStmt(for_stmt, span) => StmtJson::ForStmt { for_stmt, span },
+            Stmt::WhileStmt(while_stmt, span) => StmtJson::WhileStmt { while_stmt, span },
+            Stmt::DoWhileStmt(do_while_stmt, span) => StmtJson::DoWhileStmt {
+                do_while_stmt,
+                span,
+            },
+            Stmt::SwitchStmt(switch_stmt, span) => StmtJson::SwitchStmt { switch_stmt, span },
+            Stmt::VarStmt(var_stmt, span) => StmtJson::VarStmt { var_stmt, span },
+            Stmt::ExprStmt(expr, span) => StmtJson::ExprStmt { expr, span },
+            Stmt::ContinueStmt(label, span) => StmtJson::ContinueStmt { label, span },
+            Stmt::BreakStmt(label, span) => StmtJson::BreakStmt { label, span },
+            Stmt::ReturnStmt(return_stmt, span) => StmtJson::ReturnStmt { return_stmt, span },
+            Stmt::Unreachable(stmts, span) => StmtJson::Unreachable { stmts, span },
+            Stmt::Error(span) => StmtJson::Error { span },
+        }
+    }
+}
+
+impl From<StmtJson> for Stmt {
+    fn from(stmt: StmtJson) -> Self {
+        match stmt {
+            StmtJson::BlockStmt { stmts, span } => Stmt::BlockStmt(stmts, span),
+            StmtJson::FuncDecl { func_decl, span } => Stmt::FuncDecl(func_decl, span),
+            StmtJson::IfStmt { if_stmt, span } => Stmt::IfStmt(if_stmt, span),
+            StmtJson::ForStmt { for_stmt, span } => Stmt::ForStmt(for_stmt, span),
+            StmtJson::WhileStmt { while_stmt, span } => Stmt::WhileStmt(while_stmt, span),
+            StmtJson::DoWhileStmt {
+                do_while_stmt,
+                span,
+            } => Stmt::DoWhileStmt(do_while_stmt, span),
+            StmtJson::SwitchStmt { switch_stmt, span } => Stmt::SwitchStmt(switch_stmt, span),
+            StmtJson::VarStmt { var_stmt, span } => Stmt::VarStmt(var_stmt, span),
+            StmtJson::ExprStmt { expr, span } => Stmt::ExprStmt(expr, span),
+            StmtJson::ContinueStmt { label, span } => Stmt::ContinueStmt(label, span),
+            StmtJson::BreakStmt { label, span } => Stmt::BreakStmt(label, span),
+            StmtJson::ReturnStmt { return_stmt, span } => Stmt::ReturnStmt(return_stmt, span),
+            StmtJson::Unreachable { stmts, span } => Stmt::Unreachable(stmts, span),
+            StmtJson::Error { span } => Stmt::Error(span),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::*;
+
+    fn from_json(json: &str) -> Option<Program> {
+        match serde_json::from_str::<ProgramJson>(json) {
+            Ok(ProgramJson { version, program }) if version == SCHEMA_VERSION => Some(program),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn ast_is_serialized_with_schema() {
+        let json: serde_json::Value =
+            serde_json::from_str(&parse_to_json("var x = -1; x[0](true, \"a\");").unwrap())
+                .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "version": 10,
+                "program": [
+                    {
+                        "node": "VarStmt",
+                        "span": {"start": 0, "end": 11},
+                        "is_const": false,
+                        "ident": {"name": "x", "span": {"start": 4, "end": 5}},
+                        "annotation": null,
+                        "expr": {
+                            "node": "PrefixOp",
+                            "kind": "Neg",
+                            "rhs": {"node": "Literal", "type": "Int", "value": 1},
+                        },
+                    },
+                    {
+                        "node": "ExprStmt",
+                        "span": {"start": 12, "end": 28},
+                        "expr": {
+                            "node": "PostfixOp",
+                            "kind": {
+                                "type": "Call",
+                                "value": [
+                                    {"node": "Literal", "type": "Bool", "value": true},
+                                    {"node": "Literal", "type": "String", "value": "a"},
+                                ],
+                            },
+                            "lhs": {
+                                "node": "PostfixOp",
+                                "kind": {
+                                    "type": "Index",
+                                    "value": {"node": "Literal", "type": "Int", "value": 0},
+                                },
+                                "lhs": {"node": "Ident", "name": "x", "span": {"start": 12, "end": 13}},
+                            },
+                        },
+                    },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn ast_is_round_tripped() {
+        // Sources covering every node kind in the AST tests of `parser.rs`
+        let tests = [
+            "",
+            "{} 0; { 1; { 2; } }",
+            "func foo(param1, param2) { 123; return x; } func bar() { return; }",
+            "if (true) { if (true) {} } else if (false) {} else { none; }",
+            "for (;;) {} for (var i = 0; i < 10; i = i + 1) { continue; } for (i = 0; ; 1) { break; }",
+            "while (x) { break; }",
+            "outer: for (;;) { inner: while (x) { break outer; continue inner; } } do { continue; } while (x); l: do {} while (y);",
+            "switch (x) {} switch (f(x)) { case 1: { x = 1; } case -2: {} case true: {} case \"a\": {} default: { x; } }",
+            "var x; var y = [1, \"foo\", [true, none]];",
+            "var x: int = 1; func f(a: string, b): array { return [a]; }",
+            "const x = 1; const y: string = \"a\";",
+            "+1; -1; !true; 1 + 2 * 3 - 4 / 5 % 6; a == b != c > d >= e < f <= g && h || i; x = y = 1;",
+            "arr[0][1]; foo(); foo(1, bar(2))[3];",
+            "x += 1; a[0] -= 2; x *= y /= 3; x %= 4; a[0] = 5;",
+            "x = a || b ? c ? 1 : 2 : f(3) ? [4] : 5;",
+            "~a & b | c ^ d << 1 >> 2;",
+            "-2 ** 3 ** abs(-2);",
+            "func f(a: int, b = a + 1, c: string = \"c\") { return b; }",
+        ];
+        for src in tests {
+            let program = parse(src).unwrap();
+            let restored = from_json(&to_json(&program)).unwrap();
+            assert_eq!(restored, program, "Failed in test case: {}", src);
+            assert_eq!(
+                to_json(&restored),
+                to_json(&program),
+                "Failed in test case: {}",
+                src
+            ); // Spans included
+        }
+    }
+
+    #[test]
+    fn unknown_schema_version_is_rejected() {
+        assert_eq!(from_json(r#"{"version": 7, "program": []}"#), None);
+        assert_eq!(from_json(r#"{"version": 10, "program": []}"#), Some(vec![]));
+    }
+}
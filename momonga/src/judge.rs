@@ -0,0 +1,248 @@
+use core::fmt;
+use std::{cell::RefCell, rc::Rc};
+
+use crate::env::Env;
+use crate::error::{EvalError, ParseError};
+use crate::eval::{eval, with_call_depth_limit, with_step_limit};
+use crate::parser::parse;
+use crate::stdio::with_captured_stdio;
+
+pub const DEFAULT_STEP_LIMIT: u64 = 1_000_000;
+// About half the depth a simple recursive function reaches in a 1MB stack in release builds
+pub const DEFAULT_CALL_DEPTH_LIMIT: usize = 300;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TestCase {
+    pub stdin: String,
+    pub expected_stdout: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Accepted,
+    WrongAnswer(Diff),
+    RuntimeError(EvalError),
+    TimeLimitExceeded,
+}
+
+// The first line where the actual output differs from the expected one
+#[derive(Debug, PartialEq)]
+pub struct Diff {
+    pub line: usize, // 1-based
+    pub expected: Option<String>,
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Accepted => write!(f, "Accepted"),
+            Verdict::WrongAnswer(diff) => write!(f, "Wrong answer: {}", diff),
+            Verdict::RuntimeError(err) => write!(f, "Runtime error: {}", err),
+            Verdict::TimeLimitExceeded => write!(f, "Time limit exceeded"),
+        }
+    }
+}
+
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |line: &Option<String>| match line {
+            Some(line) => format!("\"{}\"", line),
+            None => "nothing".to_string(),
+        };
+        write!(
+            f,
+            "line {}: expected {}, but got {}",
+            self.line,
+            show(&self.expected),
+            show(&self.actual)
+        )
+    }
+}
+
+pub fn judge(
+    source: &str,
+    test_cases: &[TestCase],
+    step_limit: u64,
+    call_depth_limit: usize,
+) -> Result<Vec<Verdict>, ParseError> {
+    let program = parse(source)?;
+    let verdicts = test_cases
+        .iter()
+        .map(|test_case| {
+            let (res, stdout) = with_captured_stdio(&test_case.stdin, || {
+                with_step_limit(step_limit, || {
+                    with_call_depth_limit(call_depth_limit, || {
+                        eval(&program, Rc::new(RefCell::new(Env::new_with_builtins()))).map(|_| ())
+                    })
+                })
+            });
+            match res {
+                Ok(()) => match diff(&test_case.expected_stdout, &stdout) {
+                    Some(diff) => Verdict::WrongAnswer(diff),
+                    None => Verdict::Accepted,
+                },
+                Err(EvalError::StepLimit) => Verdict::TimeLimitExceeded,
+                Err(eval_err) => Verdict::RuntimeError(eval_err),
+            }
+        })
+        .collect();
+    Ok(verdicts)
+}
+
+// Trailing whitespace of each line and trailing empty lines are ignored
+fn diff(expected: &str, actual: &[String]) -> Option<Diff> {
+    let normalize = |lines: Vec<&str>| {
+        let mut lines: Vec<String> = lines
+            .iter()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        while let Some(true) = lines.last().map(|line| line.is_empty()) {
+            lines.pop();
+        }
+        lines
+    };
+    let expected = normalize(expected.lines().collect());
+    let actual = normalize(actual.iter().flat_map(|line| line.lines()).collect());
+
+    (0..expected.len().max(actual.len()))
+        .find(|&i| expected.get(i) != actual.get(i))
+        .map(|i| Diff {
+            line: i + 1,
+            expected: expected.get(i).cloned(),
+            actual: actual.get(i).cloned(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::judge::*;
+
+    fn test_case(stdin: &str, expected_stdout: &str) -> TestCase {
+        TestCase {
+            stdin: stdin.to_string(),
+            expected_stdout: expected_stdout.to_string(),
+        }
+    }
+
+    #[test]
+    fn verdicts_are_reported_per_test_case() {
+        let src = r#"
+            var name = input();
+            if (name == "momonga") {
+                print("Hello, " + name + "!");
+            } else if (name == "loop") {
+                while (true) {}
+            } else {
+                print(name + 1);
+            }
+            "#;
+        assert_eq!(
+            judge(
+                src,
+                &[
+                    test_case("momonga\n", "Hello, momonga!\n"),
+                    test_case("momonga", "Hello, world!"),
+                    test_case("loop", ""),
+                    test_case("foo", "foo1"),
+                ],
+                DEFAULT_STEP_LIMIT,
+                DEFAULT_CALL_DEPTH_LIMIT,
+            ),
+            Ok(vec![
+                Verdict::Accepted,
+                Verdict::WrongAnswer(Diff {
+                    line: 1,
+                    expected: Some("Hello, world!".to_string()),
+                    actual: Some("Hello, momonga!".to_string()),
+                }),
+                Verdict::TimeLimitExceeded,
+                Verdict::RuntimeError(EvalError::Type),
+            ])
+        );
+    }
+
+    #[test]
+    fn each_test_case_runs_in_fresh_env() {
+        let src = r#"
+            var lines = [];
+            push(lines, input());
+            push(lines, input());
+            print(lines);
+            "#;
+        assert_eq!(
+            judge(
+                src,
+                &[
+                    test_case("1\n2\n3\n", "[1, 2]"),
+                    test_case("4\n", "[4, 5]\n\n")
+                ],
+                DEFAULT_STEP_LIMIT,
+                DEFAULT_CALL_DEPTH_LIMIT,
+            ),
+            Ok(vec![
+                Verdict::Accepted,
+                Verdict::WrongAnswer(Diff {
+                    line: 1,
+                    expected: Some("[4, 5]".to_string()),
+                    actual: Some("[4, none]".to_string()),
+                }),
+            ])
+        );
+    }
+
+    #[test]
+    fn for_stmt_without_init_or_with_non_bool_cond_is_judged() {
+        let tests = [
+            (
+                "var i = 0; for (; i < 3; i += 1) { print(i); }",
+                Verdict::Accepted,
+            ),
+            (
+                "for (var i = 0; i; i += 1) { print(i); }",
+                Verdict::RuntimeError(EvalError::Type),
+            ),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                judge(
+                    src,
+                    &[test_case("", "0\n1\n2\n")],
+                    DEFAULT_STEP_LIMIT,
+                    DEFAULT_CALL_DEPTH_LIMIT,
+                ),
+                Ok(vec![expected]),
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn unbounded_recursion_is_runtime_error() {
+        let src = r#"
+            func f(n) {
+                print(n);
+                return f(n + 1);
+            }
+            f(1);
+            "#;
+        assert_eq!(
+            judge(src, &[test_case("", "1\n2\n3")], DEFAULT_STEP_LIMIT, 3),
+            Ok(vec![Verdict::RuntimeError(EvalError::CallDepthLimit)])
+        );
+    }
+
+    #[test]
+    fn parse_error_is_reported_once() {
+        assert_eq!(
+            judge(
+                ";",
+                &[test_case("", "")],
+                DEFAULT_STEP_LIMIT,
+                DEFAULT_CALL_DEPTH_LIMIT
+            ),
+            Err(ParseError::PestParser)
+        );
+    }
+}
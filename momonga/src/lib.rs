@@ -1,9 +1,19 @@
 mod ast;
+mod codegen;
 mod data;
+mod debugger;
 mod env;
 mod error;
 mod eval;
+mod formatter;
+mod json;
+mod judge;
+mod linter;
 mod parser;
+mod resolver;
+mod stdio;
+mod trace;
+mod typechecker;
 
 use std::{cell::RefCell, rc::Rc};
 use wasm_bindgen::prelude::*;
@@ -13,6 +23,24 @@ use crate::env::Env;
 use crate::eval::eval;
 use crate::parser::parse;
 
+pub use crate::ast::Span;
+pub use crate::codegen::{to_c, to_javascript, to_python, to_wasm, SideBySide};
+pub use crate::debugger::{Debugger, Stop, Variable, DEFAULT_DEBUG_STEP_LIMIT};
+pub use crate::error::{Error, EvalError, ParseError};
+pub use crate::formatter::format;
+pub use crate::json::parse_to_json;
+pub use crate::judge::{
+    judge, Diff, TestCase, Verdict, DEFAULT_CALL_DEPTH_LIMIT, DEFAULT_STEP_LIMIT,
+};
+pub use crate::linter::{lint, Lint, LintRule};
+pub use crate::resolver::{
+    completions, diagnostics, hover, resolve, Completion, CompletionKind, Diagnostic, Resolution,
+    Severity, Symbol, SymbolKind, KEYWORDS,
+};
+pub use crate::stdio::with_captured_stdio;
+pub use crate::trace::{trace, DEFAULT_TRACE_STEP_CAP};
+pub use crate::typechecker::{typecheck, Type, TypeCheck, TypeMismatch};
+
 pub fn interpret(src: &str) -> Option<String> {
     match parse(src) {
         Ok(ast) => match eval(&ast, Rc::new(RefCell::new(Env::new_with_builtins()))) {
@@ -23,6 +51,12 @@ pub fn interpret(src: &str) -> Option<String> {
     }
 }
 
+pub fn run(src: &str) -> Result<(), Error> {
+    let ast = parse(src)?;
+    eval(&ast, Rc::new(RefCell::new(Env::new_with_builtins())))?;
+    Ok(())
+}
+
 #[wasm_bindgen]
 pub enum OutputEvent {
     Stdout,
@@ -50,7 +84,7 @@ pub fn emit_output_event(output_event: OutputEvent, data: &str) {
 pub fn momonga_run(source: &str) {
     #[cfg(debug_assertions)]
     console_error_panic_hook::set_once();
-    
+
     match parse(source) {
         Ok(ast) => match eval(&ast, Rc::new(RefCell::new(Env::new_with_builtins()))) {
             Ok(_) => (),
@@ -64,9 +98,235 @@ pub fn momonga_run(source: &str) {
 pub fn is_momonga_parse_error(source: &str) -> bool {
     #[cfg(debug_assertions)]
     console_error_panic_hook::set_once();
-    
+
     match parse(source) {
         Ok(_ast) => false,
         Err(_parse_err) => true,
     }
 }
+
+#[wasm_bindgen]
+pub fn momonga_format(source: &str) -> Option<String> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    format(source).ok()
+}
+
+#[wasm_bindgen]
+pub fn momonga_parse_to_json(source: &str) -> Result<String, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    parse_to_json(source).map_err(|parse_err| JsError::new(&parse_err.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn momonga_judge(
+    source: &str,
+    stdins: Vec<String>,
+    expected_stdouts: Vec<String>,
+    step_limit: Option<u64>,
+    call_depth_limit: Option<usize>,
+) -> Result<Vec<String>, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    if stdins.len() != expected_stdouts.len() {
+        return Err(JsError::new(&format!(
+            "{} stdins are given for {} expected stdouts",
+            stdins.len(),
+            expected_stdouts.len()
+        )));
+    }
+    let test_cases: Vec<TestCase> = stdins
+        .into_iter()
+        .zip(expected_stdouts)
+        .map(|(stdin, expected_stdout)| TestCase {
+            stdin,
+            expected_stdout,
+        })
+        .collect();
+    let step_limit = step_limit.unwrap_or(DEFAULT_STEP_LIMIT);
+    let call_depth_limit = call_depth_limit.unwrap_or(DEFAULT_CALL_DEPTH_LIMIT);
+    match judge(source, &test_cases, step_limit, call_depth_limit) {
+        Ok(verdicts) => Ok(verdicts.iter().map(|verdict| verdict.to_string()).collect()),
+        Err(parse_err) => Ok(vec![parse_err.to_string(); test_cases.len()]),
+    }
+}
+
+// Side-by-side JSON of the source and the Python code
+#[wasm_bindgen]
+pub fn momonga_to_python(source: &str) -> Result<String, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    to_python(source)
+        .map(|side_by_side| side_by_side.to_json())
+        .map_err(|parse_err| JsError::new(&parse_err.to_string()))
+}
+
+// Side-by-side JSON of the source and the JavaScript code
+#[wasm_bindgen]
+pub fn momonga_to_javascript(source: &str) -> Result<String, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    to_javascript(source)
+        .map(|side_by_side| side_by_side.to_json())
+        .map_err(|parse_err| JsError::new(&parse_err.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn momonga_to_c(source: &str) -> Result<String, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    to_c(source)
+        .map(|side_by_side| side_by_side.to_json())
+        .map_err(|parse_err| JsError::new(&parse_err.to_string()))
+}
+
+// Bytes of a WebAssembly module, which imports `print`, `input` and `error` from "env"
+#[wasm_bindgen]
+pub fn momonga_to_wasm(source: &str) -> Result<Vec<u8>, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    to_wasm(source).map_err(|parse_err| JsError::new(&parse_err.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn momonga_trace(source: &str, step_cap: Option<u64>) -> Result<String, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    trace(source, step_cap.unwrap_or(DEFAULT_TRACE_STEP_CAP))
+        .map_err(|parse_err| JsError::new(&parse_err.to_string()))
+}
+
+// JSON of the completion items at `offset`, which counts UTF-16 code units as Monaco does
+#[wasm_bindgen]
+pub fn momonga_completions(source: &str, offset: usize) -> String {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    serde_json::to_string(&completions(source, byte_offset(source, offset))).unwrap()
+}
+
+// Markdown to show on hover at `offset`, which counts UTF-16 code units as Monaco does
+#[wasm_bindgen]
+pub fn momonga_hover(source: &str, offset: usize) -> Option<String> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    hover(source, byte_offset(source, offset))
+}
+
+// JSON of the lints as [{"rule", "message", "start", "end"}], whose offsets count UTF-16 code
+// units as Monaco does
+#[wasm_bindgen]
+pub fn momonga_lint(source: &str) -> Result<String, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let lints = lint(source).map_err(|parse_err| JsError::new(&parse_err.to_string()))?;
+    let utf16_offset =
+        |offset: usize| -> usize { source[..offset].chars().map(char::len_utf16).sum() };
+    let lints: Vec<serde_json::Value> = lints
+        .iter()
+        .map(|lint| {
+            serde_json::json!({
+                "rule": lint.rule,
+                "message": lint.message,
+                "start": utf16_offset(lint.span.start),
+                "end": utf16_offset(lint.span.end),
+            })
+        })
+        .collect();
+    Ok(serde_json::Value::from(lints).to_string())
+}
+
+#[wasm_bindgen]
+pub fn momonga_typecheck(source: &str) -> Result<String, JsError> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+
+    let type_check = typecheck(source).map_err(|parse_err| JsError::new(&parse_err.to_string()))?;
+    let utf16_offset =
+        |offset: usize| -> usize { source[..offset].chars().map(char::len_utf16).sum() };
+    let mismatches: Vec<serde_json::Value> = type_check
+        .mismatches
+        .iter()
+        .map(|mismatch| {
+            serde_json::json!({
+                "message": mismatch.message,
+                "start": utf16_offset(mismatch.span.start),
+                "end": utf16_offset(mismatch.span.end),
+            })
+        })
+        .collect();
+    Ok(serde_json::Value::from(mismatches).to_string())
+}
+
+fn byte_offset(source: &str, utf16_offset: usize) -> usize {
+    let mut utf16_len = 0;
+    for (i, char) in source.char_indices() {
+        if utf16_len >= utf16_offset {
+            return i;
+        }
+        utf16_len += char.len_utf16();
+    }
+    source.len()
+}
+
+#[wasm_bindgen]
+pub struct MomongaDebugger {
+    debugger: Debugger,
+}
+
+#[wasm_bindgen]
+impl MomongaDebugger {
+    #[wasm_bindgen(constructor)]
+    pub fn new(source: &str, step_limit: Option<u64>) -> Result<MomongaDebugger, JsError> {
+        #[cfg(debug_assertions)]
+        console_error_panic_hook::set_once();
+
+        match Debugger::new(source, step_limit.unwrap_or(DEFAULT_DEBUG_STEP_LIMIT)) {
+            Ok(debugger) => Ok(MomongaDebugger { debugger }),
+            Err(parse_err) => Err(JsError::new(&parse_err.to_string())),
+        }
+    }
+
+    pub fn set_breakpoints(&mut self, lines: Vec<usize>) {
+        self.debugger.set_breakpoints(&lines);
+    }
+
+    // Each of the following returns false once the program has finished
+    pub fn resume(&mut self) -> bool {
+        self.debugger.resume().is_some()
+    }
+
+    pub fn step_in(&mut self) -> bool {
+        self.debugger.step_in().is_some()
+    }
+
+    pub fn step_over(&mut self) -> bool {
+        self.debugger.step_over().is_some()
+    }
+
+    pub fn step_out(&mut self) -> bool {
+        self.debugger.step_out().is_some()
+    }
+
+    // JSON of the form {"finished", "stop": Stop | null, "stdout": [string], "error": string | null}
+    pub fn state(&self) -> String {
+        serde_json::json!({
+            "finished": self.debugger.is_finished(),
+            "stop": self.debugger.current(),
+            "stdout": self.debugger.stdout(),
+            "error": self.debugger.error_message(),
+        })
+        .to_string()
+    }
+}
@@ -0,0 +1,379 @@
+// Warnings about code which is valid but most likely not what a beginner meant.
+
+use serde::Serialize;
+
+use crate::ast::*;
+use crate::error::ParseError;
+use crate::parser::parse;
+use crate::resolver::{literal_type, resolve, Resolution, SymbolKind};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum LintRule {
+    UnusedVariable,
+    UndeclaredAssignment,
+    UnreachableCode,
+    NonBoolCondition,
+    InfiniteLoop,
+    ShadowedParameter,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Lint {
+    pub rule: LintRule,
+    pub span: Span,
+    pub message: String,
+}
+
+// Warnings in the order of their positions
+pub fn lint(source: &str) -> Result<Vec<Lint>, ParseError> {
+    let program = parse(source)?;
+    let resolution = resolve(source);
+    let mut linter = Linter {
+        resolution: &resolution,
+        lints: vec![],
+    };
+
+    for (index, symbol) in resolution.symbols.iter().enumerate() {
+        let noun = match symbol.kind {
+            SymbolKind::Variable => "Variable",
+            SymbolKind::Constant => "Constant",
+            _ => continue,
+        };
+        if !resolution
+            .references
+            .iter()
+            .any(|(_, reference)| *reference == Some(index))
+        {
+            linter.push(
+                LintRule::UnusedVariable,
+                symbol.span,
+                format!("{} `{}` is declared but never used", noun, symbol.name),
+            );
+        }
+        let shadowed = resolution
+            .visible(symbol.span.start)
+            .into_iter()
+            .find(|visible| visible.name == symbol.name);
+        if shadowed.is_some_and(|shadowed| shadowed.kind == SymbolKind::Parameter) {
+            linter.push(
+                LintRule::ShadowedParameter,
+                symbol.span,
+                format!(
+                    "{} `{}` shadows the parameter of the same name",
+                    noun, symbol.name
+                ),
+            );
+        }
+    }
+    for (span, keyword) in &resolution.unreachable {
+        linter.push(
+            LintRule::UnreachableCode,
+            *span,
+            format!("Unreachable code after `{}`", keyword),
+        );
+    }
+    linter.stmts(&program);
+
+    linter.lints.sort_by_key(|lint| lint.span.start);
+    Ok(linter.lints)
+}
+
+struct Linter<'a> {
+    resolution: &'a Resolution,
+    lints: Vec<Lint>,
+}
+
+impl<'a> Linter<'a> {
+    fn push(&mut self, rule: LintRule, span: Span, message: String) {
+        self.lints.push(Lint {
+            rule,
+            span,
+            message,
+        });
+    }
+
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in stmts {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::BlockStmt(stmts, _) | Stmt::Unreachable(stmts, _) => self.stmts(stmts),
+            Stmt::FuncDecl(
+                FuncDecl {
+                    default_param,
+                    block,
+                    ..
+                },
+                _,
+            ) => {
+                for default in default_param.iter().flatten() {
+                    self.expr(default);
+                }
+                self.stmts(block);
+            }
+            Stmt::IfStmt(if_stmt, span) => self.if_stmt(if_stmt, *span),
+            Stmt::ForStmt(
+                ForStmt {
+                    init,
+                    cond,
+                    afterthought,
+                    block,
+                    ..
+                },
+                span,
+            ) => {
+                match init {
+                    Some(ForStmtInit::Var(VarStmt {
+                        expr: Some(expr), ..
+                    })) => self.expr(expr),
+                    Some(ForStmtInit::Expr(expr)) => self.expr(expr),
+                    _ => (),
+                }
+                if let Some(cond) = cond {
+                    self.condition(cond, *span);
+                }
+                if let Some(afterthought) = afterthought {
+                    self.expr(afterthought);
+                }
+                self.stmts(block);
+            }
+            Stmt::WhileStmt(WhileStmt { cond, block, .. }, span)
+            | Stmt::DoWhileStmt(DoWhileStmt { block, cond, .. }, span) => {
+                self.condition(cond, *span);
+                if let Expr::Literal(Literal::Bool(true)) = cond {
+                    if !exits(block, &[]) {
+                        self.push(
+                            LintRule::InfiniteLoop,
+                            *span,
+                            "`while (true)` never ends without `break`".to_string(),
+                        );
+                    }
+                }
+                self.stmts(block);
+            }
+            Stmt::SwitchStmt(switch_stmt, _) => {
+                self.expr(&switch_stmt.subject);
+                for block in switch_blocks(switch_stmt) {
+                    self.stmts(block);
+                }
+            }
+            Stmt::VarStmt(
+                VarStmt {
+                    expr: Some(expr), ..
+                },
+                _,
+            ) => self.expr(expr),
+            Stmt::ExprStmt(expr, _) => self.expr(expr),
+            Stmt::ReturnStmt(ReturnStmt { expr: Some(expr) }, _) => self.expr(expr),
+            Stmt::VarStmt(..)
+            | Stmt::ReturnStmt(..)
+            | Stmt::ContinueStmt(..)
+            | Stmt::BreakStmt(..)
+            | Stmt::Error(_) => (),
+        }
+    }
+
+    fn if_stmt(&mut self, if_stmt: &IfStmt, span: Span) {
+        self.condition(&if_stmt.condition, span);
+        self.stmts(&if_stmt.block);
+        match &if_stmt.else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => self.if_stmt(if_stmt, span),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => self.stmts(block),
+            None => (),
+        }
+    }
+
+    // Expressions have no spans, so the statement of the condition is reported
+    fn condition(&mut self, cond: &Expr, span: Span) {
+        if let Some(type_) = literal_type(cond).filter(|&type_| type_ != "bool") {
+            self.push(
+                LintRule::NonBoolCondition,
+                span,
+                format!(
+                    "Condition is always a value of type `{}`, but it must be a `bool`",
+                    type_
+                ),
+            );
+        }
+        self.expr(cond);
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(Literal::Array(exprs)) => exprs.iter().for_each(|expr| self.expr(expr)),
+            Expr::Literal(_) | Expr::Ident(_) => (),
+            Expr::PrefixOp { rhs, .. } => self.expr(rhs),
+            Expr::InfixOp { kind, lhs, rhs } => {
+                if let (true, Expr::Ident(ident)) = (kind.is_assign(), lhs.as_ref()) {
+                    let declared =
+                        self.resolution.references.iter().any(|(span, symbol)| {
+                            span.start == ident.span.start && symbol.is_some()
+                        });
+                    if !declared {
+                        self.push(
+                            LintRule::UndeclaredAssignment,
+                            ident.span,
+                            format!(
+                                "`{}` is assigned without being declared with `var`",
+                                ident.name
+                            ),
+                        );
+                    }
+                }
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::PostfixOp { kind, lhs } => {
+                self.expr(lhs);
+                match kind {
+                    PostfixOpKind::Index(index) => self.expr(index),
+                    PostfixOpKind::Call(args) => args.iter().for_each(|arg| self.expr(arg)),
+                }
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                self.expr(cond);
+                self.expr(then);
+                self.expr(otherwise);
+            }
+        }
+    }
+}
+
+// Whether running `stmts` can leave the loop around them, which a `break` of an inner loop
+// cannot, given the labels of the inner loops the statements are in
+fn exits(stmts: &[Stmt], inner_loops: &[Option<&str>]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::BreakStmt(None, _) => inner_loops.is_empty(),
+        Stmt::BreakStmt(Some(label), _) => !inner_loops.contains(&Some(label.name.as_str())),
+        Stmt::ReturnStmt(..) => true,
+        Stmt::BlockStmt(stmts, _) => exits(stmts, inner_loops),
+        Stmt::IfStmt(if_stmt, _) => if_exits(if_stmt, inner_loops),
+        Stmt::SwitchStmt(switch_stmt, _) => {
+            switch_blocks(switch_stmt).any(|block| exits(block, inner_loops))
+        }
+        Stmt::ForStmt(ForStmt { label, block, .. }, _)
+        | Stmt::WhileStmt(WhileStmt { label, block, .. }, _)
+        | Stmt::DoWhileStmt(DoWhileStmt { label, block, .. }, _) => {
+            let label = label.as_ref().map(|label| label.name.as_str());
+            exits(block, &[inner_loops, &[label]].concat())
+        }
+        _ => false,
+    })
+}
+
+fn if_exits(if_stmt: &IfStmt, inner_loops: &[Option<&str>]) -> bool {
+    exits(&if_stmt.block, inner_loops)
+        || match &if_stmt.else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => if_exits(if_stmt, inner_loops),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => exits(block, inner_loops),
+            None => false,
+        }
+}
+
+// Blocks of the cases and of the default
+fn switch_blocks(switch_stmt: &SwitchStmt) -> impl Iterator<Item = &BlockStmt> {
+    switch_stmt
+        .cases
+        .iter()
+        .map(|case| &case.block)
+        .chain(&switch_stmt.default)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::linter::*;
+
+    #[test]
+    fn lints_are_reported() {
+        let tests = [
+            ("var x = 1;\nprint(x);", vec![]),
+            ("var x = 1;", vec![(LintRule::UnusedVariable, "x")]),
+            ("const x = 1;", vec![(LintRule::UnusedVariable, "x")]),
+            (
+                "for (var i = 0; i < 3; i = i + 1) { var x = 1; }",
+                vec![(LintRule::UnusedVariable, "x")],
+            ),
+            ("x = 1;", vec![(LintRule::UndeclaredAssignment, "x")]),
+            ("var x;\nx = 1;", vec![]),
+            (
+                "func f() {\n    return 1;\n    print(1);\n    print(2);\n}\nf();",
+                vec![(LintRule::UnreachableCode, "print(1);\n    print(2);")],
+            ),
+            (
+                "var x = true;\nwhile (x) {\n    if (x) {} else {\n        continue;\n        x;\n    }\n}",
+                vec![(LintRule::UnreachableCode, "x;")],
+            ),
+            ("if (1) {}", vec![(LintRule::NonBoolCondition, "if (1) {}")]),
+            (
+                "var x = true;\nif (x) {} else if (none) {}",
+                vec![(LintRule::NonBoolCondition, "if (x) {} else if (none) {}")],
+            ),
+            (
+                "while (-1) {}",
+                vec![(LintRule::NonBoolCondition, "while (-1) {}")],
+            ),
+            ("if (true) {}", vec![]),
+            (
+                "while (true) { print(1); }",
+                vec![(LintRule::InfiniteLoop, "while (true) { print(1); }")],
+            ),
+            (
+                "while (true) { while (true) { break; } }",
+                vec![(
+                    LintRule::InfiniteLoop,
+                    "while (true) { while (true) { break; } }",
+                )],
+            ),
+            ("var s = none;\nwhile (true) { if (s == none) { break; } }", vec![]),
+            ("outer: while (true) { while (true) { break outer; } }", vec![]),
+            (
+                "do { print(1); } while (true);",
+                vec![(LintRule::InfiniteLoop, "do { print(1); } while (true);")],
+            ),
+            (
+                "do {} while (1);",
+                vec![(LintRule::NonBoolCondition, "do {} while (1);")],
+            ),
+            ("func f() { while (true) { return 1; } }\nf();", vec![]),
+            ("var c = 1;\nwhile (true) { switch (c) { case 1: { break; } } }", vec![]),
+            (
+                "switch (1) { default: { while (true) {} } }",
+                vec![(LintRule::InfiniteLoop, "while (true) {}")],
+            ),
+            (
+                "func f(a) { var a = 1; return a; }\nf(1);",
+                vec![(LintRule::ShadowedParameter, "a")],
+            ),
+            (
+                "func f(a) { func g(b) { var a = b; return a; } return g(a); }\nf(1);",
+                vec![(LintRule::ShadowedParameter, "a")],
+            ),
+        ];
+        for (src, expected) in tests {
+            let lints: Vec<(LintRule, &str)> = lint(src)
+                .unwrap()
+                .iter()
+                .map(|lint| (lint.rule, &src[lint.span.start..lint.span.end]))
+                .collect();
+            assert_eq!(lints, expected, "Failed in test case: {}", src);
+        }
+    }
+
+    #[test]
+    fn lints_are_positioned() {
+        let src = "func f(a) {\n    var a = 1;\n    return a;\n}\nf(1);";
+        let spans: Vec<(usize, usize)> = lint(src)
+            .unwrap()
+            .iter()
+            .map(|lint| (lint.span.start, lint.span.end))
+            .collect();
+        assert_eq!(spans, vec![(20, 21)]);
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::Write;
+use std::{env, fs, process::ExitCode};
+
+const USAGE: &str = "Usage: momonga <run|fmt|lint|check|trace|python|js|c|wasm> <file>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (command, path) = match args.as_slice() {
+        [command, path] => (command.as_str(), path.as_str()),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{}: {}", path, err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let res = match command {
+        "run" => momonga::run(&source),
+        "fmt" => momonga::format(&source)
+            .map(|formatted| print!("{}", formatted))
+            .map_err(momonga::Error::from),
+        "lint" => momonga::lint(&source)
+            .map(|lints| {
+                for lint in lints {
+                    let (line, column) = position(&source, lint.span.start);
+                    println!("{}:{}:{}: {}", path, line, column, lint.message);
+                }
+            })
+            .map_err(momonga::Error::from),
+        "check" => momonga::typecheck(&source)
+            .map(|type_check| {
+                for mismatch in type_check.mismatches {
+                    let (line, column) = position(&source, mismatch.span.start);
+                    println!("{}:{}:{}: {}", path, line, column, mismatch.message);
+                }
+            })
+            .map_err(momonga::Error::from),
+        "trace" => momonga::trace(&source, momonga::DEFAULT_TRACE_STEP_CAP)
+            .map(|trace| println!("{}", trace))
+            .map_err(momonga::Error::from),
+        "python" => momonga::to_python(&source)
+            .map(|side_by_side| print!("{}", side_by_side.target))
+            .map_err(momonga::Error::from),
+        "js" => momonga::to_javascript(&source)
+            .map(|side_by_side| print!("{}", side_by_side.target))
+            .map_err(momonga::Error::from),
+        "c" => momonga::to_c(&source)
+            .map(|side_by_side| print!("{}", side_by_side.target))
+            .map_err(momonga::Error::from),
+        "wasm" => momonga::to_wasm(&source)
+            .map(|bytes| std::io::stdout().write_all(&bytes).unwrap())
+            .map_err(momonga::Error::from),
+        _ => {
+            eprintln!("{}", USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    match res {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// 1-based line and column of a byte offset
+fn position(source: &str, offset: usize) -> (usize, usize) {
+    let before = &source[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
@@ -1,4 +1,4 @@
-use pest::iterators::Pair;
+use pest::iterators::{Pair, Pairs};
 use pest::pratt_parser::PrattParser;
 use pest::Parser;
 use pest_derive::Parser;
@@ -13,13 +13,19 @@ lazy_static::lazy_static! {
 
         // Precedence is defined lowest to highest
         PrattParser::new()
-            .op(Op::infix(ASSIGN, Right))
+            .op(Op::infix(ASSIGN, Right) | Op::infix(ADD_ASSIGN, Right) | Op::infix(SUBTRACT_ASSIGN, Right) | Op::infix(MULTIPLY_ASSIGN, Right) | Op::infix(DIVIDE_ASSIGN, Right) | Op::infix(MODULO_ASSIGN, Right))
+            .op(Op::infix(CONDITIONAL, Right))
             .op(Op::infix(OR, Left))
             .op(Op::infix(AND, Left))
+            .op(Op::infix(BIT_OR, Left))
+            .op(Op::infix(BIT_XOR, Left))
+            .op(Op::infix(BIT_AND, Left))
             .op(Op::infix(EQ, Left) | Op::infix(NOT_EQ, Left) | Op::infix(GT, Left) | Op::infix(GE, Left) | Op::infix(LT, Left) | Op::infix(LE, Left))
+            .op(Op::infix(SHIFT_LEFT, Left) | Op::infix(SHIFT_RIGHT, Left))
             .op(Op::infix(ADD, Left) | Op::infix(SUBTRACT, Left))
             .op(Op::infix(MULTIPLY, Left) | Op::infix(DIVIDE, Left) | Op::infix(MODULO, Left))
-            .op(Op::prefix(POS) | Op::prefix(NEG) | Op::prefix(NOT))
+            .op(Op::prefix(POS) | Op::prefix(NEG) | Op::prefix(NOT) | Op::prefix(BIT_NOT))
+            .op(Op::infix(POWER, Right)) // `-2 ** 2` is `-(2 ** 2)`
             .op(Op::postfix(INDEX) | Op::postfix(CALL) )
     };
 }
@@ -39,6 +45,172 @@ pub fn parse(source: &str) -> Result<Program, ParseError> {
     }
 }
 
+// AST of `source` with an error statement in place of each statement which fails to parse, and
+// the errors with where they were found
+//
+// Each time pest fails, the statement around the failure is masked with NUL, which the grammar
+// takes as `error_stmt`, followed by spaces so that the offsets of the rest are kept. Braces left
+// open at the end of the source are closed.
+pub fn parse_recovering(source: &str) -> (Program, Vec<(ParseError, Span)>) {
+    let mut masked = source.to_string();
+    let mut regions = vec![];
+    let mut errors = vec![];
+    let mut closed = false;
+    loop {
+        let pos = match PestMomongaParser::parse(Rule::program, &masked) {
+            Ok(mut pairs) => {
+                let mut ast_builder = AstBuilder::new_recovering(regions);
+                let program = ast_builder.program(pairs.next().unwrap()).unwrap();
+                errors.extend(ast_builder.errors);
+                errors.sort_by_key(|(_, span): &(ParseError, Span)| span.start);
+                errors.dedup_by_key(|(_, span)| span.start); // Such as an unclosed brace at the end
+                return (program, errors);
+            }
+            Err(e) => match e.location {
+                pest::error::InputLocation::Pos(pos) => pos,
+                pest::error::InputLocation::Span((start, _)) => start,
+            },
+        };
+        let pos = pos.min(source.len());
+        errors.push((ParseError::PestParser, Span::new(pos, pos)));
+
+        let mut region = stmt_around(&masked, pos);
+        if masked[region.start..region.end]
+            .trim_matches(is_whitespace)
+            .is_empty()
+        {
+            if masked[region.start..].starts_with('}') {
+                region.end = region.start + 1; // A brace closing nothing
+            } else if region.end == masked.len() && !closed && unclosed_braces(&masked) > 0 {
+                masked.push_str(&"}".repeat(unclosed_braces(&masked)));
+                closed = true;
+                continue;
+            } else {
+                return (vec![Stmt::Error(Span::new(0, source.len()))], errors);
+            }
+        }
+        let mask: String = masked[region.start..region.end]
+            .bytes()
+            .enumerate()
+            .map(|(i, byte)| match byte {
+                _ if i == 0 => '\0',
+                b'\n' | b'\r' => byte as char,
+                _ => ' ',
+            })
+            .collect();
+        masked.replace_range(region.start..region.end, &mask);
+        regions.push(Span::new(region.start, region.end.min(source.len())));
+    }
+}
+
+fn is_whitespace(char: char) -> bool {
+    matches!(char, ' ' | '\n' | '\r')
+}
+
+// Statement around `pos`, from the boundary before it up to the next `;` or the `}` closing a
+// brace opened in it, or otherwise up to a `}` closing an outer brace
+fn stmt_around(source: &str, pos: usize) -> Span {
+    let boundaries = boundaries(source);
+    let mut start = boundaries
+        .iter()
+        .rev()
+        .find(|(i, _)| *i < pos)
+        .map_or(0, |(i, _)| i + 1);
+    // Comments before the statement are kept
+    loop {
+        start = source[start..]
+            .find(|char| !is_whitespace(char))
+            .map_or(source.len(), |i| start + i);
+        let rest = &source[start..];
+        if rest.starts_with("//") {
+            start += rest.find('\n').unwrap_or(rest.len());
+        } else if let Some(i) = rest.strip_prefix("/*").and_then(|rest| rest.find("*/")) {
+            start += i + 4;
+        } else {
+            break;
+        }
+    }
+
+    let mut depth = 0;
+    for &(i, byte) in boundaries.iter().filter(|(i, _)| *i >= start) {
+        match byte {
+            b'{' => depth += 1,
+            b'}' if depth > 1 => depth -= 1,
+            b'}' if depth == 1 => return Span::new(start, i + 1),
+            b';' if depth == 0 => return Span::new(start, i + 1),
+            b'}' | b'\0' if depth == 0 => return Span::new(start, i),
+            _ => (),
+        }
+    }
+    Span::new(start, source.len())
+}
+
+fn unclosed_braces(source: &str) -> usize {
+    boundaries(source)
+        .iter()
+        .fold(0, |depth: usize, (_, byte)| match byte {
+            b'{' => depth + 1,
+            b'}' => depth.saturating_sub(1),
+            _ => depth,
+        })
+}
+
+// Positions of `;`, `{`, `}` and NUL outside of strings and comments, except the `;` in the
+// headers of for statements
+fn boundaries(source: &str) -> Vec<(usize, u8)> {
+    let bytes = source.as_bytes();
+    let mut boundaries = vec![];
+    let mut parens = vec![]; // Whether each open parenthesis begins the header of a for statement
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                i = source[i..].find('\n').map_or(bytes.len(), |j| i + j);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i = source[i + 2..]
+                    .find("*/")
+                    .map_or(bytes.len(), |j| i + j + 3);
+            }
+            b'(' => {
+                let before = source[..i].trim_end_matches(is_whitespace);
+                parens.push(before.strip_suffix("for").is_some_and(|before| {
+                    !before.ends_with(|char: char| char.is_ascii_alphanumeric() || char == '_')
+                }));
+            }
+            b')' => {
+                parens.pop();
+            }
+            b';' if parens.contains(&true) => (),
+            b'{' | b'}' => {
+                parens.clear();
+                boundaries.push((i, bytes[i]));
+            }
+            b';' | b'\0' => boundaries.push((i, bytes[i])),
+            _ => (),
+        }
+        i += 1;
+    }
+    boundaries
+}
+
+// Whether two values of cases are the same, taking `-0` as `0`
+fn same_case_value(lhs: &Expr, rhs: &Expr) -> bool {
+    fn unsigned(value: &Expr) -> &Expr {
+        match value {
+            Expr::PrefixOp { rhs, .. } if **rhs == Expr::literal_int(0) => rhs,
+            value => value,
+        }
+    }
+    unsigned(lhs) == unsigned(rhs)
+}
+
 enum AstBuildFlow {
     Value,
     Break,
@@ -47,12 +219,30 @@ enum AstBuildFlow {
 }
 struct AstBuilder {
     flow: AstBuildFlow,
+    labels: Vec<String>, // Of the loops enclosing the statement being built
+    recovering: bool,
+    regions: Vec<Span>, // Statements masked by `parse_recovering`
+    errors: Vec<(ParseError, Span)>,
 }
 
 impl AstBuilder {
     fn new() -> Self {
         Self {
             flow: AstBuildFlow::Value,
+            labels: vec![],
+            recovering: false,
+            regions: vec![],
+            errors: vec![],
+        }
+    }
+
+    fn new_recovering(regions: Vec<Span>) -> Self {
+        Self {
+            flow: AstBuildFlow::Value,
+            labels: vec![],
+            recovering: true,
+            regions,
+            errors: vec![],
         }
     }
 
@@ -62,15 +252,14 @@ impl AstBuilder {
             match unknown_pair.as_rule() {
                 Rule::stmt => {
                     let stmt = self.stmt(unknown_pair)?;
-                    if let AstBuildFlow::Continue = self.flow {
-                        return Err(ParseError::BuildAst);
-                    };
-                    if let AstBuildFlow::Break = self.flow {
-                        return Err(ParseError::BuildAst);
-                    };
-                    if let AstBuildFlow::Return = self.flow {
-                        return Err(ParseError::BuildAst);
-                    };
+                    if !matches!(self.flow, AstBuildFlow::Value) {
+                        if !self.recovering {
+                            return Err(ParseError::BuildAst);
+                        }
+                        // The statement is kept so that the names declared in it are not lost
+                        self.errors.push((ParseError::BuildAst, stmt.span()));
+                        self.flow = AstBuildFlow::Value;
+                    }
                     program.push(stmt);
                 }
                 Rule::EOI => break,
@@ -82,25 +271,47 @@ impl AstBuilder {
 
     fn stmt(&mut self, stmt_pair: Pair<Rule>) -> Result<Stmt, ParseError> {
         let unknown_pair = stmt_pair.into_inner().next().unwrap();
+        let span = self.span(&unknown_pair);
         match unknown_pair.as_rule() {
-            Rule::block_stmt => Ok(Stmt::BlockStmt(self.block_stmt(unknown_pair)?)),
-            Rule::func_decl => Ok(Stmt::FuncDecl(self.func_decl(unknown_pair)?)),
-            Rule::if_stmt => Ok(Stmt::IfStmt(self.if_stmt(unknown_pair)?)),
-            Rule::for_stmt => Ok(Stmt::ForStmt(self.for_stmt(unknown_pair)?)),
-            Rule::while_stmt => Ok(Stmt::WhileStmt(self.while_stmt(unknown_pair)?)),
-            Rule::var_stmt => Ok(Stmt::VarStmt(self.var_stmt(unknown_pair)?)),
-            Rule::expr => Ok(Stmt::ExprStmt(self.expr(unknown_pair)?)),
+            Rule::block_stmt => Ok(Stmt::BlockStmt(self.block_stmt(unknown_pair)?, span)),
+            Rule::func_decl => Ok(Stmt::FuncDecl(self.func_decl(unknown_pair)?, span)),
+            Rule::if_stmt => Ok(Stmt::IfStmt(self.if_stmt(unknown_pair)?, span)),
+            Rule::for_stmt => Ok(Stmt::ForStmt(self.for_stmt(unknown_pair)?, span)),
+            Rule::while_stmt => Ok(Stmt::WhileStmt(self.while_stmt(unknown_pair)?, span)),
+            Rule::do_while_stmt => Ok(Stmt::DoWhileStmt(self.do_while_stmt(unknown_pair)?, span)),
+            Rule::switch_stmt => Ok(Stmt::SwitchStmt(self.switch_stmt(unknown_pair)?, span)),
+            Rule::var_stmt => Ok(Stmt::VarStmt(self.var_stmt(unknown_pair)?, span)),
+            Rule::expr_stmt => Ok(Stmt::ExprStmt(
+                self.expr(unknown_pair.into_inner().next().unwrap())?,
+                span,
+            )),
             Rule::continue_stmt => {
                 self.flow = AstBuildFlow::Continue;
-                Ok(Stmt::ContinueStmt)
+                let label = self.jump_label(unknown_pair)?;
+                Ok(Stmt::ContinueStmt(label, span))
             }
             Rule::break_stmt => {
                 self.flow = AstBuildFlow::Break;
-                Ok(Stmt::BreakStmt)
+                let label = self.jump_label(unknown_pair)?;
+                Ok(Stmt::BreakStmt(label, span))
             }
             Rule::return_stmt => {
                 self.flow = AstBuildFlow::Return;
-                Ok(Stmt::ReturnStmt(self.return_stmt(unknown_pair)?))
+                Ok(Stmt::ReturnStmt(self.return_stmt(unknown_pair)?, span))
+            }
+            Rule::error_stmt => {
+                let region = self
+                    .regions
+                    .iter()
+                    .find(|region| region.start == span.start);
+                match region {
+                    Some(region) => Ok(Stmt::Error(*region)),
+                    None if self.recovering => {
+                        self.errors.push((ParseError::PestParser, span));
+                        Ok(Stmt::Error(span))
+                    }
+                    None => Err(ParseError::PestParser), // NUL in the source
+                }
             }
             _ => unreachable!(),
         }
@@ -108,15 +319,30 @@ impl AstBuilder {
 
     fn block_stmt(&mut self, block_stmt_pair: Pair<Rule>) -> Result<BlockStmt, ParseError> {
         let mut block_stmt = vec![];
-        for stmt_pair in block_stmt_pair.into_inner() {
+        let mut stmt_pairs = block_stmt_pair.into_inner().peekable();
+        while let Some(stmt_pair) = stmt_pairs.next() {
             let stmt = self.stmt(stmt_pair)?;
 
-            // Skip building ASTs after these control flow statements
-            if let Stmt::ContinueStmt | Stmt::BreakStmt | Stmt::ReturnStmt(_) = stmt {
-                block_stmt.push(stmt);
-                break;
+            // The statements after these control flow statements are kept apart as unreachable,
+            // without changing the flow
+            let jumps = matches!(
+                stmt,
+                Stmt::ContinueStmt(..) | Stmt::BreakStmt(..) | Stmt::ReturnStmt(..)
+            );
+            block_stmt.push(stmt);
+            if jumps && stmt_pairs.peek().is_some() {
+                let flow = std::mem::replace(&mut self.flow, AstBuildFlow::Value);
+                let mut unreachable = vec![];
+                for stmt_pair in stmt_pairs.by_ref() {
+                    unreachable.push(self.stmt(stmt_pair)?);
+                }
+                let span = Span::new(
+                    unreachable.first().unwrap().span().start,
+                    unreachable.last().unwrap().span().end,
+                );
+                block_stmt.push(Stmt::Unreachable(unreachable, span));
+                self.flow = flow;
             }
-            block_stmt.push(stmt)
         }
         Ok(block_stmt)
     }
@@ -126,17 +352,43 @@ impl AstBuilder {
         let ident_func = self.ident(func_decl_inner.next().unwrap())?;
 
         let mut ident_param = vec![];
+        let mut annotation_param = vec![];
+        let mut default_param = vec![];
+        let mut annotation_return = None;
         loop {
             let unknown_pair = func_decl_inner.next().unwrap();
             match unknown_pair.as_rule() {
                 Rule::IDENT => {
                     ident_param.push(self.ident(unknown_pair)?);
+                    annotation_param.push(None);
+                    default_param.push(None);
+                }
+                Rule::TYPE => {
+                    *annotation_param.last_mut().unwrap() = Some(self.annotation(unknown_pair));
+                }
+                Rule::expr => {
+                    *default_param.last_mut().unwrap() = Some(self.expr(unknown_pair)?);
+                }
+                Rule::func_decl_return => {
+                    let type_pair = unknown_pair.into_inner().next().unwrap();
+                    annotation_return = Some(self.annotation(type_pair));
                 }
                 Rule::block_stmt => {
+                    // A parameter with no default value cannot follow one with it
+                    if let Some(first) = default_param.iter().position(Option::is_some) {
+                        for (ident, default) in ident_param.iter().zip(&default_param).skip(first) {
+                            if default.is_none() {
+                                self.invalid(ident.span)?;
+                            }
+                        }
+                    }
                     let block = self.func_block_stmt(unknown_pair)?;
                     return Ok(FuncDecl {
                         ident_func,
                         ident_param,
+                        annotation_param,
+                        default_param,
+                        annotation_return,
                         block,
                     });
                 }
@@ -149,7 +401,10 @@ impl AstBuilder {
         &mut self,
         func_block_stmt_pair: Pair<Rule>,
     ) -> Result<BlockStmt, ParseError> {
+        // Loops outside of the function cannot be jumped to from it
+        let labels = std::mem::take(&mut self.labels);
         let block_stmt = self.block_stmt(func_block_stmt_pair);
+        self.labels = labels;
         if let AstBuildFlow::Return = self.flow {
             self.flow = AstBuildFlow::Value;
         };
@@ -165,10 +420,7 @@ impl AstBuilder {
                 let unknown_pair = if_stmt_else_clause_pair.into_inner().next().unwrap();
                 match unknown_pair.as_rule() {
                     Rule::block_stmt => {
-                        let mut block_stmt = vec![];
-                        for stmt_pair in unknown_pair.into_inner() {
-                            block_stmt.push(self.stmt(stmt_pair)?)
-                        }
+                        let block_stmt = self.block_stmt(unknown_pair)?;
                         Ok(IfStmt {
                             condition,
                             block,
@@ -194,98 +446,30 @@ impl AstBuilder {
     }
 
     fn for_stmt(&mut self, for_stmt_pair: Pair<Rule>) -> Result<ForStmt, ParseError> {
-        // TODO: Refactor
-        let mut for_stmt_inner = for_stmt_pair.into_inner();
-        let unknown_pair = for_stmt_inner.next().unwrap();
-        match unknown_pair.as_rule() {
-            Rule::for_stmt_init => {
-                let init = Some(self.for_stmt_init(unknown_pair)?);
-                let unknown_pair = for_stmt_inner.next().unwrap();
-                match unknown_pair.as_rule() {
-                    Rule::for_stmt_cond => {
-                        let cond = Some(self.expr(unknown_pair)?);
-                        let unknown_pair = for_stmt_inner.next().unwrap();
-                        match unknown_pair.as_rule() {
-                            Rule::for_stmt_afterthought => {
-                                let afterthought = Some(self.expr(unknown_pair)?);
-                                let block =
-                                    self.block_stmt_of_loop(for_stmt_inner.next().unwrap())?;
-                                Ok(ForStmt {
-                                    init,
-                                    cond,
-                                    afterthought,
-                                    block,
-                                })
-                            }
-                            Rule::block_stmt => Ok(ForStmt {
-                                init,
-                                cond,
-                                afterthought: None,
-                                block: self.block_stmt_of_loop(unknown_pair)?,
-                            }),
-                            _ => unreachable!(),
-                        }
-                    }
-                    Rule::for_stmt_afterthought => {
-                        let afterthought = Some(self.expr(unknown_pair)?);
-                        let block = self.block_stmt_of_loop(for_stmt_inner.next().unwrap())?;
-                        Ok(ForStmt {
-                            init,
-                            cond: None,
-                            afterthought,
-                            block,
-                        })
-                    }
-                    Rule::block_stmt => Ok(ForStmt {
+        let mut label = None;
+        let mut init = None;
+        let mut cond = None;
+        let mut afterthought = None;
+        for unknown_pair in for_stmt_pair.into_inner() {
+            match unknown_pair.as_rule() {
+                Rule::label => label = Some(self.label(unknown_pair)?),
+                Rule::for_stmt_init => init = Some(self.for_stmt_init(unknown_pair)?),
+                Rule::for_stmt_cond => cond = Some(self.expr(unknown_pair)?),
+                Rule::for_stmt_afterthought => afterthought = Some(self.expr(unknown_pair)?),
+                Rule::block_stmt => {
+                    let block = self.block_stmt_of_loop(unknown_pair, &label)?;
+                    return Ok(ForStmt {
+                        label,
                         init,
-                        cond: None,
-                        afterthought: None,
-                        block: self.block_stmt_of_loop(unknown_pair)?,
-                    }),
-                    _ => unreachable!(),
-                }
-            }
-            Rule::for_stmt_cond => {
-                let cond = Some(self.expr(unknown_pair)?);
-                let unknown_pair = for_stmt_inner.next().unwrap();
-                match unknown_pair.as_rule() {
-                    Rule::for_stmt_afterthought => {
-                        let afterthought = Some(self.expr(unknown_pair)?);
-                        let block = self.block_stmt_of_loop(for_stmt_inner.next().unwrap())?;
-                        Ok(ForStmt {
-                            init: None,
-                            cond,
-                            afterthought,
-                            block,
-                        })
-                    }
-                    Rule::block_stmt => Ok(ForStmt {
-                        init: None,
                         cond,
-                        afterthought: None,
-                        block: self.block_stmt_of_loop(unknown_pair)?,
-                    }),
-                    _ => unreachable!(),
+                        afterthought,
+                        block,
+                    });
                 }
+                _ => unreachable!(),
             }
-            Rule::for_stmt_afterthought => {
-                let afterthought = Some(self.expr(unknown_pair)?);
-                let block = self.block_stmt_of_loop(for_stmt_inner.next().unwrap())?;
-                Ok(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought,
-                    block,
-                })
-            }
-            Rule::block_stmt => Ok(ForStmt {
-                init: None,
-                cond: None,
-                afterthought: None,
-                block: self.block_stmt_of_loop(unknown_pair)?,
-            }),
-            _ => unreachable!(),
         }
+        unreachable!()
     }
 
     fn for_stmt_init(&self, for_stmt_init_pair: Pair<Rule>) -> Result<ForStmtInit, ParseError> {
@@ -293,46 +477,193 @@ impl AstBuilder {
         let unknown_pair = for_stmt_init_inner.next().unwrap();
         if let Rule::IDENT = unknown_pair.as_rule() {
             let ident = self.ident(unknown_pair)?;
-            let expr = Some(self.expr(for_stmt_init_inner.next().unwrap())?);
-            Ok(ForStmtInit::Var(VarStmt { ident, expr }))
+            let mut expr_pair = for_stmt_init_inner.next().unwrap();
+            let mut annotation = None;
+            if let Rule::TYPE = expr_pair.as_rule() {
+                annotation = Some(self.annotation(expr_pair));
+                expr_pair = for_stmt_init_inner.next().unwrap();
+            }
+            let expr = Some(self.expr(expr_pair)?);
+            Ok(ForStmtInit::Var(VarStmt {
+                is_const: false,
+                ident,
+                annotation,
+                expr,
+            }))
         } else {
             let expr = self.expr(unknown_pair)?;
             Ok(ForStmtInit::Expr(expr))
         }
     }
 
-    fn block_stmt_of_loop(&mut self, block_stmt_pair: Pair<Rule>) -> Result<BlockStmt, ParseError> {
-        let block_stmt = self.block_stmt(block_stmt_pair)?;
+    fn block_stmt_of_loop(
+        &mut self,
+        block_stmt_pair: Pair<Rule>,
+        label: &Option<Ident>,
+    ) -> Result<BlockStmt, ParseError> {
+        if let Some(label) = label {
+            if self.labels.contains(&label.name) {
+                self.invalid(label.span)?; // The inner loop could never be jumped to
+            }
+            self.labels.push(label.name.clone());
+        }
+        let block_stmt = self.block_stmt(block_stmt_pair);
+        if label.is_some() {
+            self.labels.pop();
+        }
         if let AstBuildFlow::Continue | AstBuildFlow::Break = self.flow {
             self.flow = AstBuildFlow::Value;
         };
-        Ok(block_stmt)
+        block_stmt
     }
 
     fn while_stmt(&mut self, while_stmt_pair: Pair<Rule>) -> Result<WhileStmt, ParseError> {
         let mut while_stmt_inner = while_stmt_pair.into_inner();
+        let label = self.loop_label(&mut while_stmt_inner)?;
         let cond = while_stmt_inner.next().map(|p| self.expr(p)).unwrap()?;
-        let block = while_stmt_inner.next().map(|p| self.block_stmt_of_loop(p)).unwrap()?;
-        Ok(WhileStmt {
-            cond,
-            block
+        let block = self.block_stmt_of_loop(while_stmt_inner.next().unwrap(), &label)?;
+        Ok(WhileStmt { label, cond, block })
+    }
+
+    fn do_while_stmt(&mut self, do_while_stmt_pair: Pair<Rule>) -> Result<DoWhileStmt, ParseError> {
+        let mut do_while_stmt_inner = do_while_stmt_pair.into_inner();
+        let label = self.loop_label(&mut do_while_stmt_inner)?;
+        let block = self.block_stmt_of_loop(do_while_stmt_inner.next().unwrap(), &label)?;
+        let cond = self.expr(do_while_stmt_inner.next().unwrap())?;
+        Ok(DoWhileStmt { label, block, cond })
+    }
+
+    fn switch_stmt(&mut self, switch_stmt_pair: Pair<Rule>) -> Result<SwitchStmt, ParseError> {
+        let mut switch_stmt_inner = switch_stmt_pair.into_inner();
+        let subject = self.expr(switch_stmt_inner.next().unwrap())?;
+        let mut cases: Vec<SwitchStmtCase> = vec![];
+        let mut default = None;
+        for unknown_pair in switch_stmt_inner {
+            let rule = unknown_pair.as_rule();
+            let mut unknown_inner = unknown_pair.into_inner();
+            match rule {
+                Rule::switch_stmt_case => {
+                    let value_pair = unknown_inner.next().unwrap();
+                    let span = self.span(&value_pair);
+                    let literal = value_pair.as_str().to_string();
+                    // An int beyond 64 bits leaves the case out
+                    let Some(value) = self.switch_stmt_case_value(value_pair) else {
+                        self.invalid(span)?;
+                        self.block_stmt(unknown_inner.next().unwrap())?;
+                        continue;
+                    };
+                    if cases
+                        .iter()
+                        .any(|case| same_case_value(&case.value, &value))
+                    {
+                        // The case could never be run
+                        self.reject(ParseError::DuplicateCase(literal), span)?;
+                    }
+                    let block = self.block_stmt(unknown_inner.next().unwrap())?;
+                    cases.push(SwitchStmtCase { value, block });
+                }
+                Rule::switch_stmt_default => {
+                    default = Some(self.block_stmt(unknown_inner.next().unwrap())?);
+                }
+                _ => unreachable!(),
+            }
+        }
+        Ok(SwitchStmt {
+            subject,
+            cases,
+            default,
         })
     }
 
+    // Literal of a case, or none for an int beyond 64 bits, which is a syntax error rather than out
+    // of range as cases are never evaluated
+    fn switch_stmt_case_value(&self, value_pair: Pair<Rule>) -> Option<Expr> {
+        let mut value_inner = value_pair.into_inner();
+        let unknown_pair = value_inner.next().unwrap();
+        match unknown_pair.as_rule() {
+            Rule::NEG => {
+                let int = value_inner.next().unwrap().as_str().parse().ok()?;
+                Some(Expr::prefix(PrefixOpKind::Neg, Expr::literal_int(int)))
+            }
+            Rule::BOOL_LITERAL => Some(Expr::literal_bool(unknown_pair.as_str().parse().unwrap())),
+            Rule::INT_LITERAL => Some(Expr::literal_int(unknown_pair.as_str().parse().ok()?)),
+            Rule::STRING_LITERAL => Some(Expr::literal_string(unknown_pair.as_str().to_string())),
+            _ => unreachable!(),
+        }
+    }
+
+    fn label(&self, label_pair: Pair<Rule>) -> Result<Ident, ParseError> {
+        self.ident(label_pair.into_inner().next().unwrap())
+    }
+
+    fn loop_label(&self, loop_inner: &mut Pairs<Rule>) -> Result<Option<Ident>, ParseError> {
+        match loop_inner.peek() {
+            Some(pair) if pair.as_rule() == Rule::label => {
+                self.label(loop_inner.next().unwrap()).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // Label of `break` or `continue`, which must be of a loop enclosing it
+    fn jump_label(&mut self, jump_pair: Pair<Rule>) -> Result<Option<Ident>, ParseError> {
+        let Some(ident_pair) = jump_pair.into_inner().next() else {
+            return Ok(None);
+        };
+        let ident = self.ident(ident_pair)?;
+        if !self.labels.contains(&ident.name) {
+            self.invalid(ident.span)?;
+        }
+        Ok(Some(ident))
+    }
+
+    // Error which is only recorded when recovering, so that the rest is still built
+    fn invalid(&mut self, span: Span) -> Result<(), ParseError> {
+        self.reject(ParseError::BuildAst, span)
+    }
+
+    fn reject(&mut self, error: ParseError, span: Span) -> Result<(), ParseError> {
+        if !self.recovering {
+            return Err(error);
+        }
+        self.errors.push((error, span));
+        Ok(())
+    }
+
     fn var_stmt(&self, pair: Pair<Rule>) -> Result<VarStmt, ParseError> {
         let mut var_stmt_inner = pair.into_inner();
-        let ident_pair = var_stmt_inner.next().unwrap();
-        let expr = if let Some(expr_pair) = var_stmt_inner.next() {
-            Some(self.expr(expr_pair)?)
-        } else {
-            None
-        };
+        let mut ident_pair = var_stmt_inner.next().unwrap();
+        let is_const = ident_pair.as_rule() == Rule::CONST;
+        if is_const {
+            ident_pair = var_stmt_inner.next().unwrap();
+        }
+        let mut annotation = None;
+        let mut expr = None;
+        for unknown_pair in var_stmt_inner {
+            match unknown_pair.as_rule() {
+                Rule::TYPE => annotation = Some(self.annotation(unknown_pair)),
+                _ => expr = Some(self.expr(unknown_pair)?),
+            }
+        }
         Ok(VarStmt {
+            is_const,
             ident: self.ident(ident_pair)?,
+            annotation,
             expr,
         })
     }
 
+    fn annotation(&self, type_pair: Pair<Rule>) -> TypeAnnotation {
+        match type_pair.as_str() {
+            "int" => TypeAnnotation::Int,
+            "bool" => TypeAnnotation::Bool,
+            "string" => TypeAnnotation::String,
+            "array" => TypeAnnotation::Array,
+            "none" => TypeAnnotation::None,
+            _ => unreachable!(),
+        }
+    }
+
     fn return_stmt(&self, return_stmt_pair: Pair<Rule>) -> Result<ReturnStmt, ParseError> {
         let mut return_stmt_inner = return_stmt_pair.into_inner();
         let expr = if let Some(expr_pair) = return_stmt_inner.next() {
@@ -375,6 +706,7 @@ impl AstBuilder {
                     Rule::POS => PrefixOpKind::Pos,
                     Rule::NEG => PrefixOpKind::Neg,
                     Rule::NOT => PrefixOpKind::Not,
+                    Rule::BIT_NOT => PrefixOpKind::BitNot,
                     _rule => unreachable!(),
                 };
                 Ok(Expr::PrefixOp {
@@ -384,11 +716,16 @@ impl AstBuilder {
             })
             .map_infix(|lhs, infix_pair, rhs| {
                 let infix = match infix_pair.as_rule() {
+                    Rule::CONDITIONAL => {
+                        let then = self.expr(infix_pair.into_inner().next().unwrap())?;
+                        return Ok(Expr::conditional(lhs?, then, rhs?));
+                    }
                     Rule::ADD => InfixOpKind::Add,
                     Rule::SUBTRACT => InfixOpKind::Subtract,
                     Rule::MULTIPLY => InfixOpKind::Multiply,
                     Rule::DIVIDE => InfixOpKind::Divide,
                     Rule::MODULO => InfixOpKind::Modulo,
+                    Rule::POWER => InfixOpKind::Power,
                     Rule::EQ => InfixOpKind::Eq,
                     Rule::NOT_EQ => InfixOpKind::NotEq,
                     Rule::GE => InfixOpKind::Ge,
@@ -397,7 +734,17 @@ impl AstBuilder {
                     Rule::LT => InfixOpKind::Lt,
                     Rule::AND => InfixOpKind::And,
                     Rule::OR => InfixOpKind::Or,
+                    Rule::BIT_AND => InfixOpKind::BitAnd,
+                    Rule::BIT_OR => InfixOpKind::BitOr,
+                    Rule::BIT_XOR => InfixOpKind::BitXor,
+                    Rule::SHIFT_LEFT => InfixOpKind::ShiftLeft,
+                    Rule::SHIFT_RIGHT => InfixOpKind::ShiftRight,
                     Rule::ASSIGN => InfixOpKind::Assign,
+                    Rule::ADD_ASSIGN => InfixOpKind::AddAssign,
+                    Rule::SUBTRACT_ASSIGN => InfixOpKind::SubtractAssign,
+                    Rule::MULTIPLY_ASSIGN => InfixOpKind::MultiplyAssign,
+                    Rule::DIVIDE_ASSIGN => InfixOpKind::DivideAssign,
+                    Rule::MODULO_ASSIGN => InfixOpKind::ModuloAssign,
                     _ => unreachable!(),
                 };
                 Ok(Expr::InfixOp {
@@ -430,12 +777,19 @@ impl AstBuilder {
     fn ident(&self, ident_pair: Pair<Rule>) -> Result<Ident, ParseError> {
         Ok(Ident {
             name: ident_pair.as_str().to_string(),
+            span: self.span(&ident_pair),
         })
     }
+
+    fn span(&self, pair: &Pair<Rule>) -> Span {
+        let span = pair.as_span();
+        Span::new(span.start(), span.end())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::ast::without_spans;
     use crate::error::*;
     use crate::parser::*;
 
@@ -601,13 +955,123 @@ mod tests {
             while(){}
             "#,
                 Err(ParseError::PestParser),
-            ),        ];
+            ),
+        ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn errors_are_recovered_from() {
+        // Source of each top-level statement, and of each statement in the first function
+        fn outline(src: &str, program: &Program) -> Vec<String> {
+            let text = |stmt: &Stmt| match stmt {
+                Stmt::Error(span) => format!("error: {}", &src[span.start..span.end]),
+                _ => src[stmt.span().start..stmt.span().end.min(src.len())].to_string(),
+            };
+            let mut outline: Vec<String> = program.iter().map(text).collect();
+            if let Some(Stmt::FuncDecl(FuncDecl { block, .. }, _)) = program.first() {
+                outline.extend(block.iter().map(|stmt| format!("in f: {}", text(stmt))));
+            }
+            outline
+        }
+
+        let tests = [
+            ("func f() { return; }\n", vec![], vec!["func f() { return; }", "in f: return;"]),
+            (
+                "var x = 1;\nvar = 2;\nx;\n",
+                vec![(ParseError::PestParser, 15, 15)],
+                vec!["var x = 1;", "error: var = 2;", "x;"],
+            ),
+            (
+                "var x = 1;\nif (x) {\n    break;\n}\n",
+                vec![(ParseError::BuildAst, 11, 32)],
+                vec!["var x = 1;", "if (x) {\n    break;\n}"],
+            ),
+            (
+                "func f(a) {\n    var y = a +;\n    y;\n}\n}\nprint(f(1));\n",
+                vec![(ParseError::PestParser, 27, 27), (ParseError::PestParser, 38, 38)],
+                vec![
+                    "func f(a) {\n    var y = a +;\n    y;\n}",
+                    "error: }",
+                    "print(f(1));",
+                    "in f: error: var y = a +;",
+                    "in f: y;",
+                ],
+            ),
+            (
+                "func f(a) {\n    var s = \"};\"; // }\n    for (var i = 0; i < ; i = i + 1) { a; }\n    pri\n",
+                vec![
+                    (ParseError::PestParser, 59, 59),
+                    (ParseError::PestParser, 87, 87),
+                ],
+                vec![
+                    "func f(a) {\n    var s = \"};\"; // }\n    for (var i = 0; i < ; i = i + 1) { a; }\n    pri\n",
+                    "in f: var s = \"};\";",
+                    "in f: error: for (var i = 0; i < ; i = i + 1) { a; }",
+                    "in f: error: pri\n",
+                ],
+            ),
+            (
+                "switch (x) {\n    case 1: {}\n    case 1: {}\n}\n",
+                vec![(ParseError::DuplicateCase("1".to_string()), 37, 38)],
+                vec!["switch (x) {\n    case 1: {}\n    case 1: {}\n}"],
+            ),
+            (
+                "switch (x) {\n    case 99999999999999999999: {}\n}\nprint(x);\n",
+                vec![(ParseError::BuildAst, 22, 42)],
+                vec!["switch (x) {\n    case 99999999999999999999: {}\n}", "print(x);"],
+            ),
+            (
+                "while (x) {\n    break outer;\n}\n",
+                vec![(ParseError::BuildAst, 22, 27)],
+                vec!["while (x) {\n    break outer;\n}"],
+            ),
+            (
+                "var x = 1;\n/* never closed",
+                vec![(ParseError::PestParser, 11, 11)],
+                vec!["var x = 1;", "error: /* never closed"],
+            ),
+        ];
+        for (src, expected_errors, expected_outline) in tests {
+            let (program, errors) = parse_recovering(src);
+            let errors: Vec<_> = errors
+                .into_iter()
+                .map(|(parse_err, span)| (parse_err, span.start, span.end))
+                .collect();
+            assert_eq!(errors, expected_errors, "Failed in test case: {}", src);
+            assert_eq!(
+                outline(src, &program),
+                expected_outline,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
+    #[test]
+    fn span_is_recorded() {
+        let src = "var x = 1; // comment\nif (x == 1) {\n    x;\n}\n";
+        let program = parse(src).unwrap();
+        let spans: Vec<&str> = program
+            .iter()
+            .map(|stmt| &src[stmt.span().start..stmt.span().end])
+            .collect();
+        assert_eq!(spans, vec!["var x = 1;", "if (x == 1) {\n    x;\n}"]);
+
+        let Stmt::VarStmt(VarStmt { ident, .. }, _) = &program[0] else {
+            unreachable!()
+        };
+        assert_eq!((ident.span.start, ident.span.end), (4, 5));
+    }
+
     #[test]
     fn block_stmt_ast_is_built_correctly() {
         let tests = [
@@ -616,7 +1080,7 @@ mod tests {
                 r#"
             {}
             "#,
-                Ok(vec![Stmt::BlockStmt(vec![])]),
+                Ok(vec![Stmt::BlockStmt(vec![], Span::default())]),
             ),
             (
                 r#"
@@ -629,11 +1093,17 @@ mod tests {
             }
             "#,
                 Ok(vec![
-                    Stmt::ExprStmt(Expr::literal_int(0)),
-                    Stmt::BlockStmt(vec![
-                        Stmt::ExprStmt(Expr::literal_int(1)),
-                        Stmt::BlockStmt(vec![Stmt::ExprStmt(Expr::literal_int(2))]),
-                    ]),
+                    Stmt::ExprStmt(Expr::literal_int(0), Span::default()),
+                    Stmt::BlockStmt(
+                        vec![
+                            Stmt::ExprStmt(Expr::literal_int(1), Span::default()),
+                            Stmt::BlockStmt(
+                                vec![Stmt::ExprStmt(Expr::literal_int(2), Span::default())],
+                                Span::default(),
+                            ),
+                        ],
+                        Span::default(),
+                    ),
                 ]),
             ),
             (
@@ -663,7 +1133,12 @@ mod tests {
         ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -674,27 +1149,42 @@ mod tests {
                 r#"
             func foo(){}
             "#,
-                Ok(vec![Stmt::FuncDecl(FuncDecl {
-                    ident_func: Ident {
-                        name: "foo".to_string(),
+                Ok(vec![Stmt::FuncDecl(
+                    FuncDecl {
+                        ident_func: Ident {
+                            name: "foo".to_string(),
+                            span: Span::default(),
+                        },
+                        ident_param: vec![],
+                        annotation_param: vec![],
+                        default_param: vec![],
+                        annotation_return: None,
+                        block: vec![],
                     },
-                    ident_param: vec![],
-                    block: vec![],
-                })]),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             func foo(param1){}
             "#,
-                Ok(vec![Stmt::FuncDecl(FuncDecl {
-                    ident_func: Ident {
-                        name: "foo".to_string(),
+                Ok(vec![Stmt::FuncDecl(
+                    FuncDecl {
+                        ident_func: Ident {
+                            name: "foo".to_string(),
+                            span: Span::default(),
+                        },
+                        ident_param: vec![Ident {
+                            name: "param1".to_string(),
+                            span: Span::default(),
+                        }],
+                        annotation_param: vec![None],
+                        default_param: vec![None],
+                        annotation_return: None,
+                        block: vec![],
                     },
-                    ident_param: vec![Ident {
-                        name: "param1".to_string(),
-                    }],
-                    block: vec![],
-                })]),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
@@ -703,38 +1193,160 @@ mod tests {
                 return x;
             }
             "#,
-                Ok(vec![Stmt::FuncDecl(FuncDecl {
-                    ident_func: Ident {
-                        name: "foo".to_string(),
+                Ok(vec![Stmt::FuncDecl(
+                    FuncDecl {
+                        ident_func: Ident {
+                            name: "foo".to_string(),
+                            span: Span::default(),
+                        },
+                        ident_param: vec![Ident {
+                            name: "param".to_string(),
+                            span: Span::default(),
+                        }],
+                        annotation_param: vec![None],
+                        default_param: vec![None],
+                        annotation_return: None,
+                        block: vec![
+                            Stmt::ExprStmt(Expr::literal_int(123), Span::default()),
+                            Stmt::ReturnStmt(
+                                ReturnStmt {
+                                    expr: Some(Expr::Ident(Ident {
+                                        name: "x".to_string(),
+                                        span: Span::default(),
+                                    })),
+                                },
+                                Span::default(),
+                            ),
+                        ],
                     },
-                    ident_param: vec![Ident {
-                        name: "param".to_string(),
-                    }],
-                    block: vec![
-                        Stmt::ExprStmt(Expr::literal_int(123)),
-                        Stmt::ReturnStmt(ReturnStmt {
-                            expr: Some(Expr::Ident(Ident {
-                                name: "x".to_string(),
-                            })),
-                        }),
-                    ],
-                })]),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             func foo(){
                 return;
-                // Following statements will be ignored by parser
+                // Following statements are kept as unreachable
                 123;
             }
             "#,
-                Ok(vec![Stmt::FuncDecl(FuncDecl {
-                    ident_func: Ident {
-                        name: "foo".to_string(),
+                Ok(vec![Stmt::FuncDecl(
+                    FuncDecl {
+                        ident_func: Ident {
+                            name: "foo".to_string(),
+                            span: Span::default(),
+                        },
+                        ident_param: vec![],
+                        annotation_param: vec![],
+                        default_param: vec![],
+                        annotation_return: None,
+                        block: vec![
+                            Stmt::ReturnStmt(ReturnStmt { expr: None }, Span::default()),
+                            Stmt::Unreachable(
+                                vec![Stmt::ExprStmt(Expr::literal_int(123), Span::default())],
+                                Span::default(),
+                            ),
+                        ],
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            func foo(a: int, b, c: none): bool {}
+            "#,
+                Ok(vec![Stmt::FuncDecl(
+                    FuncDecl {
+                        ident_func: Ident {
+                            name: "foo".to_string(),
+                            span: Span::default(),
+                        },
+                        ident_param: vec![
+                            Ident {
+                                name: "a".to_string(),
+                                span: Span::default(),
+                            },
+                            Ident {
+                                name: "b".to_string(),
+                                span: Span::default(),
+                            },
+                            Ident {
+                                name: "c".to_string(),
+                                span: Span::default(),
+                            },
+                        ],
+                        annotation_param: vec![
+                            Some(TypeAnnotation::Int),
+                            None,
+                            Some(TypeAnnotation::None),
+                        ],
+                        default_param: vec![None, None, None],
+                        annotation_return: Some(TypeAnnotation::Bool),
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            func foo(a, b: int = a * 2, c = []) {}
+            "#,
+                Ok(vec![Stmt::FuncDecl(
+                    FuncDecl {
+                        ident_func: Ident {
+                            name: "foo".to_string(),
+                            span: Span::default(),
+                        },
+                        ident_param: vec![
+                            Ident {
+                                name: "a".to_string(),
+                                span: Span::default(),
+                            },
+                            Ident {
+                                name: "b".to_string(),
+                                span: Span::default(),
+                            },
+                            Ident {
+                                name: "c".to_string(),
+                                span: Span::default(),
+                            },
+                        ],
+                        annotation_param: vec![None, Some(TypeAnnotation::Int), None],
+                        default_param: vec![
+                            None,
+                            Some(Expr::InfixOp {
+                                kind: InfixOpKind::Multiply,
+                                lhs: Box::new(Expr::Ident(Ident {
+                                    name: "a".to_string(),
+                                    span: Span::default(),
+                                })),
+                                rhs: Box::new(Expr::literal_int(2)),
+                            }),
+                            Some(Expr::literal_array(vec![])),
+                        ],
+                        annotation_return: None,
+                        block: vec![],
                     },
-                    ident_param: vec![],
-                    block: vec![Stmt::ReturnStmt(ReturnStmt { expr: None })],
-                })]),
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            func foo(a = 1, b) {}
+            "#,
+                Err(ParseError::BuildAst),
+            ),
+            (
+                r#"
+            func foo(a = ) {}
+            "#,
+                Err(ParseError::PestParser),
+            ),
+            (
+                r#"
+            func foo(): {}
+            "#,
+                Err(ParseError::PestParser),
             ),
             (
                 r#"
@@ -757,7 +1369,12 @@ mod tests {
         ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -776,25 +1393,34 @@ mod tests {
                 if (true) {}
             }
             "#,
-                Ok(vec![Stmt::IfStmt(IfStmt {
-                    condition: Expr::literal_bool(true),
-                    block: vec![Stmt::IfStmt(IfStmt {
+                Ok(vec![Stmt::IfStmt(
+                    IfStmt {
                         condition: Expr::literal_bool(true),
-                        block: vec![],
+                        block: vec![Stmt::IfStmt(
+                            IfStmt {
+                                condition: Expr::literal_bool(true),
+                                block: vec![],
+                                else_clause: None,
+                            },
+                            Span::default(),
+                        )],
                         else_clause: None,
-                    })],
-                    else_clause: None,
-                })]),
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             if(true) {} else {}
             "#,
-                Ok(vec![Stmt::IfStmt(IfStmt {
-                    condition: Expr::literal_bool(true),
-                    block: vec![],
-                    else_clause: Some(IfStmtElseClause::IfStmtBlock(vec![])),
-                })]),
+                Ok(vec![Stmt::IfStmt(
+                    IfStmt {
+                        condition: Expr::literal_bool(true),
+                        block: vec![],
+                        else_clause: Some(IfStmtElseClause::IfStmtBlock(vec![])),
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
@@ -802,15 +1428,18 @@ mod tests {
             } else if(false) {
             }
             "#,
-                Ok(vec![Stmt::IfStmt(IfStmt {
-                    condition: Expr::literal_bool(true),
-                    block: vec![],
-                    else_clause: Some(IfStmtElseClause::IfStmt(Box::new(IfStmt {
-                        condition: Expr::literal_bool(false),
+                Ok(vec![Stmt::IfStmt(
+                    IfStmt {
+                        condition: Expr::literal_bool(true),
                         block: vec![],
-                        else_clause: None,
-                    }))),
-                })]),
+                        else_clause: Some(IfStmtElseClause::IfStmt(Box::new(IfStmt {
+                            condition: Expr::literal_bool(false),
+                            block: vec![],
+                            else_clause: None,
+                        }))),
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
@@ -820,19 +1449,22 @@ mod tests {
             } else {
             }
             "#,
-                Ok(vec![Stmt::IfStmt(IfStmt {
-                    condition: Expr::literal_bool(true),
-                    block: vec![],
-                    else_clause: Some(IfStmtElseClause::IfStmt(Box::new(IfStmt {
-                        condition: Expr::literal_bool(false),
+                Ok(vec![Stmt::IfStmt(
+                    IfStmt {
+                        condition: Expr::literal_bool(true),
                         block: vec![],
                         else_clause: Some(IfStmtElseClause::IfStmt(Box::new(IfStmt {
                             condition: Expr::literal_bool(false),
                             block: vec![],
-                            else_clause: Some(IfStmtElseClause::IfStmtBlock(vec![])),
+                            else_clause: Some(IfStmtElseClause::IfStmt(Box::new(IfStmt {
+                                condition: Expr::literal_bool(false),
+                                block: vec![],
+                                else_clause: Some(IfStmtElseClause::IfStmtBlock(vec![])),
+                            }))),
                         }))),
-                    }))),
-                })]),
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
@@ -861,7 +1493,12 @@ mod tests {
         ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -872,254 +1509,371 @@ mod tests {
                 r#"
             for(; ; ){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: None,
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: None,
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(; ; ){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: None,
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: None,
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(; ; 1){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(; ; 1){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(; 1; ){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: None,
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: None,
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(; 1; ){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: None,
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
-            ),
-            (
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: None,
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
                 r#"
             for(; 1; 1){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(; 1; 1){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; ; ){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: None,
-                    afterthought: None,
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: None,
+                        afterthought: None,
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; ; ){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: None,
-                    afterthought: None,
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: None,
+                        afterthought: None,
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; ; 1){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: None,
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: None,
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; ; 1){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: None,
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: None,
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; 1; ){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: None,
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: None,
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; 1; ){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: None,
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: None,
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; 1; 1){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(1; 1; 1){1;}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
-                    cond: Some(Expr::literal_int(1)),
-                    afterthought: Some(Expr::literal_int(1)),
-                    block: vec![Stmt::ExprStmt(Expr::literal_int(1))],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Expr(Expr::literal_int(1))),
+                        cond: Some(Expr::literal_int(1)),
+                        afterthought: Some(Expr::literal_int(1)),
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(var i = 1; ; ){}
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: Some(ForStmtInit::Var(VarStmt {
-                        ident: Ident {
-                            name: "i".to_string(),
-                        },
-                        expr: Some(Expr::literal_int(1)),
-                    })),
-                    cond: None,
-                    afterthought: None,
-                    block: vec![],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: Some(ForStmtInit::Var(VarStmt {
+                            is_const: false,
+                            ident: Ident {
+                                name: "i".to_string(),
+                                span: Span::default(),
+                            },
+                            annotation: None,
+                            expr: Some(Expr::literal_int(1)),
+                        })),
+                        cond: None,
+                        afterthought: None,
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(;;){
                 break;
-                continue;  // ignored by parser
+                continue;  // Unreachable
             }
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: None,
-                    block: vec![Stmt::BreakStmt],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: None,
+                        block: vec![
+                            Stmt::BreakStmt(None, Span::default()),
+                            Stmt::Unreachable(
+                                vec![Stmt::ContinueStmt(None, Span::default())],
+                                Span::default(),
+                            ),
+                        ],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(;;){
                 continue;
-                break;  // Ignored by parser
+                break;  // Unreachable
             }
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: None,
-                    block: vec![Stmt::ContinueStmt],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: None,
+                        block: vec![
+                            Stmt::ContinueStmt(None, Span::default()),
+                            Stmt::Unreachable(
+                                vec![Stmt::BreakStmt(None, Span::default())],
+                                Span::default(),
+                            ),
+                        ],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(;;){
                 {
                     continue;
-                    break;  // Ignored by parser
+                    break;  // Unreachable
 
                 }
             }
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: None,
-                    block: vec![Stmt::BlockStmt(vec![Stmt::ContinueStmt])],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: None,
+                        block: vec![Stmt::BlockStmt(
+                            vec![
+                                Stmt::ContinueStmt(None, Span::default()),
+                                Stmt::Unreachable(
+                                    vec![Stmt::BreakStmt(None, Span::default())],
+                                    Span::default(),
+                                ),
+                            ],
+                            Span::default(),
+                        )],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             for(;;){
                 {
                     break;
-                    continue;  // ignored by parser
+                    continue;  // Unreachable
                 }
             }
             "#,
-                Ok(vec![Stmt::ForStmt(ForStmt {
-                    init: None,
-                    cond: None,
-                    afterthought: None,
-                    block: vec![Stmt::BlockStmt(vec![Stmt::BreakStmt])],
-                })]),
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: None,
+                        init: None,
+                        cond: None,
+                        afterthought: None,
+                        block: vec![Stmt::BlockStmt(
+                            vec![
+                                Stmt::BreakStmt(None, Span::default()),
+                                Stmt::Unreachable(
+                                    vec![Stmt::ContinueStmt(None, Span::default())],
+                                    Span::default(),
+                                ),
+                            ],
+                            Span::default(),
+                        )],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
@@ -1154,7 +1908,12 @@ mod tests {
         ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -1165,12 +1924,14 @@ mod tests {
                 r#"
             while (true) {}
             "#,
-                Ok(vec![
-                    Stmt::WhileStmt(WhileStmt {
+                Ok(vec![Stmt::WhileStmt(
+                    WhileStmt {
+                        label: None,
                         cond: Expr::literal_bool(true),
-                        block: vec![]
-                    })
-                ])
+                        block: vec![],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
@@ -1178,51 +1939,370 @@ mod tests {
                 42;
             }
             "#,
-                Ok(vec![
-                    Stmt::WhileStmt(WhileStmt {
+                Ok(vec![Stmt::WhileStmt(
+                    WhileStmt {
+                        label: None,
                         cond: Expr::literal_bool(true),
-                        block: vec![
-                            Stmt::ExprStmt(Expr::literal_int(42))
-                        ]
-                    })
-                ])
+                        block: vec![Stmt::ExprStmt(Expr::literal_int(42), Span::default())],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             while (true) {
                 break;
-                continue; // Ignored by parser
+                continue; // Unreachable
             }
             "#,
-                Ok(vec![
-                    Stmt::WhileStmt(WhileStmt {
+                Ok(vec![Stmt::WhileStmt(
+                    WhileStmt {
+                        label: None,
                         cond: Expr::literal_bool(true),
                         block: vec![
-                            Stmt::BreakStmt
-                        ]
-                    })
-                ])
+                            Stmt::BreakStmt(None, Span::default()),
+                            Stmt::Unreachable(
+                                vec![Stmt::ContinueStmt(None, Span::default())],
+                                Span::default(),
+                            ),
+                        ],
+                    },
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             while (true) {
                 continue;
-                break; // Ignored by parser
+                break; // Unreachable
             }
             "#,
-                Ok(vec![
-                    Stmt::WhileStmt(WhileStmt {
+                Ok(vec![Stmt::WhileStmt(
+                    WhileStmt {
+                        label: None,
+                        cond: Expr::literal_bool(true),
+                        block: vec![
+                            Stmt::ContinueStmt(None, Span::default()),
+                            Stmt::Unreachable(
+                                vec![Stmt::BreakStmt(None, Span::default())],
+                                Span::default(),
+                            ),
+                        ],
+                    },
+                    Span::default(),
+                )]),
+            ),
+        ];
+
+        for (src, expected) in tests {
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn do_while_stmt_ast_is_built_correctly() {
+        let tests = [
+            (
+                "do {} while (true);",
+                Ok(vec![Stmt::DoWhileStmt(
+                    DoWhileStmt {
+                        label: None,
+                        block: vec![],
                         cond: Expr::literal_bool(true),
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                "do{ continue; }while(x);",
+                Ok(vec![Stmt::DoWhileStmt(
+                    DoWhileStmt {
+                        label: None,
+                        block: vec![Stmt::ContinueStmt(None, Span::default())],
+                        cond: Expr::ident("x"),
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                "done = 1;",
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Assign,
+                        Expr::ident("done"),
+                        Expr::literal_int(1),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            ("do {} while (true)", Err(ParseError::PestParser)),
+            ("do {}", Err(ParseError::PestParser)),
+        ];
+
+        for (src, expected) in tests {
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn labelled_loop_ast_is_built_correctly() {
+        fn label(name: &str) -> Option<Ident> {
+            Some(Ident {
+                name: name.to_string(),
+                span: Span::default(),
+            })
+        }
+
+        let tests = [
+            (
+                r#"
+            outer: for (;;) {
+                inner : while (true) {
+                    break outer;
+                }
+                continue outer;
+            }
+            "#,
+                Ok(vec![Stmt::ForStmt(
+                    ForStmt {
+                        label: label("outer"),
+                        init: None,
+                        cond: None,
+                        afterthought: None,
                         block: vec![
-                            Stmt::ContinueStmt
-                        ]
-                    })
-                ])
+                            Stmt::WhileStmt(
+                                WhileStmt {
+                                    label: label("inner"),
+                                    cond: Expr::literal_bool(true),
+                                    block: vec![Stmt::BreakStmt(label("outer"), Span::default())],
+                                },
+                                Span::default(),
+                            ),
+                            Stmt::ContinueStmt(label("outer"), Span::default()),
+                        ],
+                    },
+                    Span::default(),
+                )]),
             ),
+            (
+                "l: do { break l; } while (true);",
+                Ok(vec![Stmt::DoWhileStmt(
+                    DoWhileStmt {
+                        label: label("l"),
+                        block: vec![Stmt::BreakStmt(label("l"), Span::default())],
+                        cond: Expr::literal_bool(true),
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                "a: while (true) {} a: while (true) { break a; }",
+                Ok(vec![
+                    Stmt::WhileStmt(
+                        WhileStmt {
+                            label: label("a"),
+                            cond: Expr::literal_bool(true),
+                            block: vec![],
+                        },
+                        Span::default(),
+                    ),
+                    Stmt::WhileStmt(
+                        WhileStmt {
+                            label: label("a"),
+                            cond: Expr::literal_bool(true),
+                            block: vec![Stmt::BreakStmt(label("a"), Span::default())],
+                        },
+                        Span::default(),
+                    ),
+                ]),
+            ),
+            ("while (true) { break outer; }", Err(ParseError::BuildAst)), // No such label
+            (
+                "a: while (true) {} while (true) { continue a; }",
+                Err(ParseError::BuildAst),
+            ), // Not enclosing
+            (
+                "a: while (true) { a: while (true) {} }",
+                Err(ParseError::BuildAst),
+            ), // Reused by an inner loop
+            (
+                "a: while (true) { func f() { break a; } }",
+                Err(ParseError::BuildAst),
+            ), // Out of the function
+            ("a: { break a; }", Err(ParseError::PestParser)),             // Only loops are labelled
+            ("a: var x;", Err(ParseError::PestParser)),
         ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn switch_stmt_ast_is_built_correctly() {
+        let tests = [
+            (
+                "switch (x) {}",
+                Ok(vec![Stmt::SwitchStmt(
+                    SwitchStmt {
+                        subject: Expr::ident("x"),
+                        cases: vec![],
+                        default: None,
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            switch (x + 1) {
+                case 1: { print(1); }
+                case -2: {}
+                case "a": {}
+                case true: {}
+                default: { x; }
+            }
+            "#,
+                Ok(vec![Stmt::SwitchStmt(
+                    SwitchStmt {
+                        subject: Expr::infix(
+                            InfixOpKind::Add,
+                            Expr::ident("x"),
+                            Expr::literal_int(1),
+                        ),
+                        cases: vec![
+                            SwitchStmtCase {
+                                value: Expr::literal_int(1),
+                                block: vec![Stmt::ExprStmt(
+                                    Expr::PostfixOp {
+                                        kind: PostfixOpKind::Call(vec![Expr::literal_int(1)]),
+                                        lhs: Box::new(Expr::ident("print")),
+                                    },
+                                    Span::default(),
+                                )],
+                            },
+                            SwitchStmtCase {
+                                value: Expr::prefix(PrefixOpKind::Neg, Expr::literal_int(2)),
+                                block: vec![],
+                            },
+                            SwitchStmtCase {
+                                value: Expr::literal_string("a".to_string()),
+                                block: vec![],
+                            },
+                            SwitchStmtCase {
+                                value: Expr::literal_bool(true),
+                                block: vec![],
+                            },
+                        ],
+                        default: Some(vec![Stmt::ExprStmt(Expr::ident("x"), Span::default())]),
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                "while (true) { switch (x) { default: { break; } } }",
+                Ok(vec![Stmt::WhileStmt(
+                    WhileStmt {
+                        label: None,
+                        cond: Expr::literal_bool(true),
+                        block: vec![Stmt::SwitchStmt(
+                            SwitchStmt {
+                                subject: Expr::ident("x"),
+                                cases: vec![],
+                                default: Some(vec![Stmt::BreakStmt(None, Span::default())]),
+                            },
+                            Span::default(),
+                        )],
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                "switch (x) { case 1: {} case 2: {} case 1: {} }",
+                Err(ParseError::DuplicateCase("1".to_string())),
+            ),
+            (
+                "switch (x) { case -0: {} case 0: {} }",
+                Err(ParseError::DuplicateCase("0".to_string())),
+            ),
+            (
+                "switch (x) { case \"a\": {} case \"a\": {} }",
+                Err(ParseError::DuplicateCase("\"a\"".to_string())),
+            ),
+            (
+                "switch (x) { case 99999999999999999999: {} }",
+                Err(ParseError::BuildAst),
+            ), // Beyond 64 bits
+            (
+                "switch (x) { case -99999999999999999999: {} }",
+                Err(ParseError::BuildAst),
+            ),
+            (
+                "switch (x) { case 1: {} case -1: {} case \"1\": {} }",
+                Ok(vec![Stmt::SwitchStmt(
+                    SwitchStmt {
+                        subject: Expr::ident("x"),
+                        cases: vec![
+                            SwitchStmtCase {
+                                value: Expr::literal_int(1),
+                                block: vec![],
+                            },
+                            SwitchStmtCase {
+                                value: Expr::prefix(PrefixOpKind::Neg, Expr::literal_int(1)),
+                                block: vec![],
+                            },
+                            SwitchStmtCase {
+                                value: Expr::literal_string("1".to_string()),
+                                block: vec![],
+                            },
+                        ],
+                        default: None,
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                "switch (x) { case 1: print(1); }",
+                Err(ParseError::PestParser),
+            ), // Braces are required
+            ("switch (x) { case y: {} }", Err(ParseError::PestParser)), // Only literals
+            ("switch (x) { case none: {} }", Err(ParseError::PestParser)),
+            ("switch (x) { case [1]: {} }", Err(ParseError::PestParser)),
+            (
+                "switch (x) { default: {} case 1: {} }",
+                Err(ParseError::PestParser),
+            ), // The default is last
+            (
+                "switch (x) { default: {} default: {} }",
+                Err(ParseError::PestParser),
+            ),
+            (
+                "switch (x) { case 1: { break; } }",
+                Err(ParseError::BuildAst),
+            ), // Not in a loop
+        ];
+
+        for (src, expected) in tests {
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -1233,40 +2313,127 @@ mod tests {
                 r#"
             var x;
             "#,
-                Ok(vec![Stmt::VarStmt(VarStmt {
-                    ident: Ident {
-                        name: "x".to_string(),
+                Ok(vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: false,
+                        ident: Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: None,
+                        expr: None,
                     },
-                    expr: None,
-                })]),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             var x = none;
             "#,
-                Ok(vec![Stmt::VarStmt(VarStmt {
-                    ident: Ident {
-                        name: "x".to_string(),
+                Ok(vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: false,
+                        ident: Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: None,
+                        expr: Some(Expr::literal_none()),
                     },
-                    expr: Some(Expr::literal_none()),
-                })]),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             var x = 1 + 2;
             "#,
-                Ok(vec![Stmt::VarStmt(VarStmt {
-                    ident: Ident {
-                        name: "x".to_string(),
+                Ok(vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: false,
+                        ident: Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: None,
+                        expr: Some(Expr::infix(
+                            InfixOpKind::Add,
+                            Expr::literal_int(1),
+                            Expr::literal_int(2),
+                        )),
                     },
-                    expr: Some(Expr::infix(
-                        InfixOpKind::Add,
-                        Expr::literal_int(1),
-                        Expr::literal_int(2),
-                    )),
-                })]),
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            var x: string = "a";
+            "#,
+                Ok(vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: false,
+                        ident: Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: Some(TypeAnnotation::String),
+                        expr: Some(Expr::literal_string("a".to_string())),
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            var int : array;
+            "#,
+                Ok(vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: false,
+                        ident: Ident {
+                            name: "int".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: Some(TypeAnnotation::Array),
+                        expr: None,
+                    },
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            const x: int = 1;
+            "#,
+                Ok(vec![Stmt::VarStmt(
+                    VarStmt {
+                        is_const: true,
+                        ident: Ident {
+                            name: "x".to_string(),
+                            span: Span::default(),
+                        },
+                        annotation: Some(TypeAnnotation::Int),
+                        expr: Some(Expr::literal_int(1)),
+                    },
+                    Span::default(),
+                )]),
             ),
             // PestParser
+            (
+                r#"
+            const x;
+            "#,
+                Err(ParseError::PestParser),
+            ),
+            (
+                r#"
+            var const;
+            "#,
+                Err(ParseError::PestParser),
+            ),
+            (
+                r#"
+            var x: integer;
+            "#,
+                Err(ParseError::PestParser),
+            ),
             (
                 r#"
             var 0;
@@ -1359,7 +2526,12 @@ mod tests {
             ),
         ];
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -1371,108 +2543,158 @@ mod tests {
                 r#"
             true;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_bool(true))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_bool(true),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             false;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_bool(false))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_bool(false),
+                    Span::default(),
+                )]),
             ),
             // Integer
             (
                 r#"
             0;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_int(0))]),
+                Ok(vec![Stmt::ExprStmt(Expr::literal_int(0), Span::default())]),
             ),
             (
                 r#"
             1;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_int(1))]),
+                Ok(vec![Stmt::ExprStmt(Expr::literal_int(1), Span::default())]),
             ),
             (
                 r#"
                 9223372036854775807;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_int(9223372036854775807))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_int(9223372036854775807),
+                    Span::default(),
+                )]),
             ),
             // String
             (
                 r#"
             "foo";
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_string(
-                    "foo".to_string(),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_string("foo".to_string()),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             "\"";
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_string(
-                    "\\\"".to_string(),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_string("\\\"".to_string()),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             "\\";
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_string(
-                    "\\\\".to_string(),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_string("\\\\".to_string()),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             "\n";
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_string(
-                    "\\n".to_string(),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_string("\\n".to_string()),
+                    Span::default(),
+                )]),
             ),
             // Array
             (
                 r#"
             [1, 2, 3];
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_array(vec![
-                    Expr::literal_int(1),
-                    Expr::literal_int(2),
-                    Expr::literal_int(3),
-                ]))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_array(vec![
+                        Expr::literal_int(1),
+                        Expr::literal_int(2),
+                        Expr::literal_int(3),
+                    ]),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             [];
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_array(vec![]))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::literal_array(vec![]),
+                    Span::default(),
+                )]),
             ),
             // None
             (
                 r#"
             none;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::literal_none())]),
+                Ok(vec![Stmt::ExprStmt(Expr::literal_none(), Span::default())]),
             ),
         ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
     #[test]
     fn ident_expr_stmt_ast_is_built_correctly() {
-        let tests = [(
-            r#"
+        let tests = [
+            (
+                r#"
             foo;
             "#,
-            Ok(vec![Stmt::ExprStmt(Expr::ident("foo"))]),
-        )];
+                Ok(vec![Stmt::ExprStmt(Expr::ident("foo"), Span::default())]),
+            ),
+            // Names starting with a keyword
+            (
+                r#"
+            constant; format; nonempty; truth; if_;
+            "#,
+                Ok(vec![
+                    Stmt::ExprStmt(Expr::ident("constant"), Span::default()),
+                    Stmt::ExprStmt(Expr::ident("format"), Span::default()),
+                    Stmt::ExprStmt(Expr::ident("nonempty"), Span::default()),
+                    Stmt::ExprStmt(Expr::ident("truth"), Span::default()),
+                    Stmt::ExprStmt(Expr::ident("if_"), Span::default()),
+                ]),
+            ),
+            (
+                r#"
+            const;
+            "#,
+                Err(ParseError::PestParser),
+            ),
+        ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -1484,43 +2706,61 @@ mod tests {
                 r#"
             +1;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::prefix(
-                    PrefixOpKind::Pos,
-                    Expr::literal_int(1),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::prefix(PrefixOpKind::Pos, Expr::literal_int(1)),
+                    Span::default(),
+                )]),
             ),
             // Neg
             (
                 r#"
             -1;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::prefix(
-                    PrefixOpKind::Neg,
-                    Expr::literal_int(1),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::prefix(PrefixOpKind::Neg, Expr::literal_int(1)),
+                    Span::default(),
+                )]),
             ),
             // Not
             (
                 r#"
             !true;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::prefix(
-                    PrefixOpKind::Not,
-                    Expr::literal_bool(true),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::prefix(PrefixOpKind::Not, Expr::literal_bool(true)),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             !false;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::prefix(
-                    PrefixOpKind::Not,
-                    Expr::literal_bool(false),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::prefix(PrefixOpKind::Not, Expr::literal_bool(false)),
+                    Span::default(),
+                )]),
+            ),
+            // BitNot
+            (
+                r#"
+            ~-1;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::prefix(
+                        PrefixOpKind::BitNot,
+                        Expr::prefix(PrefixOpKind::Neg, Expr::literal_int(1)),
+                    ),
+                    Span::default(),
+                )]),
             ),
         ];
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -1531,56 +2771,216 @@ mod tests {
                 r#"
             2 + 3;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::infix(
-                    InfixOpKind::Add,
-                    Expr::literal_int(2),
-                    Expr::literal_int(3),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(InfixOpKind::Add, Expr::literal_int(2), Expr::literal_int(3)),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             2 - 3;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::infix(
-                    InfixOpKind::Subtract,
-                    Expr::literal_int(2),
-                    Expr::literal_int(3),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Subtract,
+                        Expr::literal_int(2),
+                        Expr::literal_int(3),
+                    ),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             2 * 3;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::infix(
-                    InfixOpKind::Multiply,
-                    Expr::literal_int(2),
-                    Expr::literal_int(3),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Multiply,
+                        Expr::literal_int(2),
+                        Expr::literal_int(3),
+                    ),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             2 / 3;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::infix(
-                    InfixOpKind::Divide,
-                    Expr::literal_int(2),
-                    Expr::literal_int(3),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Divide,
+                        Expr::literal_int(2),
+                        Expr::literal_int(3),
+                    ),
+                    Span::default(),
+                )]),
             ),
             (
                 r#"
             2 % 3;
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::infix(
-                    InfixOpKind::Modulo,
-                    Expr::literal_int(2),
-                    Expr::literal_int(3),
-                ))]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Modulo,
+                        Expr::literal_int(2),
+                        Expr::literal_int(3),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            x += 1 + 2;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::AddAssign,
+                        Expr::ident("x"),
+                        Expr::infix(InfixOpKind::Add, Expr::literal_int(1), Expr::literal_int(2)),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            x -= y *= 2;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::SubtractAssign,
+                        Expr::ident("x"),
+                        Expr::infix(
+                            InfixOpKind::MultiplyAssign,
+                            Expr::ident("y"),
+                            Expr::literal_int(2),
+                        ),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            (
+                r#"
+            a[0] /= 2 %= 3;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::DivideAssign,
+                        Expr::PostfixOp {
+                            kind: PostfixOpKind::Index(Box::new(Expr::literal_int(0))),
+                            lhs: Box::new(Expr::ident("a")),
+                        },
+                        Expr::infix(
+                            InfixOpKind::ModuloAssign,
+                            Expr::literal_int(2),
+                            Expr::literal_int(3),
+                        ),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            // Bitwise operators bind looser than comparisons, and shifts tighter
+            (
+                r#"
+            a | b ^ c & d == 1 << 2 + 3;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::BitOr,
+                        Expr::ident("a"),
+                        Expr::infix(
+                            InfixOpKind::BitXor,
+                            Expr::ident("b"),
+                            Expr::infix(
+                                InfixOpKind::BitAnd,
+                                Expr::ident("c"),
+                                Expr::infix(
+                                    InfixOpKind::Eq,
+                                    Expr::ident("d"),
+                                    Expr::infix(
+                                        InfixOpKind::ShiftLeft,
+                                        Expr::literal_int(1),
+                                        Expr::infix(
+                                            InfixOpKind::Add,
+                                            Expr::literal_int(2),
+                                            Expr::literal_int(3),
+                                        ),
+                                    ),
+                                ),
+                            ),
+                        ),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            // Longer operators are not split, as `&&` into `&` and `&`
+            (
+                r#"
+            a && b || c >> 1 <= d;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Or,
+                        Expr::infix(InfixOpKind::And, Expr::ident("a"), Expr::ident("b")),
+                        Expr::infix(
+                            InfixOpKind::Le,
+                            Expr::infix(
+                                InfixOpKind::ShiftRight,
+                                Expr::ident("c"),
+                                Expr::literal_int(1),
+                            ),
+                            Expr::ident("d"),
+                        ),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            // `**` is right-associative and binds tighter than prefix operators on its left
+            (
+                r#"
+            -2 ** 3 ** -1 * 4;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Multiply,
+                        Expr::prefix(
+                            PrefixOpKind::Neg,
+                            Expr::infix(
+                                InfixOpKind::Power,
+                                Expr::literal_int(2),
+                                Expr::infix(
+                                    InfixOpKind::Power,
+                                    Expr::literal_int(3),
+                                    Expr::prefix(PrefixOpKind::Neg, Expr::literal_int(1)),
+                                ),
+                            ),
+                        ),
+                        Expr::literal_int(4),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            // PestParser
+            (
+                r#"
+            x++;
+            "#,
+                Err(ParseError::PestParser),
+            ),
+            (
+                r#"
+            x <<< 1;
+            "#,
+                Err(ParseError::PestParser),
             ),
         ];
 
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 
@@ -1592,27 +2992,117 @@ mod tests {
                 r#"
             [x, y][1];
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::PostfixOp {
-                    kind: PostfixOpKind::Index(Box::new(Expr::literal_int(1))),
-                    lhs: Box::new(Expr::literal_array(vec![
-                        Expr::ident("x"),
-                        Expr::ident("y"),
-                    ])),
-                })]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::PostfixOp {
+                        kind: PostfixOpKind::Index(Box::new(Expr::literal_int(1))),
+                        lhs: Box::new(Expr::literal_array(vec![
+                            Expr::ident("x"),
+                            Expr::ident("y"),
+                        ])),
+                    },
+                    Span::default(),
+                )]),
             ),
             // Call
             (
                 r#"
             foo(1, x);
             "#,
-                Ok(vec![Stmt::ExprStmt(Expr::PostfixOp {
-                    kind: PostfixOpKind::Call(vec![Expr::literal_int(1), Expr::ident("x")]),
-                    lhs: Box::new(Expr::ident("foo")),
-                })]),
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::PostfixOp {
+                        kind: PostfixOpKind::Call(vec![Expr::literal_int(1), Expr::ident("x")]),
+                        lhs: Box::new(Expr::ident("foo")),
+                    },
+                    Span::default(),
+                )]),
+            ),
+        ];
+        for (src, expected) in tests {
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+    #[test]
+    fn conditional_expr_stmt_ast_is_built_correctly() {
+        let tests = [
+            (
+                r#"
+            x ? 1 : 2;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::conditional(Expr::ident("x"), Expr::literal_int(1), Expr::literal_int(2)),
+                    Span::default(),
+                )]),
+            ),
+            // Binds looser than `||` and tighter than `=`
+            (
+                r#"
+            x = a || b ? 1 : 2;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::infix(
+                        InfixOpKind::Assign,
+                        Expr::ident("x"),
+                        Expr::conditional(
+                            Expr::infix(InfixOpKind::Or, Expr::ident("a"), Expr::ident("b")),
+                            Expr::literal_int(1),
+                            Expr::literal_int(2),
+                        ),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            // Nests to the right, and any expression may come between `?` and `:`
+            (
+                r#"
+            a ? b ? 1 : 2 : c ? x = 3 : 4;
+            "#,
+                Ok(vec![Stmt::ExprStmt(
+                    Expr::conditional(
+                        Expr::ident("a"),
+                        Expr::conditional(
+                            Expr::ident("b"),
+                            Expr::literal_int(1),
+                            Expr::literal_int(2),
+                        ),
+                        Expr::conditional(
+                            Expr::ident("c"),
+                            Expr::infix(
+                                InfixOpKind::Assign,
+                                Expr::ident("x"),
+                                Expr::literal_int(3),
+                            ),
+                            Expr::literal_int(4),
+                        ),
+                    ),
+                    Span::default(),
+                )]),
+            ),
+            // PestParser
+            (
+                r#"
+            a ? 1;
+            "#,
+                Err(ParseError::PestParser),
+            ),
+            (
+                r#"
+            a ? : 2;
+            "#,
+                Err(ParseError::PestParser),
             ),
         ];
         for (src, expected) in tests {
-            assert_eq!(parse(src), expected, "Failed in test case: {}", src);
+            assert_eq!(
+                parse(src).map(without_spans),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
         }
     }
 }
@@ -0,0 +1,791 @@
+// Names declared in a program and what each identifier refers to, for editor features.
+//
+// Variables are resolved lexically even though a function sees the variables of its caller at
+// runtime. A name refers to the innermost declaration visible at the use, or if none is visible
+// yet, to the innermost one declared later, such as a global used by a function declared before
+// it.
+
+use std::cmp::Reverse;
+
+use serde::Serialize;
+
+use crate::ast::*;
+use crate::data::{Arity, Builtin, BUILTINS};
+use crate::error::{EvalError, ParseError};
+use crate::formatter::signature;
+use crate::parser::parse_recovering;
+
+// Words excluded from `IDENT` in the grammar, and the other words starting statements or clauses
+pub const KEYWORDS: [&str; 17] = [
+    "func", "return", "if", "else", "for", "while", "do", "switch", "case", "default", "var",
+    "const", "true", "false", "break", "continue", "none",
+];
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum SymbolKind {
+    Variable,
+    Constant,
+    Parameter,
+    Function,
+    Builtin,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub detail: String,    // Declaration, such as `var x = 1;` and `func f(a, b)`
+    pub doc: &'static str, // Description of builtins, empty for the others
+    pub literal_type: Option<&'static str>, // Type of the literal a variable is initialized with
+    #[serde(skip)]
+    pub arity: Option<Arity>, // Numbers of arguments a function can be called with
+    #[serde(skip)]
+    pub span: Span, // Identifier in the declaration, empty for builtins
+    #[serde(skip)]
+    pub decl: Span, // Whole declaration
+    #[serde(skip)]
+    pub scope: Span, // Where the symbol can be referred to
+    #[serde(skip)]
+    visible_from: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Resolution {
+    pub symbols: Vec<Symbol>,
+    pub references: Vec<(Span, Option<usize>)>, // Each identifier used and the index of its symbol
+    pub calls: Vec<(Span, usize)>, // Identifier of each function called by name and the number of arguments
+    pub syntax_errors: Vec<(ParseError, Span)>,
+    pub unreachable: Vec<(Span, &'static str)>, // Unreachable statements and the keyword before them
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum CompletionKind {
+    Keyword,
+    Variable,
+    Constant,
+    Function,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionKind,
+    pub detail: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct Diagnostic {
+    #[serde(skip)]
+    pub span: Span,
+    pub severity: Severity,
+    pub message: String,
+}
+
+// Resolution of the statements which parse, so that a program being edited is resolved too
+pub fn resolve(source: &str) -> Resolution {
+    let (program, syntax_errors) = parse_recovering(source);
+    let scope = Span::new(0, source.len());
+    let mut resolver = Resolver {
+        source,
+        symbols: vec![],
+        uses: vec![],
+        calls: vec![],
+        unreachable: vec![],
+    };
+    let mut builtins: Vec<&Builtin> = BUILTINS.iter().collect();
+    builtins.sort_by_key(|builtin| builtin.name);
+    for builtin in builtins {
+        let mut params = builtin.params.to_vec();
+        if builtin.arity.max.is_none() {
+            params.push("..."); // Any number of further arguments
+        }
+        resolver.symbols.push(Symbol {
+            name: builtin.name.to_string(),
+            kind: SymbolKind::Builtin,
+            detail: format!("func {}({})", builtin.name, params.join(", ")),
+            doc: builtin.doc,
+            literal_type: None,
+            arity: Some(builtin.arity),
+            span: Span::default(),
+            decl: Span::default(),
+            scope,
+            visible_from: 0,
+        });
+    }
+    resolver.stmts(&program, scope);
+
+    let references = resolver
+        .uses
+        .iter()
+        .map(|(name, span)| (*span, resolver.lookup(name, span.start)))
+        .collect();
+    Resolution {
+        symbols: resolver.symbols,
+        references,
+        calls: resolver.calls,
+        syntax_errors,
+        unreachable: resolver.unreachable,
+    }
+}
+
+// Each syntax error, and a warning for each name not declared anywhere, call with a wrong number of
+// arguments and unreachable code
+pub fn diagnostics(source: &str) -> Vec<Diagnostic> {
+    let resolution = resolve(source);
+    let errors = resolution
+        .syntax_errors
+        .iter()
+        .map(|(parse_err, span)| Diagnostic {
+            span: *span,
+            severity: Severity::Error,
+            message: parse_err.to_string(),
+        });
+    let undefined = resolution
+        .references
+        .iter()
+        .filter(|(_, symbol)| symbol.is_none())
+        .map(|(span, _)| Diagnostic {
+            span: *span,
+            severity: Severity::Warning,
+            message: format!("Undefined name: {}", &source[span.start..span.end]),
+        });
+    let arguments = resolution.calls.iter().filter_map(|(span, count)| {
+        let symbol = resolution.symbol_at(span.start)?;
+        let arity = symbol.arity.filter(|arity| !arity.accepts(*count))?;
+        Some(Diagnostic {
+            span: *span,
+            severity: Severity::Warning,
+            message: EvalError::Argument {
+                name: symbol.name.clone(),
+                arity,
+                count: *count,
+            }
+            .to_string(),
+        })
+    });
+    let unreachable = resolution
+        .unreachable
+        .iter()
+        .map(|(span, keyword)| Diagnostic {
+            span: *span,
+            severity: Severity::Warning,
+            message: format!("Unreachable code after `{}`", keyword),
+        });
+    let mut diagnostics: Vec<Diagnostic> = errors
+        .chain(undefined)
+        .chain(arguments)
+        .chain(unreachable)
+        .collect();
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
+
+// Keywords and the names visible at `offset`, even while the line there is being typed
+pub fn completions(source: &str, offset: usize) -> Vec<Completion> {
+    let mut completions: Vec<Completion> = KEYWORDS
+        .iter()
+        .map(|keyword| Completion {
+            label: keyword.to_string(),
+            kind: CompletionKind::Keyword,
+            detail: String::new(),
+        })
+        .collect();
+    completions.extend(
+        resolve(source)
+            .visible(offset)
+            .into_iter()
+            .map(|symbol| Completion {
+                label: symbol.name.clone(),
+                kind: match symbol.kind {
+                    SymbolKind::Variable | SymbolKind::Parameter => CompletionKind::Variable,
+                    SymbolKind::Constant => CompletionKind::Constant,
+                    SymbolKind::Function | SymbolKind::Builtin => CompletionKind::Function,
+                },
+                detail: symbol.detail.clone(),
+            }),
+    );
+    completions
+}
+
+// Markdown describing the symbol of the identifier at `offset`
+pub fn hover(source: &str, offset: usize) -> Option<String> {
+    let resolution = resolve(source);
+    let symbol = resolution.symbol_at(offset)?;
+    let mut hover = format!("```momonga\n{}\n```", symbol.detail);
+    if !symbol.doc.is_empty() {
+        hover.push_str(&format!("\n\n{}", symbol.doc));
+    }
+    if let Some(literal_type) = symbol.literal_type {
+        hover.push_str(&format!(
+            "\n\nInitialized with a value of type `{}`",
+            literal_type
+        ));
+    }
+    Some(hover)
+}
+
+// Type of a literal, possibly with a sign or negation
+pub fn literal_type(expr: &Expr) -> Option<&'static str> {
+    match expr {
+        Expr::Literal(Literal::Bool(_)) => Some("bool"),
+        Expr::Literal(Literal::Int(_)) => Some("int"),
+        Expr::Literal(Literal::String(_)) => Some("string"),
+        Expr::Literal(Literal::Array(_)) => Some("array"),
+        Expr::Literal(Literal::None) => Some("none"),
+        Expr::PrefixOp {
+            kind: PrefixOpKind::Pos | PrefixOpKind::Neg,
+            rhs,
+        } if literal_type(rhs) == Some("int") => Some("int"),
+        Expr::PrefixOp {
+            kind: PrefixOpKind::Not,
+            rhs,
+        } if literal_type(rhs) == Some("bool") => Some("bool"),
+        _ => None,
+    }
+}
+
+impl Resolution {
+    // Symbol declared or referred to by the identifier at `offset`
+    pub fn symbol_at(&self, offset: usize) -> Option<&Symbol> {
+        let contains = |span: &Span| span.start <= offset && offset <= span.end;
+        self.references
+            .iter()
+            .find(|(span, _)| contains(span))
+            .and_then(|(_, symbol)| symbol.map(|index| &self.symbols[index]))
+            .or_else(|| {
+                self.symbols
+                    .iter()
+                    .find(|symbol| symbol.kind != SymbolKind::Builtin && contains(&symbol.span))
+            })
+    }
+
+    // Symbols which can be referred to at `offset`, the innermost first without shadowed ones
+    pub fn visible(&self, offset: usize) -> Vec<&Symbol> {
+        let mut symbols: Vec<&Symbol> = self
+            .symbols
+            .iter()
+            .filter(|symbol| {
+                symbol.scope.start <= offset
+                    && offset <= symbol.scope.end
+                    && (symbol.visible_from <= offset || symbol.kind == SymbolKind::Function)
+            })
+            .collect();
+        symbols.sort_by_key(|symbol| {
+            (
+                symbol.scope.end - symbol.scope.start,
+                Reverse(symbol.visible_from),
+            )
+        });
+        let mut names = vec![];
+        symbols.retain(|symbol| {
+            let shadowed = names.contains(&symbol.name);
+            names.push(symbol.name.clone());
+            !shadowed
+        });
+        symbols
+    }
+}
+
+struct Resolver<'a> {
+    source: &'a str,
+    symbols: Vec<Symbol>,
+    uses: Vec<(&'a str, Span)>,
+    calls: Vec<(Span, usize)>,
+    unreachable: Vec<(Span, &'static str)>,
+}
+
+impl<'a> Resolver<'a> {
+    fn declare(
+        &mut self,
+        kind: SymbolKind,
+        ident: &Ident,
+        detail: String,
+        decl: Span,
+        scope: Span,
+        visible_from: usize,
+    ) {
+        self.symbols.push(Symbol {
+            name: ident.name.clone(),
+            kind,
+            detail,
+            doc: "",
+            literal_type: None,
+            arity: None,
+            span: ident.span,
+            decl,
+            scope,
+            visible_from,
+        });
+    }
+
+    fn lookup(&self, name: &str, offset: usize) -> Option<usize> {
+        let candidates = self.symbols.iter().enumerate().filter(|(_, symbol)| {
+            symbol.name == name && symbol.scope.start <= offset && offset <= symbol.scope.end
+        });
+        let size = |symbol: &Symbol| symbol.scope.end - symbol.scope.start;
+        candidates
+            .clone()
+            .filter(|(_, symbol)| symbol.visible_from <= offset)
+            .min_by_key(|(_, symbol)| (size(symbol), Reverse(symbol.visible_from)))
+            .or_else(|| candidates.min_by_key(|(_, symbol)| (size(symbol), symbol.visible_from)))
+            .map(|(index, _)| index)
+    }
+
+    // Blocks of `if`, `for`, `while` and `switch` have no spans of their own, so the statement is the scope
+    fn stmts(&mut self, stmts: &'a [Stmt], scope: Span) {
+        for (i, stmt) in stmts.iter().enumerate() {
+            if let Stmt::Unreachable(_, span) = stmt {
+                let keyword = match stmts[i - 1] {
+                    Stmt::ContinueStmt(..) => "continue",
+                    Stmt::BreakStmt(..) => "break",
+                    _ => "return",
+                };
+                self.unreachable.push((*span, keyword));
+            }
+            self.stmt(stmt, scope);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &'a Stmt, scope: Span) {
+        match stmt {
+            Stmt::BlockStmt(stmts, span) => self.stmts(stmts, *span),
+            Stmt::Unreachable(stmts, _) => self.stmts(stmts, scope),
+            Stmt::FuncDecl(
+                func_decl @ FuncDecl {
+                    ident_func,
+                    ident_param,
+                    default_param,
+                    block,
+                    ..
+                },
+                span,
+            ) => {
+                let detail = signature(func_decl);
+                let visible_from = ident_func.span.end;
+                self.declare(
+                    SymbolKind::Function,
+                    ident_func,
+                    detail.clone(),
+                    *span,
+                    scope,
+                    visible_from,
+                );
+                self.symbols.last_mut().unwrap().arity = Some(Arity::params(default_param));
+                for (ident, default) in ident_param.iter().zip(default_param) {
+                    let detail = format!("{} (parameter of {})", ident.name, detail);
+                    self.declare(
+                        SymbolKind::Parameter,
+                        ident,
+                        detail,
+                        ident.span,
+                        *span,
+                        ident.span.end,
+                    );
+                    if let Some(default) = default {
+                        self.expr(default);
+                    }
+                }
+                self.stmts(block, *span);
+            }
+            Stmt::IfStmt(if_stmt, span) => self.if_stmt(if_stmt, *span),
+            Stmt::ForStmt(
+                ForStmt {
+                    init,
+                    cond,
+                    afterthought,
+                    block,
+                    ..
+                },
+                span,
+            ) => {
+                match init {
+                    Some(ForStmtInit::Var(VarStmt { ident, expr, .. })) => {
+                        if let Some(expr) = expr {
+                            self.expr(expr);
+                        }
+                        let detail = format!("var {}", ident.name);
+                        self.declare(
+                            SymbolKind::Variable,
+                            ident,
+                            detail,
+                            ident.span,
+                            *span,
+                            ident.span.end,
+                        );
+                        self.symbols.last_mut().unwrap().literal_type =
+                            expr.as_ref().and_then(literal_type);
+                    }
+                    Some(ForStmtInit::Expr(expr)) => self.expr(expr),
+                    None => (),
+                }
+                for expr in [cond, afterthought].into_iter().flatten() {
+                    self.expr(expr);
+                }
+                self.stmts(block, *span);
+            }
+            Stmt::WhileStmt(WhileStmt { cond, block, .. }, span) => {
+                self.expr(cond);
+                self.stmts(block, *span);
+            }
+            Stmt::DoWhileStmt(DoWhileStmt { block, cond, .. }, span) => {
+                // The condition is out of the scope of the block, which ends at its `}`
+                let end = match block.last() {
+                    Some(stmt) => {
+                        let end = stmt.span().end;
+                        self.source[end..]
+                            .find('}')
+                            .map_or(span.end, |i| end + i + 1)
+                    }
+                    None => span.start,
+                };
+                self.stmts(block, Span::new(span.start, end));
+                self.expr(cond);
+            }
+            Stmt::SwitchStmt(
+                SwitchStmt {
+                    subject,
+                    cases,
+                    default,
+                },
+                span,
+            ) => {
+                self.expr(subject);
+                for SwitchStmtCase { block, .. } in cases {
+                    self.stmts(block, *span);
+                }
+                if let Some(block) = default {
+                    self.stmts(block, *span);
+                }
+            }
+            Stmt::VarStmt(
+                VarStmt {
+                    is_const,
+                    ident,
+                    expr,
+                    ..
+                },
+                span,
+            ) => {
+                if let Some(expr) = expr {
+                    self.expr(expr);
+                }
+                let kind = if *is_const {
+                    SymbolKind::Constant
+                } else {
+                    SymbolKind::Variable
+                };
+                let detail = self.source[span.start..span.end].to_string();
+                self.declare(kind, ident, detail, *span, scope, span.end);
+                self.symbols.last_mut().unwrap().literal_type =
+                    expr.as_ref().and_then(literal_type);
+            }
+            Stmt::ExprStmt(expr, _) => self.expr(expr),
+            Stmt::ReturnStmt(ReturnStmt { expr: Some(expr) }, _) => self.expr(expr),
+            Stmt::ReturnStmt(..) | Stmt::ContinueStmt(..) | Stmt::BreakStmt(..) => (),
+            Stmt::Error(_) => (),
+        }
+    }
+
+    fn if_stmt(&mut self, if_stmt: &'a IfStmt, scope: Span) {
+        self.expr(&if_stmt.condition);
+        self.stmts(&if_stmt.block, scope);
+        match &if_stmt.else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => self.if_stmt(if_stmt, scope),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => self.stmts(block, scope),
+            None => (),
+        }
+    }
+
+    fn expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Literal(Literal::Array(exprs)) => exprs.iter().for_each(|expr| self.expr(expr)),
+            Expr::Literal(_) => (),
+            Expr::Ident(ident) => self.uses.push((&ident.name, ident.span)),
+            Expr::PrefixOp { rhs, .. } => self.expr(rhs),
+            Expr::InfixOp { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            Expr::PostfixOp { kind, lhs } => {
+                self.expr(lhs);
+                match kind {
+                    PostfixOpKind::Index(index) => self.expr(index),
+                    PostfixOpKind::Call(args) => {
+                        if let Expr::Ident(ident) = &**lhs {
+                            self.calls.push((ident.span, args.len()));
+                        }
+                        args.iter().for_each(|arg| self.expr(arg));
+                    }
+                }
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                self.expr(cond);
+                self.expr(then);
+                self.expr(otherwise);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::resolver::*;
+
+    // Source of the declaration each identifier marked with `^` below it refers to
+    fn declaration_at(src: &str, marker: usize) -> Option<String> {
+        let resolution = resolve(src);
+        let symbol = resolution.symbol_at(marker)?;
+        match symbol.kind {
+            SymbolKind::Builtin => Some(format!("builtin {}", symbol.name)),
+            _ => Some(src[symbol.decl.start..symbol.decl.end].to_string()),
+        }
+    }
+
+    #[test]
+    fn identifiers_are_resolved() {
+        let tests = [
+            ("var x = 1;\nprint(x);", "x)", Some("var x = 1;")),
+            ("var x = 1;\nprint(x);", "print", Some("builtin print")),
+            (
+                "var x = 1;\n{ var x = 2; x; }\nx;",
+                "x; }",
+                Some("var x = 2;"),
+            ),
+            (
+                "var x = 1;\n{ var x = 2; x; }\nx;",
+                "x;\0",
+                Some("var x = 1;"),
+            ),
+            (
+                "var x = 1;\n{ x; var x = 2; }",
+                "x; var",
+                Some("var x = 1;"),
+            ),
+            (
+                "var x = 1;\nvar x = x + 1;\nx;",
+                "x + 1",
+                Some("var x = 1;"),
+            ),
+            (
+                "func f(a) { return a + g; }\nvar g = 1;",
+                "g;",
+                Some("var g = 1;"),
+            ),
+            (
+                "func f(n) { return f(n - 1); }",
+                "f(n -",
+                Some("func f(n) { return f(n - 1); }"),
+            ),
+            ("func f(n) { return n; }", "n; }", Some("n")),
+            ("for (var i = 0; i < 3; i = i + 1) {}", "i < 3", Some("i")),
+            (
+                "var x = 1;\ndo { var x = 2; } while (x < 2);",
+                "x < 2",
+                Some("var x = 1;"),
+            ),
+            ("var print = 1;\nprint;", "print;", Some("var print = 1;")),
+            ("y;", "y", None),
+        ];
+        for (src, marker, expected) in tests {
+            // A trailing NUL in the marker stands for the end of the source
+            let offset = match marker.strip_suffix('\0') {
+                Some(marker) => src.rfind(marker).unwrap(),
+                None => src.find(marker).unwrap(),
+            };
+            assert_eq!(
+                declaration_at(src, offset).as_deref(),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn visible_symbols_are_listed() {
+        let src = "var x = 1;\nfunc f(a) {\n    var x = a;\n    \n}\nvar y = 2;\n";
+        let resolution = resolve(src);
+        let offset = src.find("    \n").unwrap();
+        let visible: Vec<&str> = resolution
+            .visible(offset)
+            .iter()
+            .map(|symbol| symbol.detail.as_str())
+            .collect();
+        assert_eq!(
+            visible,
+            vec![
+                "var x = a;",
+                "a (parameter of func f(a))",
+                "func f(a)",
+                "func abs(n)",
+                "func gcd(a, b)",
+                "func input()",
+                "func len(value)",
+                "func max(a, b, ...)",
+                "func min(a, b, ...)",
+                "func pop(array)",
+                "func pow(base, exponent)",
+                "func print(value, ...)",
+                "func push(array, item, ...)",
+                "func sqrt(n)",
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostics_are_reported() {
+        let tests = [
+            ("var x = 1;\nprint(x);", vec![]),
+            (
+                "print(y);",
+                vec![(6, 7, Severity::Warning, "Undefined name: y")],
+            ),
+            (
+                "var x = 1;\nvar = 2;",
+                vec![(15, 15, Severity::Error, "Syntax error")],
+            ),
+            (
+                "var = 1;\nfunc f(a {}\nprint(y);",
+                vec![
+                    (4, 4, Severity::Error, "Syntax error"),
+                    (9, 9, Severity::Error, "Syntax error"),
+                    (27, 28, Severity::Warning, "Undefined name: y"),
+                ],
+            ),
+            (
+                "func f() {\n    return 1;\n    print(2);\n}",
+                vec![(29, 38, Severity::Warning, "Unreachable code after `return`")],
+            ),
+            (
+                "print();\npush([1], 2, 3);\nprint(pow(2), len(\"a\", \"b\"));",
+                vec![
+                    (
+                        0,
+                        5,
+                        Severity::Warning,
+                        "`print` expects at least 1 argument, got 0",
+                    ),
+                    (
+                        32,
+                        35,
+                        Severity::Warning,
+                        "`pow` expects 2 arguments, got 1",
+                    ),
+                    (40, 43, Severity::Warning, "`len` expects 1 argument, got 2"),
+                ],
+            ),
+            ("var print = 1;\nprint();", vec![]),
+            (
+                "func f(a, b = a) {}\nf();\nf(1);\nf(1, 2, 3);",
+                vec![
+                    (
+                        20,
+                        21,
+                        Severity::Warning,
+                        "`f` expects 1 to 2 arguments, got 0",
+                    ),
+                    (
+                        31,
+                        32,
+                        Severity::Warning,
+                        "`f` expects 1 to 2 arguments, got 3",
+                    ),
+                ],
+            ),
+        ];
+        for (src, expected) in tests {
+            let diagnostics: Vec<_> = diagnostics(src)
+                .into_iter()
+                .map(|d| (d.span.start, d.span.end, d.severity, d.message))
+                .collect();
+            let expected: Vec<_> = expected
+                .into_iter()
+                .map(|(start, end, severity, message)| (start, end, severity, message.to_string()))
+                .collect();
+            assert_eq!(diagnostics, expected, "Failed in test case: {}", src);
+        }
+    }
+
+    #[test]
+    fn completions_survive_lines_being_typed() {
+        let tests = [
+            ("var count = 0;\nfunc f(n) {\n    co\n}\n", "co\n", true),
+            ("var count = 0;\nfunc f(n) {\n    if (n == \n", "if", true),
+            (
+                "var count = 0;\nfunc f(n) {\n    var s = \"{{\"; // {\n    if (\n",
+                "if",
+                true,
+            ),
+            ("var count = 0;\nfunc f(n) {}\nco", "co", false),
+        ];
+        for (src, marker, in_func) in tests {
+            let offset = src.rfind(marker).unwrap() + marker.trim_end().len();
+            let labels: Vec<String> = completions(src, offset)
+                .into_iter()
+                .map(|completion| completion.label)
+                .collect();
+            assert!(
+                labels.contains(&"count".to_string()),
+                "Failed in test case: {}",
+                src
+            );
+            assert!(
+                labels.contains(&"while".to_string()),
+                "Failed in test case: {}",
+                src
+            );
+            assert_eq!(
+                labels.contains(&"n".to_string()),
+                in_func,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn hover_describes_symbols() {
+        let tests = [
+            (
+                "var xs = [1, 2];\nlen(xs);",
+                "len",
+                Some("```momonga\nfunc len(value)\n```\n\nReturns the number of characters of a string or items of an array."),
+            ),
+            (
+                "var xs = [1, 2];\nlen(xs);",
+                "xs)",
+                Some("```momonga\nvar xs = [1, 2];\n```\n\nInitialized with a value of type `array`"),
+            ),
+            (
+                "var n = -1;\nn;",
+                "n;",
+                Some("```momonga\nvar n = -1;\n```\n\nInitialized with a value of type `int`"),
+            ),
+            (
+                "var s = input();\ns;",
+                "s;",
+                Some("```momonga\nvar s = input();\n```"),
+            ),
+            ("var x = 1;\n\"x\";", "x\";", None),
+        ];
+        for (src, marker, expected) in tests {
+            let offset = src.rfind(marker).unwrap();
+            assert_eq!(
+                hover(src, offset).as_deref(),
+                expected,
+                "Failed in test case: {}",
+                src
+            );
+        }
+    }
+}
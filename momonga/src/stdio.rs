@@ -0,0 +1,50 @@
+use std::{cell::RefCell, collections::VecDeque};
+
+#[cfg(target_arch = "wasm32")]
+use crate::{emit_output_event, OutputEvent};
+
+thread_local! {
+    static STDIN: RefCell<VecDeque<String>> = const { RefCell::new(VecDeque::new()) };
+    static STDOUT: RefCell<Option<Vec<String>>> = const { RefCell::new(None) }; // Captured lines, if any
+}
+
+// Run `f` with `stdin` fed to `input()` and collect every line written by `print()`
+pub fn with_captured_stdio<R>(stdin: &str, f: impl FnOnce() -> R) -> (R, Vec<String>) {
+    STDIN.with(|lines| *lines.borrow_mut() = stdin.lines().map(|line| line.to_string()).collect());
+    STDOUT.with(|lines| *lines.borrow_mut() = Some(vec![]));
+
+    let res = f();
+
+    STDIN.with(|lines| lines.borrow_mut().clear());
+    let stdout = STDOUT
+        .with(|lines| lines.borrow_mut().take())
+        .unwrap_or_default();
+    (res, stdout)
+}
+
+pub fn write_line(line: &str) {
+    let captured = STDOUT.with(|lines| match *lines.borrow_mut() {
+        Some(ref mut lines) => {
+            lines.push(line.to_string());
+            true
+        }
+        None => false,
+    });
+    if captured {
+        return;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    emit_output_event(OutputEvent::Stdout, line);
+    #[cfg(not(target_arch = "wasm32"))]
+    println!("{}", line);
+}
+
+// Number of lines captured so far
+pub fn captured_len() -> usize {
+    STDOUT.with(|lines| lines.borrow().as_ref().map_or(0, |lines| lines.len()))
+}
+
+pub fn read_line() -> Option<String> {
+    STDIN.with(|lines| lines.borrow_mut().pop_front())
+}
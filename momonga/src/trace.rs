@@ -0,0 +1,122 @@
+// Execution trace for replaying a program step by step, in the style of Python Tutor.
+//
+//   {"steps": [Step, ...], "truncated": bool, "error": string | null}
+//
+//   Step     {"line": int, "call_stack": [string], "scopes": [[Variable]], "stdout": [string]}
+//            `scopes` lists the scope chain innermost first, `stdout` is everything printed so far
+//   Variable {"name": string, "value": string}
+//
+// `truncated` is set when the step cap is reached before the program finishes.
+
+use serde::Serialize;
+
+use crate::debugger::{record, Stop};
+use crate::error::{EvalError, ParseError};
+
+pub const DEFAULT_TRACE_STEP_CAP: u64 = 1_000;
+
+#[derive(Serialize)]
+struct Trace<'a> {
+    steps: Vec<Step<'a>>,
+    truncated: bool,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Step<'a> {
+    #[serde(flatten)]
+    stop: &'a Stop,
+    stdout: &'a [String],
+}
+
+// The cap counts executed statements and loop iterations, like the step limit of `judge`
+pub fn trace(source: &str, step_cap: u64) -> Result<String, ParseError> {
+    let recording = record(source, step_cap)?;
+    let steps = recording
+        .stops
+        .iter()
+        .map(|stop| Step {
+            stop,
+            stdout: &recording.stdout[..stop.stdout_len],
+        })
+        .collect();
+    let (truncated, error) = match &recording.result {
+        Ok(()) => (false, None),
+        Err(EvalError::StepLimit) => (true, None),
+        Err(eval_err) => (false, Some(eval_err.to_string())),
+    };
+    Ok(serde_json::to_string(&Trace {
+        steps,
+        truncated,
+        error,
+    })
+    .unwrap()) // Steps consist of strings and numbers only
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::trace::*;
+
+    fn trace_json(source: &str, step_cap: u64) -> serde_json::Value {
+        serde_json::from_str(&trace(source, step_cap).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn every_statement_is_recorded() {
+        let src =
+            "func double(n) {\n    return n * 2;\n}\nvar x = double(2);\nprint(x);\nx = \"a\";\n";
+        assert_eq!(
+            trace_json(src, DEFAULT_TRACE_STEP_CAP),
+            serde_json::json!({
+                "steps": [
+                    {"line": 1, "call_stack": [], "scopes": [[]], "stdout": []},
+                    {
+                        "line": 4,
+                        "call_stack": [],
+                        "scopes": [[{"name": "double", "value": "func"}]],
+                        "stdout": [],
+                    },
+                    {
+                        "line": 2,
+                        "call_stack": ["double"],
+                        "scopes": [
+                            [{"name": "n", "value": "2"}],
+                            [{"name": "double", "value": "func"}],
+                        ],
+                        "stdout": [],
+                    },
+                    {
+                        "line": 5,
+                        "call_stack": [],
+                        "scopes": [[{"name": "double", "value": "func"}, {"name": "x", "value": "4"}]],
+                        "stdout": [],
+                    },
+                    {
+                        "line": 6,
+                        "call_stack": [],
+                        "scopes": [[{"name": "double", "value": "func"}, {"name": "x", "value": "4"}]],
+                        "stdout": ["4"],
+                    },
+                ],
+                "truncated": false,
+                "error": null,
+            })
+        );
+    }
+
+    #[test]
+    fn trace_is_capped() {
+        let json = trace_json("var i = 0;\nwhile (true) {\n    i = i + 1;\n}\n", 10);
+        assert_eq!(json["truncated"], true);
+        assert_eq!(json["error"], serde_json::Value::Null);
+        assert!(json["steps"].as_array().unwrap().len() <= 10);
+    }
+
+    #[test]
+    fn runtime_error_ends_trace() {
+        let json = trace_json("print(1);\nprint(-true);\n", DEFAULT_TRACE_STEP_CAP);
+        assert_eq!(json["steps"].as_array().unwrap().len(), 2);
+        assert_eq!(json["truncated"], false);
+        assert_eq!(json["error"], "Type error");
+    }
+}
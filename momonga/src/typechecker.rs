@@ -0,0 +1,789 @@
+// Types inferred before running a program, to report operations which always fail with a type error
+
+use serde::Serialize;
+use std::{collections::HashMap, fmt, mem};
+
+use crate::ast::*;
+use crate::error::ParseError;
+use crate::formatter::{expr as format_expr, infix_symbol};
+use crate::parser::parse;
+use crate::resolver::{resolve, Resolution, SymbolKind};
+
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum Type {
+    Int,
+    Bool,
+    String,
+    Array,
+    None,
+    Unknown, // Values of more than one type, or of a type which cannot be inferred
+}
+
+impl From<TypeAnnotation> for Type {
+    fn from(annotation: TypeAnnotation) -> Self {
+        match annotation {
+            TypeAnnotation::Int => Type::Int,
+            TypeAnnotation::Bool => Type::Bool,
+            TypeAnnotation::String => Type::String,
+            TypeAnnotation::Array => Type::Array,
+            TypeAnnotation::None => Type::None,
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Type::Int => "int",
+            Type::Bool => "bool",
+            Type::String => "string",
+            Type::Array => "array",
+            Type::None => "none",
+            Type::Unknown => "unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct TypeMismatch {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize)]
+pub struct TypeCheck {
+    // Declared identifier of each variable and parameter with its type, and of each function with
+    // the type of what it returns
+    pub types: Vec<(Span, Type)>,
+    pub mismatches: Vec<TypeMismatch>, // In the order of their positions
+}
+
+// A variable has its annotated type, or else the type of every value assigned to it, or
+// `Type::Unknown` if they disagree. `var x;` assigns nothing, as `none` fails almost every
+// operation anyway.
+pub fn typecheck(source: &str) -> Result<TypeCheck, ParseError> {
+    let program = parse(source)?;
+    let resolution = resolve(source);
+    let mut symbols: HashMap<usize, usize> = resolution
+        .references
+        .iter()
+        .filter_map(|(span, symbol)| symbol.map(|symbol| (span.start, symbol)))
+        .collect();
+    for (index, symbol) in resolution.symbols.iter().enumerate() {
+        if symbol.kind != SymbolKind::Builtin {
+            symbols.insert(symbol.span.start, index);
+        }
+    }
+    let mut checker = Checker {
+        resolution: &resolution,
+        symbols,
+        types: vec![None; resolution.symbols.len()],
+        annotations: HashMap::new(),
+        func: None,
+        span: Span::default(),
+        mismatches: None,
+    };
+
+    // A call or an assignment may tell the type of a name used before it, so check again until
+    // nothing changes, which ends soon as a type only changes from none to known to unknown
+    loop {
+        let types = checker.types.clone();
+        checker.stmts(&program);
+        if checker.types == types {
+            break;
+        }
+    }
+    checker.mismatches = Some(vec![]);
+    checker.stmts(&program);
+
+    let types = resolution
+        .symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, symbol)| symbol.kind != SymbolKind::Builtin)
+        .map(|(index, symbol)| {
+            let type_ = checker.type_of(index).unwrap_or(Type::Unknown);
+            (symbol.span, type_)
+        })
+        .collect();
+    let mut mismatches = checker.mismatches.unwrap();
+    mismatches.sort_by_key(|mismatch| mismatch.span.start);
+    Ok(TypeCheck { types, mismatches })
+}
+
+struct Checker<'a> {
+    resolution: &'a Resolution,
+    symbols: HashMap<usize, usize>, // Start of each identifier and the index of its symbol
+    types: Vec<Option<Type>>,       // Of each symbol, `None` until a value is assigned to it
+    annotations: HashMap<usize, Type>, // Of symbols declared with types
+    func: Option<usize>,            // Symbol of the function being checked
+    span: Span,                     // Statement being checked, as expressions have no spans
+    mismatches: Option<Vec<TypeMismatch>>, // Collected once the types are settled
+}
+
+impl<'a> Checker<'a> {
+    fn join(&mut self, symbol: usize, type_: Type) {
+        self.types[symbol] = match self.types[symbol] {
+            Some(known) if known != type_ => Some(Type::Unknown),
+            _ => Some(type_),
+        };
+    }
+
+    // A value bound to a name declared with a type must be of the type
+    fn bind(&mut self, symbol: usize, type_: Type) {
+        let Some(&annotation) = self.annotations.get(&symbol) else {
+            self.join(symbol, type_);
+            return;
+        };
+        if type_ != annotation && type_ != Type::Unknown {
+            let symbol = &self.resolution.symbols[symbol];
+            let declared = match symbol.kind {
+                SymbolKind::Function => "to return",
+                _ => "as",
+            };
+            self.report(format!(
+                "`{}` is declared {} `{}`, but gets a value of type `{}`",
+                symbol.name, declared, annotation, type_
+            ));
+        }
+    }
+
+    fn annotate(&mut self, ident: &Ident, annotation: Option<TypeAnnotation>) {
+        if let (Some(&symbol), Some(annotation)) = (self.symbols.get(&ident.span.start), annotation)
+        {
+            self.annotations.insert(symbol, annotation.into());
+        }
+    }
+
+    fn assign(&mut self, ident: &Ident, type_: Option<Type>) {
+        if let (Some(&symbol), Some(type_)) = (self.symbols.get(&ident.span.start), type_) {
+            self.bind(symbol, type_);
+        }
+    }
+
+    fn type_of(&self, symbol: usize) -> Option<Type> {
+        self.annotations
+            .get(&symbol)
+            .copied()
+            .or(self.types[symbol])
+    }
+
+    fn report(&mut self, message: String) {
+        if let Some(mismatches) = &mut self.mismatches {
+            mismatches.push(TypeMismatch {
+                span: self.span,
+                message,
+            });
+        }
+    }
+
+    // Statements after a jump are never run, so nothing is inferred nor reported from them
+    fn stmts(&mut self, stmts: &[Stmt]) {
+        for stmt in reachable(stmts) {
+            self.stmt(stmt);
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        self.span = stmt.span();
+        match stmt {
+            Stmt::BlockStmt(stmts, _) => self.stmts(stmts),
+            Stmt::FuncDecl(
+                FuncDecl {
+                    ident_func,
+                    ident_param,
+                    annotation_param,
+                    default_param,
+                    annotation_return,
+                    block,
+                },
+                span,
+            ) => {
+                self.annotate(ident_func, *annotation_return);
+                for (ident, annotation) in ident_param.iter().zip(annotation_param) {
+                    self.annotate(ident, *annotation);
+                }
+                // A default value is assigned to its parameter like an argument
+                for (ident, default) in ident_param.iter().zip(default_param) {
+                    if let Some(default) = default {
+                        let type_ = self.expr(default);
+                        self.assign(ident, type_);
+                    }
+                }
+                let symbol = self.symbols.get(&ident_func.span.start).copied();
+                let func = mem::replace(&mut self.func, symbol);
+                self.stmts(block);
+                if let (Some(symbol), false) = (symbol, returns(block)) {
+                    self.span = *span;
+                    self.bind(symbol, Type::None);
+                }
+                self.func = func;
+            }
+            Stmt::IfStmt(if_stmt, span) => self.if_stmt(if_stmt, *span),
+            Stmt::ForStmt(
+                ForStmt {
+                    init,
+                    cond,
+                    afterthought,
+                    block,
+                    ..
+                },
+                span,
+            ) => {
+                match init {
+                    Some(ForStmtInit::Var(var_stmt)) => self.var_stmt(var_stmt),
+                    Some(ForStmtInit::Expr(expr)) => {
+                        self.expr(expr);
+                    }
+                    None => (),
+                }
+                if let Some(cond) = cond {
+                    self.condition(cond, *span);
+                }
+                if let Some(afterthought) = afterthought {
+                    self.expr(afterthought);
+                }
+                self.stmts(block);
+            }
+            Stmt::WhileStmt(WhileStmt { cond, block, .. }, span) => {
+                self.condition(cond, *span);
+                self.stmts(block);
+            }
+            Stmt::DoWhileStmt(DoWhileStmt { block, cond, .. }, span) => {
+                self.stmts(block);
+                self.condition(cond, *span);
+            }
+            Stmt::SwitchStmt(switch_stmt, span) => self.switch_stmt(switch_stmt, *span),
+            Stmt::VarStmt(var_stmt, _) => self.var_stmt(var_stmt),
+            Stmt::ExprStmt(expr, _) => {
+                self.expr(expr);
+            }
+            Stmt::ReturnStmt(ReturnStmt { expr }, _) => {
+                let type_ = match expr {
+                    Some(expr) => self.expr(expr),
+                    None => Some(Type::None),
+                };
+                if let (Some(func), Some(type_)) = (self.func, type_) {
+                    self.bind(func, type_);
+                }
+            }
+            Stmt::ContinueStmt(..) | Stmt::BreakStmt(..) => (),
+            Stmt::Unreachable(..) | Stmt::Error(_) => (),
+        }
+    }
+
+    fn var_stmt(&mut self, var_stmt: &VarStmt) {
+        self.annotate(&var_stmt.ident, var_stmt.annotation);
+        if let Some(expr) = &var_stmt.expr {
+            let type_ = self.expr(expr);
+            self.assign(&var_stmt.ident, type_);
+        }
+    }
+
+    fn if_stmt(&mut self, if_stmt: &IfStmt, span: Span) {
+        self.condition(&if_stmt.condition, span);
+        self.stmts(&if_stmt.block);
+        match &if_stmt.else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => self.if_stmt(if_stmt, span),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => self.stmts(block),
+            None => (),
+        }
+    }
+
+    // Cases are compared with `==`, which fails for values of different types
+    fn switch_stmt(&mut self, switch_stmt: &SwitchStmt, span: Span) {
+        let subject = &switch_stmt.subject;
+        let subject_type = known(self.expr(subject));
+        for SwitchStmtCase { value, block } in &switch_stmt.cases {
+            self.span = span;
+            let value_type = known(self.expr(value));
+            if let (Some(subject_type), Some(value_type)) = (subject_type, value_type) {
+                if subject_type != value_type {
+                    self.report(format!(
+                        "Case `{}` is a value of type `{}`, but `{}` is a value of type `{}`",
+                        format_expr(value),
+                        value_type,
+                        format_expr(subject),
+                        subject_type
+                    ));
+                }
+            }
+            self.stmts(block);
+        }
+        if let Some(block) = &switch_stmt.default {
+            self.stmts(block);
+        }
+    }
+
+    fn condition(&mut self, cond: &Expr, span: Span) {
+        self.span = span;
+        if let Some(type_) = known(self.expr(cond)).filter(|&type_| type_ != Type::Bool) {
+            self.report(format!(
+                "Condition `{}` is a value of type `{}`, but it must be a `bool`",
+                format_expr(cond),
+                type_
+            ));
+        }
+    }
+
+    // Type of the value of `expr`, `None` if it is made of names with no values assigned yet
+    fn expr(&mut self, expr: &Expr) -> Option<Type> {
+        match expr {
+            Expr::Literal(literal) => Some(match literal {
+                Literal::Bool(_) => Type::Bool,
+                Literal::Int(_) => Type::Int,
+                Literal::String(_) => Type::String,
+                Literal::Array(exprs) => {
+                    for expr in exprs {
+                        self.expr(expr);
+                    }
+                    Type::Array
+                }
+                Literal::None => Type::None,
+            }),
+            Expr::Ident(ident) => match self.symbols.get(&ident.span.start) {
+                Some(&symbol) => match self.resolution.symbols[symbol].kind {
+                    SymbolKind::Variable | SymbolKind::Constant | SymbolKind::Parameter => {
+                        self.type_of(symbol)
+                    }
+                    SymbolKind::Function | SymbolKind::Builtin => Some(Type::Unknown),
+                },
+                None => Some(Type::Unknown),
+            },
+            Expr::PrefixOp { kind, rhs } => {
+                let rhs_type = self.expr(rhs);
+                let (symbol, type_) = match kind {
+                    PrefixOpKind::Pos => ("+", Type::Int),
+                    PrefixOpKind::Neg => ("-", Type::Int),
+                    PrefixOpKind::Not => ("!", Type::Bool),
+                    PrefixOpKind::BitNot => ("~", Type::Int),
+                };
+                if let Some(rhs_type) = known(rhs_type).filter(|&rhs_type| rhs_type != type_) {
+                    self.report(format!(
+                        "`{}` cannot be applied to `{}` in `{}`",
+                        symbol,
+                        rhs_type,
+                        format_expr(expr)
+                    ));
+                }
+                Some(type_)
+            }
+            Expr::InfixOp { kind, lhs, rhs } if kind.is_assign() => {
+                let rhs_type = self.expr(rhs);
+                let type_ = match kind.compound() {
+                    Some(_) => {
+                        let lhs_type = self.expr(lhs);
+                        self.infix_op(kind, [lhs_type, rhs_type], expr)
+                    }
+                    None => rhs_type,
+                };
+                // A compound assignment keeps the type it was checked against
+                match lhs.as_ref() {
+                    _ if kind.compound().is_some() => (),
+                    Expr::Ident(ident) => self.assign(ident, type_),
+                    lhs => {
+                        self.expr(lhs);
+                    }
+                }
+                type_
+            }
+            Expr::InfixOp { kind, lhs, rhs } => {
+                let lhs_type = self.expr(lhs);
+                let rhs_type = self.expr(rhs);
+                self.infix_op(kind, [lhs_type, rhs_type], expr)
+            }
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Index(index),
+                lhs,
+            } => {
+                let lhs_type = self.expr(lhs);
+                let index_type = self.expr(index);
+                if let Some(type_) = known(lhs_type).filter(|&type_| type_ != Type::Array) {
+                    self.report(format!(
+                        "Only an `array` can be indexed, but `{}` is a value of type `{}`",
+                        format_expr(lhs),
+                        type_
+                    ));
+                }
+                if let Some(type_) = known(index_type).filter(|&type_| type_ != Type::Int) {
+                    self.report(format!(
+                        "An index must be an `int`, but `{}` is a value of type `{}`",
+                        format_expr(index),
+                        type_
+                    ));
+                }
+                Some(Type::Unknown)
+            }
+            Expr::PostfixOp {
+                kind: PostfixOpKind::Call(args),
+                lhs,
+            } => {
+                let arg_types: Vec<Option<Type>> = args.iter().map(|arg| self.expr(arg)).collect();
+                let symbol = match lhs.as_ref() {
+                    Expr::Ident(ident) => self.symbols.get(&ident.span.start).copied(),
+                    lhs => {
+                        self.expr(lhs);
+                        None
+                    }
+                };
+                let Some(symbol) = symbol else {
+                    return Some(Type::Unknown);
+                };
+                match self.resolution.symbols[symbol].kind {
+                    SymbolKind::Function => {
+                        // Parameters are declared right after their function
+                        let params = self.resolution.symbols[symbol + 1..]
+                            .iter()
+                            .take_while(|param| {
+                                param.kind == SymbolKind::Parameter
+                                    && param.scope == self.resolution.symbols[symbol].decl
+                            })
+                            .count();
+                        for (param, arg_type) in arg_types.into_iter().take(params).enumerate() {
+                            if let Some(arg_type) = arg_type {
+                                self.bind(symbol + 1 + param, arg_type);
+                            }
+                        }
+                        self.type_of(symbol)
+                    }
+                    SymbolKind::Builtin => {
+                        let name = &self.resolution.symbols[symbol].name;
+                        self.builtin(name, &arg_types, expr)
+                    }
+                    SymbolKind::Variable | SymbolKind::Constant | SymbolKind::Parameter => {
+                        Some(Type::Unknown)
+                    }
+                }
+            }
+            Expr::Conditional {
+                cond,
+                then,
+                otherwise,
+            } => {
+                self.condition(cond, self.span);
+                match (self.expr(then), self.expr(otherwise)) {
+                    (Some(then), Some(otherwise)) if then != otherwise => Some(Type::Unknown),
+                    (then, otherwise) => then.or(otherwise),
+                }
+            }
+        }
+    }
+
+    fn infix_op(
+        &mut self,
+        kind: &InfixOpKind,
+        types: [Option<Type>; 2],
+        expr: &Expr,
+    ) -> Option<Type> {
+        // Types of operands, which must be the same, and of the result, the operands' for `+`
+        let operator = kind.compound().unwrap_or_else(|| kind.clone()); // `+` for `+=`
+        let (operands, result): (&[Type], Option<Type>) = match &operator {
+            InfixOpKind::Add => (&[Type::Int, Type::String], None),
+            InfixOpKind::Subtract
+            | InfixOpKind::Multiply
+            | InfixOpKind::Divide
+            | InfixOpKind::Modulo
+            | InfixOpKind::Power
+            | InfixOpKind::BitAnd
+            | InfixOpKind::BitOr
+            | InfixOpKind::BitXor
+            | InfixOpKind::ShiftLeft
+            | InfixOpKind::ShiftRight => (&[Type::Int], Some(Type::Int)),
+            InfixOpKind::Gt | InfixOpKind::Ge | InfixOpKind::Lt | InfixOpKind::Le => {
+                (&[Type::Int], Some(Type::Bool))
+            }
+            InfixOpKind::Eq | InfixOpKind::NotEq => (
+                &[Type::Bool, Type::Int, Type::String, Type::Array],
+                Some(Type::Bool),
+            ),
+            InfixOpKind::And | InfixOpKind::Or => (&[Type::Bool], Some(Type::Bool)),
+            InfixOpKind::Assign
+            | InfixOpKind::AddAssign
+            | InfixOpKind::SubtractAssign
+            | InfixOpKind::MultiplyAssign
+            | InfixOpKind::DivideAssign
+            | InfixOpKind::ModuloAssign => unreachable!(),
+        };
+        let mismatch = match types.map(known) {
+            [Some(lhs), Some(rhs)] if !operands.contains(&lhs) || !operands.contains(&rhs) => {
+                Some(format!("`{}` and `{}`", lhs, rhs))
+            }
+            [Some(lhs), Some(rhs)] if lhs != rhs => Some(format!("`{}` and `{}`", lhs, rhs)),
+            [Some(type_), None] | [None, Some(type_)] if !operands.contains(&type_) => {
+                Some(format!("`{}`", type_))
+            }
+            _ => None,
+        };
+        if let Some(mismatch) = mismatch {
+            self.report(format!(
+                "`{}` cannot be applied to {} in `{}`",
+                infix_symbol(kind),
+                mismatch,
+                format_expr(expr)
+            ));
+            return result.or(Some(Type::Unknown));
+        }
+        match types {
+            [None, None] => result,
+            _ => result.or_else(|| {
+                types
+                    .into_iter()
+                    .flat_map(known)
+                    .next()
+                    .or(Some(Type::Unknown))
+            }),
+        }
+    }
+
+    // What a builtin returns, reporting the first argument if the builtin always rejects it
+    fn builtin(&mut self, name: &str, arg_types: &[Option<Type>], expr: &Expr) -> Option<Type> {
+        let (params, type_): (&[Type], Type) = match name {
+            "len" => (&[Type::String, Type::Array], Type::Int),
+            "push" => (&[Type::Array], Type::Array),
+            "pop" => (&[Type::Array], Type::Unknown),
+            "print" => (&[], Type::None),
+            "abs" | "min" | "max" | "pow" | "sqrt" | "gcd" => (&[Type::Int], Type::Int),
+            _ => (&[], Type::Unknown), // `input` returns `none` at the end of the input
+        };
+        // Math builtins take integers only, while the others restrict their first argument
+        let checked = match name {
+            "len" | "push" | "pop" => &arg_types[..arg_types.len().min(1)],
+            _ => arg_types,
+        };
+        for &arg_type in checked {
+            if let Some(arg_type) =
+                known(arg_type).filter(|arg_type| !params.is_empty() && !params.contains(arg_type))
+            {
+                self.report(format!(
+                    "`{}` cannot be applied to `{}` in `{}`",
+                    name,
+                    arg_type,
+                    format_expr(expr)
+                ));
+            }
+        }
+        Some(type_)
+    }
+}
+
+// A type which is certain, leaving out unknown ones and ones of names with no values yet
+fn known(type_: Option<Type>) -> Option<Type> {
+    type_.filter(|&type_| type_ != Type::Unknown)
+}
+
+// Whether running `stmts` always ends with `return`, so that a function does not return `none`
+fn returns(stmts: &[Stmt]) -> bool {
+    stmts.iter().any(|stmt| match stmt {
+        Stmt::ReturnStmt(..) => true,
+        Stmt::BlockStmt(stmts, _) => returns(stmts),
+        Stmt::IfStmt(if_stmt, _) => if_returns(if_stmt),
+        _ => false,
+    })
+}
+
+fn if_returns(if_stmt: &IfStmt) -> bool {
+    returns(&if_stmt.block)
+        && match &if_stmt.else_clause {
+            Some(IfStmtElseClause::IfStmt(if_stmt)) => if_returns(if_stmt),
+            Some(IfStmtElseClause::IfStmtBlock(block)) => returns(block),
+            None => false,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::typechecker::*;
+
+    #[test]
+    fn types_are_inferred() {
+        let tests = [
+            ("var x = 1;", vec![("x", Type::Int)]),
+            ("var x = \"a\" + \"b\";", vec![("x", Type::String)]),
+            ("var x = [1] == [2];", vec![("x", Type::Bool)]),
+            ("var x = len([]);", vec![("x", Type::Int)]),
+            ("var x = input();", vec![("x", Type::Unknown)]),
+            ("var x = 1;\nx = true;", vec![("x", Type::Unknown)]),
+            ("var x;\nx = push([], 1);", vec![("x", Type::Array)]),
+            ("var x;", vec![("x", Type::Unknown)]),
+            (
+                "func f(a) { return a + 1; }\nf(1);",
+                vec![("f", Type::Int), ("a", Type::Int)],
+            ),
+            (
+                "func f(a) { return a; }\nf(1);\nf(\"a\");",
+                vec![("f", Type::Unknown), ("a", Type::Unknown)],
+            ),
+            ("func f() { print(1); }", vec![("f", Type::None)]),
+            (
+                "func f(a) { if (a) { return 1; } }\nf(true);",
+                vec![("f", Type::Unknown), ("a", Type::Bool)],
+            ),
+            (
+                "func f(a) { if (a) { return 1; } else { return 2; } }",
+                vec![("f", Type::Int), ("a", Type::Unknown)],
+            ),
+            (
+                "var x = f();\nfunc f() { return g(); }\nfunc g() { return \"a\"; }",
+                vec![
+                    ("x", Type::String),
+                    ("f", Type::String),
+                    ("g", Type::String),
+                ],
+            ),
+            (
+                "func fact(n) { if (n <= 1) { return 1; } return n * fact(n - 1); }\nfact(5);",
+                vec![("fact", Type::Int), ("n", Type::Int)],
+            ),
+            ("var x: int;", vec![("x", Type::Int)]),
+            (
+                "func f(a: string): int { return len(a); }",
+                vec![("f", Type::Int), ("a", Type::String)],
+            ),
+        ];
+        for (src, expected) in tests {
+            let types: Vec<(&str, Type)> = typecheck(src)
+                .unwrap()
+                .types
+                .iter()
+                .map(|(span, type_)| (&src[span.start..span.end], *type_))
+                .collect();
+            assert_eq!(types, expected, "Failed in test case: {}", src);
+        }
+    }
+
+    #[test]
+    fn mismatches_are_reported() {
+        let tests = [
+            ("var x = 1 + 2;\nprint(x * 3);", vec![]),
+            (
+                "var x = 1 + \"a\";",
+                vec!["`+` cannot be applied to `int` and `string` in `1 + \"a\"`"],
+            ),
+            (
+                "var s = \"a\";\nprint(s - 1);",
+                vec!["`-` cannot be applied to `string` and `int` in `s - 1`"],
+            ),
+            (
+                "var a = [1];\nprint(true + pop(a));",
+                vec!["`+` cannot be applied to `bool` in `true + pop(a)`"],
+            ),
+            ("var a = [1];\nprint(1 + pop(a));", vec![]),
+            (
+                "var s = \"a\";\ns *= 2;",
+                vec!["`*=` cannot be applied to `string` and `int` in `s *= 2`"],
+            ),
+            (
+                "var s = \"a\";\ns += \"b\";\nprint(s - 1);",
+                vec!["`-` cannot be applied to `string` and `int` in `s - 1`"],
+            ),
+            (
+                "var x;\nprint(x == none);\nx = 1;",
+                vec!["`==` cannot be applied to `int` and `none` in `x == none`"],
+            ),
+            ("print(!1);", vec!["`!` cannot be applied to `int` in `!1`"]),
+            (
+                "var b = true;\nprint(~b);\nprint((1 | 2) << b);",
+                vec![
+                    "`~` cannot be applied to `bool` in `~b`",
+                    "`<<` cannot be applied to `int` and `bool` in `(1 | 2) << b`",
+                ],
+            ),
+            (
+                "var x = 1;\nif (x) {}",
+                vec!["Condition `x` is a value of type `int`, but it must be a `bool`"],
+            ),
+            (
+                "var n = 0;\ndo { n += 1; } while (n);",
+                vec!["Condition `n` is a value of type `int`, but it must be a `bool`"],
+            ),
+            (
+                "var n = 1;\nvar s = n ? \"a\" : \"b\";\nprint(s - 1);",
+                vec![
+                    "Condition `n` is a value of type `int`, but it must be a `bool`",
+                    "`-` cannot be applied to `string` and `int` in `s - 1`",
+                ],
+            ),
+            ("var b = true;\nvar x = b ? 1 : \"a\";\nprint(x - 1);", vec![]),
+            (
+                "var x = 1;\nswitch (x) { case -1: {} case \"a\": {} default: { print(x + true); } }",
+                vec![
+                    "Case `\"a\"` is a value of type `string`, but `x` is a value of type `int`",
+                    "`+` cannot be applied to `int` and `bool` in `x + true`",
+                ],
+            ),
+            (
+                "var a = \"abc\";\nprint(a[0]);",
+                vec!["Only an `array` can be indexed, but `a` is a value of type `string`"],
+            ),
+            (
+                "var a = [1];\nprint(a[true]);",
+                vec!["An index must be an `int`, but `true` is a value of type `bool`"],
+            ),
+            (
+                "print(len(1));",
+                vec!["`len` cannot be applied to `int` in `len(1)`"],
+            ),
+            (
+                "print(max(1, \"a\", 2) ** 2);",
+                vec!["`max` cannot be applied to `string` in `max(1, \"a\", 2)`"],
+            ),
+            ("var x: int = gcd(12, 18) ** abs(-1);", vec![]),
+            (
+                "func f(a) { return a * 2; }\nf(\"a\");",
+                vec!["`*` cannot be applied to `string` and `int` in `a * 2`"],
+            ),
+            (
+                "func f(a) { return a; }\nvar s = f(1) + \"a\";",
+                vec!["`+` cannot be applied to `int` and `string` in `f(1) + \"a\"`"],
+            ),
+            ("func f(a) { return a * 2; }\nf(1);\nf(\"a\");", vec![]),
+            ("var x = 1;\nwhile (true) { break; x = x && true; }", vec![]),
+            (
+                "var x: int = \"a\";",
+                vec!["`x` is declared as `int`, but gets a value of type `string`"],
+            ),
+            (
+                "var x: int;\nx = 1;\nx = none;",
+                vec!["`x` is declared as `int`, but gets a value of type `none`"],
+            ),
+            (
+                "func f(a: int) { return; }\nf(true);",
+                vec!["`a` is declared as `int`, but gets a value of type `bool`"],
+            ),
+            (
+                "func f(a, b: int = a) { return; }\nf(\"a\");",
+                vec!["`b` is declared as `int`, but gets a value of type `string`"],
+            ),
+            (
+                "func f(): int { print(1); }",
+                vec!["`f` is declared to return `int`, but gets a value of type `none`"],
+            ),
+            (
+                "func f(a: array): array { return push(a, 1); }\nf([]);",
+                vec![],
+            ),
+        ];
+        for (src, expected) in tests {
+            let messages: Vec<String> = typecheck(src)
+                .unwrap()
+                .mismatches
+                .into_iter()
+                .map(|mismatch| mismatch.message)
+                .collect();
+            assert_eq!(messages, expected, "Failed in test case: {}", src);
+        }
+    }
+
+    #[test]
+    fn mismatches_are_positioned() {
+        let src = "var s = \"a\";\nif (s) {\n    print(-s);\n}";
+        let spans: Vec<&str> = typecheck(src)
+            .unwrap()
+            .mismatches
+            .iter()
+            .map(|mismatch| &src[mismatch.span.start..mismatch.span.end])
+            .collect();
+        assert_eq!(spans, vec![src[13..].trim_end(), "print(-s);"]);
+    }
+}
@@ -0,0 +1,140 @@
+// Annotations are only checked by the interpreter, so these are kept apart from `integration_test.rs`
+// whose snippets the generated code of `codegen_test.rs` is checked to agree with
+extern crate momonga;
+use crate::momonga::*;
+
+#[test]
+fn annotated_declarations_work_like_untyped_ones() {
+    let tests = [
+        (
+            r#"
+            var x: int = 1;
+            x = x + 1;
+            x;
+            "#,
+            Some("2".to_string()),
+        ),
+        (
+            r#"
+            func greet(name: string, times: int): string {
+                var s: string = "";
+                for (var i: int = 0; i < times; i = i + 1) {
+                    s = s + name;
+                }
+                return s;
+            }
+            greet("a", 3);
+            "#,
+            Some("aaa".to_string()),
+        ),
+        (
+            r#"
+            func f(xs: array, b: bool, n: none): none {
+                return;
+            }
+            f([], true, none);
+            "#,
+            Some("none".to_string()),
+        ),
+        // Annotated variables start as `none` without initialization
+        (
+            r#"
+            var x: int;
+            print(x);
+            x;
+            "#,
+            Some("none".to_string()),
+        ),
+        (
+            r#"
+            var x: int = 1;
+            var x = "a";
+            x;
+            "#,
+            Some("a".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn values_of_other_types_are_type_errors() {
+    let tests = [
+        (
+            r#"
+            var x: int = "1";
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            var x: bool;
+            x = 0;
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            var x: int;
+            x = x;
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            var x: string = "a";
+            func f() { x = none; }
+            f();
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            func f(a: int) { a = "a"; }
+            f(1);
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            func f(a, b: array) { return a; }
+            f(1, 2);
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            func f(a, b: int = "a") { return a; }
+            f(1);
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            func f(): int { return "1"; }
+            f();
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            func f(): string {}
+            f();
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            for (var i: int = true; ; ) {}
+            "#,
+            Some("Type error".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
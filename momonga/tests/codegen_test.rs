@@ -0,0 +1,436 @@
+extern crate momonga;
+use crate::momonga::*;
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::{env, fs, path::Path};
+
+// Source snippets of `integration_test.rs` grouped by test function, skipping commented out ones
+fn integration_snippets() -> Vec<(String, Vec<String>)> {
+    let source = fs::read_to_string("tests/integration_test.rs").unwrap();
+    let mut tests: Vec<(String, Vec<String>)> = vec![];
+    let mut lines = source.lines();
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim_start();
+        if let Some(name) = trimmed.strip_prefix("fn ") {
+            tests.push((name.split('(').next().unwrap().to_string(), vec![]));
+        } else if !trimmed.starts_with("//") && trimmed.ends_with("r#\"") {
+            let mut snippet = String::new();
+            for line in lines.by_ref() {
+                if line.trim_start().starts_with("\"#") {
+                    break;
+                }
+                snippet.push_str(line);
+                snippet.push('\n');
+            }
+            tests.last_mut().unwrap().1.push(snippet);
+        }
+    }
+    tests
+}
+
+// Lines printed by `eval` until the program finishes, and the message of the error ending it if any
+fn eval_output(src: &str) -> (Vec<String>, Option<String>) {
+    let mut debugger = Debugger::new(src, DEFAULT_STEP_LIMIT).unwrap();
+    while debugger.resume().is_some() {}
+    (debugger.stdout().to_vec(), debugger.error_message())
+}
+
+// Compare generated code with `tests/golden/<dir>`, or rewrite the files with UPDATE_GOLDEN=1
+fn assert_golden(
+    dir: &str,
+    extension: &str,
+    comment: &str,
+    generate: fn(&str) -> Result<String, ParseError>,
+) {
+    let update = env::var("UPDATE_GOLDEN").is_ok();
+    for (name, snippets) in integration_snippets() {
+        if snippets.is_empty() {
+            continue;
+        }
+        let mut generated = String::new();
+        for (i, snippet) in snippets.iter().enumerate() {
+            generated.push_str(&format!("{} --- {} ---\n", comment, i + 1));
+            match generate(snippet) {
+                Ok(code) => generated.push_str(&code),
+                Err(parse_err) => generated.push_str(&format!("{} {}\n", comment, parse_err)),
+            }
+        }
+
+        let path = Path::new("tests/golden")
+            .join(dir)
+            .join(format!("{}.{}", name, extension));
+        if update {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, generated).unwrap();
+        } else {
+            let golden = fs::read_to_string(&path).unwrap_or_default();
+            assert_eq!(
+                generated,
+                golden,
+                "Failed in golden file: {}",
+                path.display()
+            );
+        }
+    }
+}
+
+#[test]
+fn python_matches_golden_files() {
+    assert_golden("python", "py", "#", |src| {
+        to_python(src).map(|side_by_side| side_by_side.target)
+    });
+}
+
+// Printed lines and the value of the trailing expression statement of each program run by Python,
+// formatted like `interpret`, or the name of the exception raised
+const PYTHON_RUNNER: &str = r#"
+import ast, json, sys
+
+def show(value):
+    if value is None:
+        return "none"
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, list):
+        return "[" + ", ".join(map(show, value)) + "]"
+    return str(value)
+
+results = []
+for code in json.load(sys.stdin):
+    stdout = []
+    scope = {"print": lambda *values: stdout.append(" ".join(map(show, values))), "input": lambda: None}
+    tree = ast.parse(code)
+    if tree.body and isinstance(tree.body[-1], ast.Expr):
+        target = ast.Name("__result__", ast.Store())
+        tree.body[-1] = ast.copy_location(ast.Assign([target], tree.body[-1].value), tree.body[-1])
+        ast.fix_missing_locations(tree)
+    try:
+        exec(compile(tree, "<momonga>", "exec"), scope)
+        result = show(scope["__result__"]) if "__result__" in scope else None
+    except Exception as err:
+        result = type(err).__name__
+    results.append({"stdout": stdout, "result": result})
+print(json.dumps(results))
+"#;
+
+// Snippets relying on what the Python backend deliberately does in Python's own way
+const PYTHON_DIVERGENCES: [&str; 4] = [
+    "(2 - 3) / 5;",
+    "(2 - 3) % 5;",
+    "3 << 63; // Bits shifted out are lost",
+    "push([1, 2, 3], 4);",
+];
+
+#[test]
+fn python_agrees_with_eval() {
+    let python = Command::new("python3")
+        .args(["-c", PYTHON_RUNNER])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn();
+    let Ok(mut python) = python else {
+        eprintln!("Skipped as Python is not available");
+        return;
+    };
+
+    // Only programs `eval` runs to the end, as the Python code does not emulate runtime errors
+    let srcs: Vec<String> = integration_snippets()
+        .into_iter()
+        .flat_map(|(_, snippets)| snippets)
+        .chain(PRINTING_PROGRAMS.iter().map(|src| src.to_string()))
+        .filter(|src| !PYTHON_DIVERGENCES.contains(&src.trim()))
+        .filter(|src| to_python(src).is_ok() && eval_output(src).1.is_none())
+        .collect();
+    let codes: Vec<String> = srcs
+        .iter()
+        .map(|src| to_python(src).unwrap().target)
+        .collect();
+    python
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(serde_json::to_string(&codes).unwrap().as_bytes())
+        .unwrap();
+    let output = python.wait_with_output().unwrap();
+    let results: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+
+    for ((src, code), result) in srcs.iter().zip(&codes).zip(results) {
+        let (stdout, _) = eval_output(src);
+        assert_eq!(
+            result["stdout"],
+            serde_json::json!(stdout),
+            "Failed in test case: {}\n{}",
+            src,
+            code
+        );
+        // Statements other than expressions have no value in Python
+        if let (Some(expected), false) = (interpret(src), result["result"].is_null()) {
+            assert_eq!(
+                result["result"], expected,
+                "Failed in test case: {}\n{}",
+                src, code
+            );
+        }
+    }
+}
+
+// Completion value or error of each program run by Node.js, formatted like `interpret`
+const NODE_RUNNER: &str = r#"
+const vm = require("vm");
+const str = (v) => (v == null ? "none" : Array.isArray(v) ? `[${v.map(str).join(", ")}]` : String(v));
+const errors = { TypeError: "Type error", ReferenceError: "Name error" };
+const codes = JSON.parse(require("fs").readFileSync(0, "utf8"));
+const results = codes.map((code) => {
+    const stdout = [];
+    try {
+        const value = vm.runInNewContext(code, { console: { log: (line) => stdout.push(line) }, TextEncoder });
+        return { stdout, result: str(value) };
+    } catch (err) {
+        return { stdout, result: errors[err.name] ?? err.message };
+    }
+});
+console.log(JSON.stringify(results));
+"#;
+
+#[test]
+fn javascript_agrees_with_eval() {
+    let node = Command::new("node")
+        .args(["-e", NODE_RUNNER])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn();
+    let Ok(mut node) = node else {
+        eprintln!("Skipped as Node.js is not available");
+        return;
+    };
+
+    let srcs: Vec<String> = integration_snippets()
+        .into_iter()
+        .flat_map(|(_, snippets)| snippets)
+        .chain(PRINTING_PROGRAMS.iter().map(|src| src.to_string()))
+        .filter(|src| to_javascript(src).is_ok())
+        .collect();
+    let codes: Vec<String> = srcs
+        .iter()
+        .map(|src| to_javascript(src).unwrap().target)
+        .collect();
+    node.stdin
+        .take()
+        .unwrap()
+        .write_all(serde_json::to_string(&codes).unwrap().as_bytes())
+        .unwrap();
+    let output = node.wait_with_output().unwrap();
+    let results: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).unwrap();
+
+    for ((src, code), result) in srcs.iter().zip(&codes).zip(results) {
+        // Including the lines printed before a runtime error
+        let (stdout, _) = eval_output(src);
+        assert_eq!(
+            result["stdout"],
+            serde_json::json!(stdout),
+            "Failed in test case: {}\n{}",
+            src,
+            code
+        );
+
+        // Programs ending with a declaration have no value in Momonga, unlike in JavaScript
+        if let Some(expected) = interpret(src) {
+            assert_eq!(
+                result["result"], expected,
+                "Failed in test case: {}\n{}",
+                src, code
+            );
+        }
+    }
+}
+
+// Programs printing their results, in addition to the snippets
+const PRINTING_PROGRAMS: [&str; 5] = [
+    r#"
+for (var i = 1; i <= 15; i = i + 1) {
+    if (i % 15 == 0) { print("FizzBuzz"); } else if (i % 3 == 0) { print("Fizz"); } else if (i % 5 == 0) { print("Buzz"); } else { print(i); }
+}
+"#,
+    r#"
+func sort(xs) {
+    for (var i = 0; i < len(xs); i = i + 1) {
+        for (var j = 0; j < len(xs) - 1 - i; j = j + 1) {
+            if (xs[j] > xs[j + 1]) {
+                var ys = [];
+                for (var k = 0; k < len(xs); k = k + 1) {
+                    if (k == j) { push(ys, xs[j + 1]); } else if (k == j + 1) { push(ys, xs[j]); } else { push(ys, xs[k]); }
+                }
+                xs = ys;
+            }
+        }
+    }
+    return xs;
+}
+print(sort([5, -3, 9, 0, 2]));
+print(sort([]) == []);
+"#,
+    r#"
+func counter(n) {
+    var total = 0;
+    func add(x) { total = total + x; }
+    for (var i = 0; i < n; i = i + 1) { add(i); }
+    return total;
+}
+print(counter(10));
+var s = "";
+while (len(s) < 5) { s = s + "ab"; }
+print(s);
+print([[1, "a"], [true, none], []]);
+"#,
+    r#"
+var xs = [1, 2, 3];
+var ys = xs;
+print(pop(ys));
+print(xs);
+print(push(xs, [4]) == [1, 2, [4]]);
+print(-7 / 2);
+print(-7 % 2);
+print(xs[3]);
+"#,
+    r#"
+func f(a, b) { return b; }
+print(f(1, 2, 3));
+print(f(1));
+"#,
+];
+
+#[test]
+fn c_agrees_with_eval() {
+    if Command::new("cc").arg("--version").output().is_err() {
+        eprintln!("Skipped as no C compiler is available");
+        return;
+    }
+    let dir = env::temp_dir().join(format!("momonga-c-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+
+    let srcs: Vec<String> = integration_snippets()
+        .into_iter()
+        .flat_map(|(_, snippets)| snippets)
+        .chain(PRINTING_PROGRAMS.iter().map(|src| src.to_string()))
+        .filter(|src| to_c(src).is_ok())
+        .collect();
+    let exes: Vec<_> = (0..srcs.len())
+        .map(|i| dir.join(format!("program_{}", i)))
+        .collect();
+    let children: Vec<_> = srcs
+        .iter()
+        .zip(&exes)
+        .map(|(src, exe)| {
+            let c_path = exe.with_extension("c");
+            fs::write(&c_path, to_c(src).unwrap().target).unwrap();
+            Command::new("cc")
+                .args(["-std=c99", "-Wall", "-Wno-unused-function", "-Werror", "-o"])
+                .arg(exe)
+                .arg(c_path)
+                .spawn()
+                .unwrap()
+        })
+        .collect();
+    for (child, src) in children.into_iter().zip(&srcs) {
+        let status = child.wait_with_output().unwrap().status;
+        assert!(status.success(), "Failed to compile test case: {}", src);
+    }
+
+    for (src, exe) in srcs.iter().zip(&exes) {
+        let output = Command::new(exe).stdin(Stdio::null()).output().unwrap();
+        let (stdout, error) = eval_output(src);
+        let actual_stdout = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(
+            actual_stdout.lines().collect::<Vec<_>>(),
+            stdout,
+            "Failed in test case: {}",
+            src
+        );
+        match error {
+            None => assert!(output.status.success(), "Failed in test case: {}", src),
+            Some(message) => assert_eq!(
+                String::from_utf8(output.stderr).unwrap().trim_end(),
+                message,
+                "Failed in test case: {}",
+                src
+            ),
+        }
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+// Output lines and the error message of a compiled module
+#[derive(Default)]
+struct WasmHost {
+    stdout: String,
+    error: Option<String>,
+}
+
+// String the module passes to an import by its address and length
+fn read_string(caller: &wasmi::Caller<WasmHost>, ptr: i32, len: i32) -> String {
+    let memory = caller.get_export("memory").unwrap().into_memory().unwrap();
+    let mut bytes = vec![0; len as usize];
+    memory.read(caller, ptr as usize, &mut bytes).unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+fn run_wasm(bytes: &[u8]) -> Result<WasmHost, wasmi::Error> {
+    let mut config = wasmi::Config::default();
+    config.consume_fuel(true);
+    let engine = wasmi::Engine::new(&config);
+    let module = wasmi::Module::new(&engine, bytes)?;
+    let mut store = wasmi::Store::new(&engine, WasmHost::default());
+    store.add_fuel(100_000_000)?;
+
+    let mut linker = wasmi::Linker::new(&engine);
+    linker.func_wrap(
+        "env",
+        "print",
+        |mut caller: wasmi::Caller<WasmHost>, ptr: i32, len: i32| {
+            let line = read_string(&caller, ptr, len);
+            let host = caller.data_mut();
+            host.stdout.push_str(&line);
+            host.stdout.push('\n');
+        },
+    )?;
+    linker.func_wrap("env", "input", || -> i32 { -1 })?;
+    linker.func_wrap(
+        "env",
+        "error",
+        |mut caller: wasmi::Caller<WasmHost>, ptr: i32, len: i32| {
+            caller.data_mut().error = Some(read_string(&caller, ptr, len));
+        },
+    )?;
+    let instance = linker.instantiate(&mut store, &module)?.start(&mut store)?;
+    let main = instance.get_typed_func::<(), ()>(&store, "main")?;
+    if let Err(trap) = main.call(&mut store, ()) {
+        if store.data().error.is_none() {
+            return Err(trap.into());
+        }
+    }
+    Ok(store.into_data())
+}
+
+#[test]
+fn wasm_agrees_with_eval() {
+    let srcs = integration_snippets()
+        .into_iter()
+        .flat_map(|(_, snippets)| snippets)
+        .chain(PRINTING_PROGRAMS.iter().map(|src| src.to_string()));
+    for src in srcs {
+        let Ok(bytes) = to_wasm(&src) else {
+            continue;
+        };
+        let host =
+            run_wasm(&bytes).unwrap_or_else(|err| panic!("Failed in test case: {}\n{}", src, err));
+        let (stdout, error) = eval_output(&src);
+        assert_eq!(
+            host.stdout.lines().collect::<Vec<_>>(),
+            stdout,
+            "Failed in test case: {}",
+            src
+        );
+        assert_eq!(host.error, error, "Failed in test case: {}", src);
+    }
+}
@@ -0,0 +1,43 @@
+# --- 1 ---
+12 & 10
+# --- 2 ---
+12 | 10
+# --- 3 ---
+12 ^ 10
+# --- 4 ---
+~0
+# --- 5 ---
+~5
+# --- 6 ---
+1 << 62
+# --- 7 ---
+3 << 63
+# --- 8 ---
+-16 >> 2
+# --- 9 ---
+-1 >> 63
+# --- 10 ---
+5 >> 1
+# --- 11 ---
+6 & 3 == 2
+# --- 12 ---
+1 | 2 ^ 3 & 5
+# --- 13 ---
+1 << 2 + 1
+# --- 14 ---
+1 << 2 < 5
+# --- 15 ---
+n = 255
+count = 0
+while n != 0:
+    n = n & n - 1
+    count += 1
+count
+# --- 16 ---
+1 << 64
+# --- 17 ---
+1 >> -1
+# --- 18 ---
+6 & (3 == 2)
+# --- 19 ---
+~"a"
@@ -0,0 +1,11 @@
+# --- 1 ---
+arr = [1, 2, 3, "foo", [True]]
+len(arr)
+# --- 2 ---
+len("Hello, World!")
+# --- 3 ---
+len()
+# --- 4 ---
+len([1, 2, 3], [4, 5, 6])
+# --- 5 ---
+len(1)
@@ -0,0 +1,39 @@
+# --- 1 ---
+abs(-5) + abs(5)
+# --- 2 ---
+abs(-9223372036854775807 - 1)
+# --- 3 ---
+min(3, 1, 2)
+# --- 4 ---
+max(3, 1, 2, -4)
+# --- 5 ---
+min(1)
+# --- 6 ---
+max(1, True)
+# --- 7 ---
+pow(-3, 3)
+# --- 8 ---
+pow(10, 19)
+# --- 9 ---
+import math
+math.isqrt(15) + math.isqrt(16)
+# --- 10 ---
+import math
+math.isqrt(9223372036854775807)
+# --- 11 ---
+import math
+math.isqrt(-1)
+# --- 12 ---
+import math
+math.gcd(12, -18)
+# --- 13 ---
+import math
+math.gcd(0, 0)
+# --- 14 ---
+import math
+math.gcd(-9223372036854775807 - 1, 0)
+# --- 15 ---
+import math
+math.gcd(4, "2")
+# --- 16 ---
+sqrt()
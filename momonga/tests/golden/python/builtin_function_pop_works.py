@@ -0,0 +1,19 @@
+# --- 1 ---
+[1, 2, 3].pop()
+# --- 2 ---
+arr = [1, 2, 3]
+arr.pop()
+arr.pop()
+# --- 3 ---
+arr = [1, 2, 3]
+arr.pop()
+arr.pop()
+arr.pop()
+# --- 4 ---
+arr = [1, 2, 3]
+arr.pop()
+arr.pop()
+arr.pop()
+arr.pop()
+# --- 5 ---
+"not array".pop()
@@ -0,0 +1,15 @@
+# --- 1 ---
+print(1, "a", [2, "b"], True, None)
+# --- 2 ---
+x = 1
+print("x =", x)
+# --- 3 ---
+print()
+# --- 4 ---
+print(1, y)
+# --- 5 ---
+print(1, "a", [2, "b"], True, None)
+# --- 6 ---
+x = 1
+print("x =", x)
+print("x + 1 =", x + 1)
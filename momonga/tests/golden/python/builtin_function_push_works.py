@@ -0,0 +1,12 @@
+# --- 1 ---
+[1, 2, 3].append(4)
+# --- 2 ---
+arr = [1]
+arr.extend([2, [3], "4"])
+arr
+# --- 3 ---
+push()
+# --- 4 ---
+push([1, 2, 3])
+# --- 5 ---
+"not array".append(1)
@@ -0,0 +1,6 @@
+# --- 1 ---
+# --- 2 ---
+x = None
+if True:
+    x = 1
+x
@@ -0,0 +1,57 @@
+# --- 1 ---
+x = 1
+x += 2
+x -= 1
+x *= 6
+x //= 4
+x %= 2
+x
+# --- 2 ---
+s = "a"
+s += "b"
+s
+# --- 3 ---
+s = "a"
+t = s
+u = s + "b"
+s += "c"
+[s, t, u, s + s]
+# --- 4 ---
+a = [1, 2]
+a[1] += 10
+a[0] = 5
+a
+# --- 5 ---
+x = 1
+x += 1 + 2 * 3
+x
+# --- 6 ---
+x = 10
+y = 2
+x -= (y := y - 1)
+x
+# --- 7 ---
+sum = 0
+for i in range(5):
+    sum += i
+sum
+# --- 8 ---
+x = 1
+x += "a"
+# --- 9 ---
+x = 1
+x //= 0
+# --- 10 ---
+y += 1
+# --- 11 ---
+a = [1]
+a[1] += 1
+# --- 12 ---
+a = [1]
+a[-1] = 1
+# --- 13 ---
+s = "ab"
+s[0] = "c"
+# --- 14 ---
+x = 1
+x += 1
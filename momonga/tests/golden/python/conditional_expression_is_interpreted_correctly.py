@@ -0,0 +1,19 @@
+# --- 1 ---
+x = -3
+x if x > 0 else -x
+# --- 2 ---
+def sign(n):
+    return 1 if n > 0 else -1 if n < 0 else 0
+[sign(5), sign(-5), sign(0)]
+# --- 3 ---
+x = 1
+y = "small" if x == 1 or x == 2 else "large"
+(10 if x > 0 else 20) + 1
+y + ("a" if False else "b")
+# --- 4 ---
+count = 0
+y = 1 if True else (count := 100)
+y = [][5] if False else (count := count + 1)
+[count, y]
+# --- 5 ---
+2 if 1 else 3
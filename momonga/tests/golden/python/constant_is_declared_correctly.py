@@ -0,0 +1,23 @@
+# --- 1 ---
+x = 1
+x + 1
+# --- 2 ---
+constant = 1
+print(constant)
+constant
+# --- 3 ---
+x = 1
+x_2 = 2
+x_2 = 3
+x
+# --- 4 ---
+x = 1
+x_2 = [2]
+x_2.append(3)
+x = 4
+# --- 5 ---
+x = 1
+x = 2
+# --- 6 ---
+x = 1
+x = 2
@@ -0,0 +1,38 @@
+# --- 1 ---
+x = 10
+while True:
+    x = x + 1
+    if not x < 10:
+        break
+x
+# --- 2 ---
+x = 0
+sum = 0
+while True:
+    x = x + 1
+    if x % 2 == 0:
+        if not x < 5:
+            break
+        continue
+    sum = sum + x
+    if not x < 5:
+        break
+sum
+# --- 3 ---
+x = 0
+while True:
+    x = x + 1
+    if x == 3:
+        break
+    if not True:
+        break
+x
+# --- 4 ---
+x = 1
+n = 0
+while True:
+    x_2 = 100
+    n = n + 1
+    if not x < 1:
+        break
+n
@@ -0,0 +1,17 @@
+# --- 1 ---
+i = None
+i = 0
+while i < 10:
+    if i == 3:
+        break
+    i = i + 1
+i
+# --- 2 ---
+i = None
+i = 0
+while i < 10:
+    if i == 3:
+        i = i + 1
+        continue
+    i = i + 1
+i
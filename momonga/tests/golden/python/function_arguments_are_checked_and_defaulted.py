@@ -0,0 +1,40 @@
+# --- 1 ---
+def f(a, b=None):
+    if b is None: b = a * 2
+    return a + b
+f(3)
+# --- 2 ---
+def f(a, b=None):
+    if b is None: b = a * 2
+    return a + b
+f(3, 1)
+# --- 3 ---
+count = 0
+def next(step=None):
+    global count
+    if step is None: step = 1
+    count += step
+    return count
+next()
+next(10)
+next()
+# --- 4 ---
+def f(a=None):
+    if a is None: a = None
+    return a
+[f(1), f()]
+# --- 5 ---
+def f(a, b):
+    return b
+f(1)
+# --- 6 ---
+def f(a, b=None):
+    if b is None: b = 2
+    return b
+f(1, 2, 3)
+# --- 7 ---
+def f(a):
+    return a
+f(print("not evaluated"), 2)
+# --- 8 ---
+# Syntax error
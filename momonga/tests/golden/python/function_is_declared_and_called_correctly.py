@@ -0,0 +1,29 @@
+# --- 1 ---
+def add(x, y):
+    return x + y
+add(1, 2)
+# --- 2 ---
+def add(x, y):
+    return x + y
+add(1, 2)
+# --- 3 ---
+y = 1
+def add(x):
+    return x + y
+add(2)
+# --- 4 ---
+def outer(x, y):
+    def inner(x, y):
+        return x + y
+    return inner(x, y)
+outer(1, 2)
+# --- 5 ---
+def factorial(n):
+    if n == 0:
+        return 1
+    return n * factorial(n - 1)
+factorial(5)
+# --- 6 ---
+def foo():
+    return
+foo
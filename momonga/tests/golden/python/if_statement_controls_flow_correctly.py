@@ -0,0 +1,52 @@
+# --- 1 ---
+x = 0
+if True:
+    x = 1
+x
+# --- 2 ---
+x = 0
+if False:
+    x = 1
+x
+# --- 3 ---
+x = 0
+if True:
+    x = 1
+    if True:
+        x = 2
+x
+# --- 4 ---
+x = 0
+if True:
+    x = 1
+else:
+    x = 2
+x
+# --- 5 ---
+x = 0
+if False:
+    x = 1
+else:
+    x = 2
+x
+# --- 6 ---
+x = 0
+if True:
+    x = 1
+elif True:
+    x = 2
+x
+# --- 7 ---
+x = 0
+if False:
+    x = 1
+elif True:
+    x = 2
+x
+# --- 8 ---
+x = 0
+if False:
+    x = 1
+elif False:
+    x = 2
+x
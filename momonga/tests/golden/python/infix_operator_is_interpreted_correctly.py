@@ -0,0 +1,197 @@
+# --- 1 ---
+2 + 3
+# --- 2 ---
+2 - 3
+# --- 3 ---
+2 * 3
+# --- 4 ---
+2 // 3
+# --- 5 ---
+2 // 0
+# --- 6 ---
+2 % 3
+# --- 7 ---
+2 % 0
+# --- 8 ---
+9223372036854775807 + 1
+# --- 9 ---
+-9223372036854775807 - 2
+# --- 10 ---
+4611686018427387904 * 2
+# --- 11 ---
+-4611686018427387904 * 2
+# --- 12 ---
+(-9223372036854775807 - 1) // -1
+# --- 13 ---
+(-9223372036854775807 - 1) % -1
+# --- 14 ---
+x = 9223372036854775807
+x += 1
+# --- 15 ---
+0 + 0 - 0 * 0
+# --- 16 ---
+2 + 3 + 5
+# --- 17 ---
+2 + 3 - 5
+# --- 18 ---
+2 + 3 * 5
+# --- 19 ---
+2 + 3 // 5
+# --- 20 ---
+2 + 3 % 5
+# --- 21 ---
+2 - 3 + 5
+# --- 22 ---
+2 - 3 - 5
+# --- 23 ---
+2 - 3 * 5
+# --- 24 ---
+2 - 3 // 5
+# --- 25 ---
+2 - 3 % 5
+# --- 26 ---
+2 * 3 + 5
+# --- 27 ---
+2 * 3 - 5
+# --- 28 ---
+2 * 3 * 5
+# --- 29 ---
+2 * 3 // 5
+# --- 30 ---
+2 * 3 % 5
+# --- 31 ---
+2 // 3 + 5
+# --- 32 ---
+2 // 3 - 5
+# --- 33 ---
+2 // 3 * 5
+# --- 34 ---
+2 // 3 // 5
+# --- 35 ---
+2 // 3 % 5
+# --- 36 ---
+2 % 3 + 5
+# --- 37 ---
+2 % 3 - 5
+# --- 38 ---
+2 % 3 * 5
+# --- 39 ---
+2 % 3 // 5
+# --- 40 ---
+2 % 3 % 5
+# --- 41 ---
+1 * +-2
+# --- 42 ---
+# Syntax error
+# --- 43 ---
+"Hello" + "," + " " + "World" + "!"
+# --- 44 ---
+"foo" - "bar"
+# --- 45 ---
+"foo" * "bar"
+# --- 46 ---
+True and True
+# --- 47 ---
+False and False
+# --- 48 ---
+True and False
+# --- 49 ---
+False or True
+# --- 50 ---
+True or True
+# --- 51 ---
+False or False
+# --- 52 ---
+True or False
+# --- 53 ---
+True == True
+# --- 54 ---
+True == False
+# --- 55 ---
+0 == 0
+# --- 56 ---
+0 == 1
+# --- 57 ---
+"foo" == "foo"
+# --- 58 ---
+"foo" == "bar"
+# --- 59 ---
+[1, 2, 3] == [1, 2, 3]
+# --- 60 ---
+[1, 2, 3] == [4, 5, 6]
+# --- 61 ---
+True != True
+# --- 62 ---
+True != False
+# --- 63 ---
+0 != 0
+# --- 64 ---
+0 != 1
+# --- 65 ---
+"foo" != "foo"
+# --- 66 ---
+"foo" != "bar"
+# --- 67 ---
+[1, 2, 3] != [1, 2, 3]
+# --- 68 ---
+[1, 2, 3] != [4, 5, 6]
+# --- 69 ---
+0 > -1
+# --- 70 ---
+0 > 0
+# --- 71 ---
+0 > 1
+# --- 72 ---
+0 >= -1
+# --- 73 ---
+0 >= 0
+# --- 74 ---
+0 >= 1
+# --- 75 ---
+-1 < 0
+# --- 76 ---
+0 < 0
+# --- 77 ---
+1 < 0
+# --- 78 ---
+-1 <= 0
+# --- 79 ---
+0 <= 0
+# --- 80 ---
+1 <= 0
+# --- 81 ---
+0 + True
+# --- 82 ---
+"foo" - 1
+# --- 83 ---
+0 * [1, 2, 3]
+# --- 84 ---
+None // 1
+# --- 85 ---
+0 % "foo"
+# --- 86 ---
+"foo" + True
+# --- 87 ---
+0 + "foo"
+# --- 88 ---
+1 == "foo"
+# --- 89 ---
+"foo" != [1, 2, 3]
+# --- 90 ---
+0 > [1, 2, 3]
+# --- 91 ---
+None >= 1
+# --- 92 ---
+0 < True
+# --- 93 ---
+"foo" <= 1
+# --- 94 ---
+1 and True
+# --- 95 ---
+True and 1
+# --- 96 ---
+1 or True
+# --- 97 ---
+False or 1
+# --- 98 ---
+raise TypeError("cannot assign to expression")
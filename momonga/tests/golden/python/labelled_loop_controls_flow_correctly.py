@@ -0,0 +1,58 @@
+# --- 1 ---
+n = 0
+for i in range(3):
+    continue_outer = False
+    for j in range(3):
+        if j == 1:
+            continue_outer = True
+            break
+        n += 1
+    if continue_outer:
+        continue
+n
+# --- 2 ---
+n = 0
+while True:
+    break_outer = False
+    while True:
+        n += 1
+        if n == 5:
+            break_outer = True
+            break
+        if not n < 3:
+            break
+    if break_outer:
+        break
+n
+# --- 3 ---
+n = 0
+i = 0
+while True:
+    i += 1
+    continue_loop = False
+    break_loop = False
+    while True:
+        if i < 3:
+            continue_loop = True
+            break
+        break_loop = True
+        break
+    if continue_loop:
+        if not True:
+            break
+        continue
+    if break_loop:
+        break
+    n = 100
+    if not True:
+        break
+i + n
+# --- 4 ---
+def find(x):
+    for i in range(10):
+        while True:
+            if i * i == x:
+                return i
+            break
+    return -1
+find(16) * 10 + find(3)
@@ -0,0 +1,16 @@
+# --- 1 ---
+True
+# --- 2 ---
+False
+# --- 3 ---
+0
+# --- 4 ---
+9223372036854775807
+# --- 5 ---
+"foo"
+# --- 6 ---
+[]
+# --- 7 ---
+[1, 2, 3]
+# --- 8 ---
+None
@@ -0,0 +1,24 @@
+# --- 1 ---
+-1 + 2
+# --- 2 ---
+-+1 - 2
+# --- 3 ---
+def foo():
+    return 1
+-foo()
+# --- 4 ---
+(2 + 3) * 5
+# --- 5 ---
+(2 + 3) // 5
+# --- 6 ---
+(2 + 3) % 5
+# --- 7 ---
+(2 - 3) * 5
+# --- 8 ---
+(2 - 3) // 5
+# --- 9 ---
+(2 - 3) % 5
+# --- 10 ---
+5 - 3 == 2
+# --- 11 ---
+2 == 5 - 3
@@ -0,0 +1,23 @@
+# --- 1 ---
+[1, [2, 22], 3][1]
+# --- 2 ---
+[1, [2, 22], 3][1][1]
+# --- 3 ---
+[1, [2, [22, 222]], 3][1][1][1]
+# --- 4 ---
+["foo", "bar", "baz"][2]
+# --- 5 ---
+[1, 2, 3][-1]
+# --- 6 ---
+[1, 2, 3][3]
+# --- 7 ---
+1[1]
+# --- 8 ---
+def arr():
+    return [1, 2, 3]
+arr()[2]
+# --- 9 ---
+True()
+# --- 10 ---
+x = True
+x()
@@ -0,0 +1,28 @@
+# --- 1 ---
+2 ** 10
+# --- 2 ---
+2 ** 3 ** 2
+# --- 3 ---
+-2 ** 2
+# --- 4 ---
+(-2) ** 3
+# --- 5 ---
+2 * 3 ** 2
+# --- 6 ---
+7 ** 0
+# --- 7 ---
+(-1) ** 1000001
+# --- 8 ---
+0 ** 100
+# --- 9 ---
+2 ** 62
+# --- 10 ---
+(-2) ** 63
+# --- 11 ---
+2 ** 63
+# --- 12 ---
+3 ** 100
+# --- 13 ---
+2 ** -1
+# --- 14 ---
+"a" ** 2
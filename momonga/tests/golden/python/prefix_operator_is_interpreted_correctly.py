@@ -0,0 +1,40 @@
+# --- 1 ---
+not True
+# --- 2 ---
+not False
+# --- 3 ---
+not not True
+# --- 4 ---
+not not not True
+# --- 5 ---
+-9223372036854775808
+# --- 6 ---
+-9223372036854775809
+# --- 7 ---
++-9223372036854775808
+# --- 8 ---
++9223372036854775807
+# --- 9 ---
+-+9223372036854775808
+# --- 10 ---
+--9223372036854775808
+# --- 11 ---
++0
+# --- 12 ---
+-0
+# --- 13 ---
++1
+# --- 14 ---
+-1
+# --- 15 ---
+++1
+# --- 16 ---
+--1
+# --- 17 ---
+-+1
+# --- 18 ---
++-1
+# --- 19 ---
++"foo"
+# --- 20 ---
+-True
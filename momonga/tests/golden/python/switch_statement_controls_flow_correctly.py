@@ -0,0 +1,69 @@
+# --- 1 ---
+x = 2
+y = 0
+match x:
+    case 1:
+        y = 10
+    case 2:
+        y = 20
+    case 3:
+        y = 30
+    case _:
+        y = 40
+y
+# --- 2 ---
+y = "none"
+match "b":
+    case "a":
+        y = "a"
+    case _:
+        y = "default"
+y
+# --- 3 ---
+y = 0
+match -1:
+    case 1:
+        y = 1
+    case -1:
+        y = -1
+match False:
+    case True:
+        y = 100
+y
+# --- 4 ---
+n = 0
+i = 0
+while True:
+    i = i + 1
+    match i % 3:
+        case 0:
+            continue
+        case 1:
+            if i > 6:
+                break
+    n = n + i
+n
+# --- 5 ---
+def name(n):
+    match n:
+        case 0:
+            return "zero"
+        case _:
+            return "many"
+name(0) + name(5)
+# --- 6 ---
+match 1:
+    case "1":
+        pass
+# --- 7 ---
+y = 0
+match 1:
+    case 1:
+        y = 1
+    case "1":
+        y = 2
+y
+# --- 8 ---
+# Syntax error: duplicate case `1`
+# --- 9 ---
+# Syntax error
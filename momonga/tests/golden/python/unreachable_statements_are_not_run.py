@@ -0,0 +1,21 @@
+# --- 1 ---
+x = 0
+def f():
+    global x
+    x = 1
+    return
+f()
+x
+# --- 2 ---
+x = 0
+while True:
+    x = x + 1
+    break
+x
+# --- 3 ---
+x = 0
+for i in range(3):
+    if i == 1:
+        continue
+    x = x + 1
+x
@@ -0,0 +1,51 @@
+# --- 1 ---
+x = 1
+x
+# --- 2 ---
+x = None
+x
+# --- 3 ---
+x = None
+y = None
+x = y = 1
+x
+# --- 4 ---
+x = None
+y = None
+x = y = 1
+y
+# --- 5 ---
+x = 1
+x = 2
+x
+# --- 6 ---
+x = 1
+x = 2
+x
+# --- 7 ---
+x
+# --- 8 ---
+foo()
+# --- 9 ---
+x = 1
+# --- 10 ---
+x = 1
+x = 2
+x
+# --- 11 ---
+x = 1
+x_2 = None
+x_2 = 2
+x
+# --- 12 ---
+x = 1
+x_2 = None
+x_2 = 2
+x_2 = 3
+x
+# --- 13 ---
+x = 1
+x_2 = None
+x_2 = 2
+x_2 = 3
+x_2
@@ -0,0 +1,19 @@
+# --- 1 ---
+x = 1
+while x < 10:
+    x = x + 1
+x
+# --- 2 ---
+x = 1
+while x < 10:
+    x = x + 1
+    if x == 3:
+        break
+x
+# --- 3 ---
+x = 1
+while x < 10:
+    x = x + 1
+    if True:
+        continue
+x
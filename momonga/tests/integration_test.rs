@@ -286,6 +286,48 @@ fn infix_operator_is_interpreted_correctly() {
         "#,
             Some("Zero division error".to_string()),
         ),
+        (
+            r#"
+            9223372036854775807 + 1;
+        "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            -9223372036854775807 - 2;
+        "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            4611686018427387904 * 2;
+        "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            -4611686018427387904 * 2;
+        "#,
+            Some("-9223372036854775808".to_string()),
+        ),
+        (
+            r#"
+            (-9223372036854775807 - 1) / -1;
+        "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            (-9223372036854775807 - 1) % -1;
+        "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            var x = 9223372036854775807; x += 1;
+        "#,
+            Some("Out of range error".to_string()),
+        ),
         (
             r#"
             0 + 0 - 0 * 0;
@@ -987,6 +1029,228 @@ fn operator_precedence_is_interpreted_correctly() {
     }
 }
 
+#[test]
+fn bitwise_operator_is_interpreted_correctly() {
+    let tests = [
+        (
+            r#"
+            12 & 10;
+            "#,
+            Some("8".to_string()),
+        ),
+        (
+            r#"
+            12 | 10;
+            "#,
+            Some("14".to_string()),
+        ),
+        (
+            r#"
+            12 ^ 10;
+            "#,
+            Some("6".to_string()),
+        ),
+        (
+            r#"
+            ~0;
+            "#,
+            Some("-1".to_string()),
+        ),
+        (
+            r#"
+            ~5;
+            "#,
+            Some("-6".to_string()),
+        ),
+        (
+            r#"
+            1 << 62;
+            "#,
+            Some("4611686018427387904".to_string()),
+        ),
+        (
+            r#"
+            3 << 63; // Bits shifted out are lost
+            "#,
+            Some("-9223372036854775808".to_string()),
+        ),
+        (
+            r#"
+            -16 >> 2;
+            "#,
+            Some("-4".to_string()),
+        ),
+        (
+            r#"
+            -1 >> 63;
+            "#,
+            Some("-1".to_string()),
+        ),
+        (
+            r#"
+            5 >> 1;
+            "#,
+            Some("2".to_string()),
+        ),
+        (
+            r#"
+            (6 & 3) == 2;
+            "#,
+            Some("true".to_string()),
+        ),
+        (
+            r#"
+            1 | 2 ^ 3 & 5;
+            "#,
+            Some("3".to_string()),
+        ),
+        (
+            r#"
+            1 << 2 + 1;
+            "#,
+            Some("8".to_string()),
+        ),
+        (
+            r#"
+            1 << 2 < 5;
+            "#,
+            Some("true".to_string()),
+        ),
+        (
+            r#"
+            var n = 255;
+            var count = 0;
+            while (n != 0) {
+                n = n & (n - 1);
+                count += 1;
+            }
+            count;
+            "#,
+            Some("8".to_string()),
+        ),
+        (
+            r#"
+            1 << 64;
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            1 >> -1;
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            6 & 3 == 2;
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            ~"a";
+            "#,
+            Some("Type error".to_string()),
+        ),
+    ];
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn power_operator_is_interpreted_correctly() {
+    let tests = [
+        (
+            r#"
+            2 ** 10;
+            "#,
+            Some("1024".to_string()),
+        ),
+        (
+            r#"
+            2 ** 3 ** 2; // Right-associative
+            "#,
+            Some("512".to_string()),
+        ),
+        (
+            r#"
+            -2 ** 2;
+            "#,
+            Some("-4".to_string()),
+        ),
+        (
+            r#"
+            (-2) ** 3;
+            "#,
+            Some("-8".to_string()),
+        ),
+        (
+            r#"
+            2 * 3 ** 2;
+            "#,
+            Some("18".to_string()),
+        ),
+        (
+            r#"
+            7 ** 0;
+            "#,
+            Some("1".to_string()),
+        ),
+        (
+            r#"
+            (-1) ** 1000001;
+            "#,
+            Some("-1".to_string()),
+        ),
+        (
+            r#"
+            0 ** 100;
+            "#,
+            Some("0".to_string()),
+        ),
+        (
+            r#"
+            2 ** 62;
+            "#,
+            Some("4611686018427387904".to_string()),
+        ),
+        (
+            r#"
+            (-2) ** 63;
+            "#,
+            Some("-9223372036854775808".to_string()),
+        ),
+        (
+            r#"
+            2 ** 63;
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            3 ** 100;
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            2 ** -1;
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            "a" ** 2;
+            "#,
+            Some("Type error".to_string()),
+        ),
+    ];
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
 #[test]
 fn variable_is_daclared_and_assigned_value_correctly() {
     let tests = [
@@ -1116,72 +1380,62 @@ fn variable_is_daclared_and_assigned_value_correctly() {
 }
 
 #[test]
-fn function_is_declared_and_called_correctly() {
+fn constant_is_declared_correctly() {
     let tests = [
-        // Declaration
         (
             r#"
-            func add(x, y) {
-                return x + y;
-            }
-            add(1, 2);
+            const x = 1;
+            x + 1;
             "#,
-            Some("3".to_string()),
+            Some("2".to_string()),
         ),
+        // `const` is reserved only as a whole word
         (
             r#"
-            func add(x, y) {
-                return x + y;
-                return 100; // Not executed
-            }
-            add(1, 2);
+            var constant = 1;
+            print(constant);
+            constant;
             "#,
-            Some("3".to_string()),
+            Some("1".to_string()),
         ),
-        // Free variable resolution
         (
             r#"
-            var y = 1;
-            func add(x) {
-                return x + y; // y is free variable and lexically resolved
+            const x = 1;
+            {
+                var x = 2;
+                x = 3;
             }
-            add(2);
+            x;
             "#,
-            Some("3".to_string()),
+            Some("1".to_string()),
         ),
-        // Inner functions
         (
             r#"
-            func outer(x, y) {
-                func inner(x, y) {
-                    return x + y;
-                }
-                return inner(x, y);
+            var x = 1;
+            {
+                const x = [2];
+                push(x, 3);
             }
-            outer(1, 2);
+            x = 4;
             "#,
-            Some("3".to_string()),
+            Some("4".to_string()),
         ),
-        // Recursive function
+        // Error case
         (
             r#"
-            func factorial(n) {
-                if (n == 0) {
-                    return 1;
-                }
-                return n * factorial(n - 1);
-            }
-            factorial(5);
+            const x = 1;
+            x = 2;
             "#,
-            Some("120".to_string()),
+            Some("Constant assignment error".to_string()),
         ),
-        // Function is not an expression
         (
             r#"
-            func foo() {return ; }
-            foo;
+            const x = 1;
+            {
+                x = 2;
+            }
             "#,
-            Some("Invalid expression error".to_string()),
+            Some("Constant assignment error".to_string()),
         ),
     ];
 
@@ -1191,7 +1445,292 @@ fn function_is_declared_and_called_correctly() {
 }
 
 #[test]
-fn if_statement_controls_flow_correctly() {
+fn compound_assignment_is_interpreted_correctly() {
+    let tests = [
+        (
+            r#"
+            var x = 1;
+            x += 2;
+            x -= 1;
+            x *= 6;
+            x /= 4;
+            x %= 2;
+            x;
+            "#,
+            Some("1".to_string()),
+        ),
+        (
+            r#"
+            var s = "a";
+            s += "b";
+            s;
+            "#,
+            Some("ab".to_string()),
+        ),
+        (
+            r#"
+            var s = "a";
+            var t = s;
+            var u = s + "b";
+            s += "c";
+            [s, t, u, s + s];
+            "#,
+            Some("[ac, a, ab, acac]".to_string()),
+        ),
+        (
+            r#"
+            var a = [1, 2];
+            a[1] += 10;
+            a[0] = 5;
+            a;
+            "#,
+            Some("[5, 12]".to_string()),
+        ),
+        (
+            r#"
+            var x = 1;
+            x += 1 + 2 * 3;
+            x;
+            "#,
+            Some("8".to_string()),
+        ),
+        (
+            r#"
+            var x = 10;
+            var y = 2;
+            x -= y -= 1;
+            x;
+            "#,
+            Some("9".to_string()),
+        ),
+        (
+            r#"
+            var sum = 0;
+            for (var i = 0; i < 5; i += 1) {
+                sum += i;
+            }
+            sum;
+            "#,
+            Some("10".to_string()),
+        ),
+        // Error case
+        (
+            r#"
+            var x = 1;
+            x += "a";
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            var x = 1;
+            x /= 0;
+            "#,
+            Some("Zero division error".to_string()),
+        ),
+        (
+            r#"
+            y += 1;
+            "#,
+            Some("Name error".to_string()),
+        ),
+        (
+            r#"
+            var a = [1];
+            a[1] += 1;
+            "#,
+            Some("Index error".to_string()),
+        ),
+        (
+            r#"
+            var a = [1];
+            a[-1] = 1;
+            "#,
+            Some("Index error".to_string()),
+        ),
+        (
+            r#"
+            var s = "ab";
+            s[0] = "c";
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            const x = 1;
+            x += 1;
+            "#,
+            Some("Constant assignment error".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn function_is_declared_and_called_correctly() {
+    let tests = [
+        // Declaration
+        (
+            r#"
+            func add(x, y) {
+                return x + y;
+            }
+            add(1, 2);
+            "#,
+            Some("3".to_string()),
+        ),
+        (
+            r#"
+            func add(x, y) {
+                return x + y;
+                return 100; // Not executed
+            }
+            add(1, 2);
+            "#,
+            Some("3".to_string()),
+        ),
+        // Free variable resolution
+        (
+            r#"
+            var y = 1;
+            func add(x) {
+                return x + y; // y is free variable and lexically resolved
+            }
+            add(2);
+            "#,
+            Some("3".to_string()),
+        ),
+        // Inner functions
+        (
+            r#"
+            func outer(x, y) {
+                func inner(x, y) {
+                    return x + y;
+                }
+                return inner(x, y);
+            }
+            outer(1, 2);
+            "#,
+            Some("3".to_string()),
+        ),
+        // Recursive function
+        (
+            r#"
+            func factorial(n) {
+                if (n == 0) {
+                    return 1;
+                }
+                return n * factorial(n - 1);
+            }
+            factorial(5);
+            "#,
+            Some("120".to_string()),
+        ),
+        // Function is not an expression
+        (
+            r#"
+            func foo() {return ; }
+            foo;
+            "#,
+            Some("Invalid expression error".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn function_arguments_are_checked_and_defaulted() {
+    let tests = [
+        // Default values
+        (
+            r#"
+            func f(a, b = a * 2) {
+                return a + b;
+            }
+            f(3);
+            "#,
+            Some("9".to_string()),
+        ),
+        (
+            r#"
+            func f(a, b = a * 2) {
+                return a + b;
+            }
+            f(3, 1);
+            "#,
+            Some("4".to_string()),
+        ),
+        (
+            r#"
+            var count = 0;
+            func next(step = 1) {
+                count += step;
+                return count;
+            }
+            next();
+            next(10);
+            next(); // Evaluated at each call
+            "#,
+            Some("12".to_string()),
+        ),
+        (
+            r#"
+            func f(a = none) {
+                return a;
+            }
+            [f(1), f()];
+            "#,
+            Some("[1, none]".to_string()),
+        ),
+        // Error case
+        (
+            r#"
+            func f(a, b) {
+                return b;
+            }
+            f(1);
+            "#,
+            Some("`f` expects 2 arguments, got 1".to_string()),
+        ),
+        (
+            r#"
+            func f(a, b = 2) {
+                return b;
+            }
+            f(1, 2, 3);
+            "#,
+            Some("`f` expects 1 to 2 arguments, got 3".to_string()),
+        ),
+        (
+            r#"
+            func f(a) {
+                return a;
+            }
+            f(print("not evaluated"), 2);
+            "#,
+            Some("`f` expects 1 argument, got 2".to_string()),
+        ),
+        (
+            r#"
+            func f(a = 1, b) {}
+            "#,
+            Some("Syntax error".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn if_statement_controls_flow_correctly() {
     let tests = [
         (
             r#"
@@ -1378,40 +1917,57 @@ fn while_statement_controls_flow_correctly() {
 }
 
 #[test]
-fn builtin_function_len_works() {
+fn do_while_statement_controls_flow_correctly() {
     let tests = [
         (
             r#"
-            var arr = [1, 2, 3, "foo", [true]];
-            len(arr);
-            "#,
-            Some("5".to_string()),
-        ),
-        (
-            r#"
-            len("Hello, World!");
+            var x = 10;
+            do {
+                x = x + 1;
+            } while (x < 10);
+            x;
             "#,
-            Some("13".to_string()),
+            Some("11".to_string()),
         ),
-        // Error case
         (
             r#"
-            len();
+            var x = 0;
+            var sum = 0;
+            do {
+                x = x + 1;
+                if (x % 2 == 0) {
+                    continue;
+                }
+                sum = sum + x;
+            } while (x < 5);
+            sum;
             "#,
-            Some("Argument error".to_string()),
+            Some("9".to_string()),
         ),
         (
             r#"
-            len([1, 2, 3], [4, 5, 6]);
-
+            var x = 0;
+            do {
+                x = x + 1;
+                if (x == 3) {
+                    break;
+                }
+            } while (true);
+            x;
             "#,
-            Some("Argument error".to_string()),
+            Some("3".to_string()),
         ),
         (
             r#"
-            len(1);
+            var x = 1;
+            var n = 0;
+            do {
+                var x = 100; // The condition does not see this
+                n = n + 1;
+            } while (x < 1);
+            n;
             "#,
-            Some("Type error".to_string()),
+            Some("1".to_string()),
         ),
     ];
 
@@ -1421,30 +1977,409 @@ fn builtin_function_len_works() {
 }
 
 #[test]
-fn builtin_function_push_works() {
+fn labelled_loop_controls_flow_correctly() {
     let tests = [
         (
             r#"
-            push([1, 2, 3], 4);
+            var n = 0;
+            outer: for (var i = 0; i < 3; i += 1) {
+                for (var j = 0; j < 3; j += 1) {
+                    if (j == 1) {
+                        continue outer;
+                    }
+                    n += 1;
+                }
+            }
+            n;
             "#,
-            Some("[1, 2, 3, 4]".to_string()),
+            Some("3".to_string()),
         ),
-        // Error case
         (
             r#"
-            push();
+            var n = 0;
+            outer: while (true) {
+                do {
+                    n += 1;
+                    if (n == 5) {
+                        break outer;
+                    }
+                } while (n < 3);
+            }
+            n;
             "#,
-            Some("Argument error".to_string()),
+            Some("5".to_string()),
         ),
         (
             r#"
-            push([1, 2, 3]);
+            var n = 0;
+            var i = 0;
+            loop: do {
+                i += 1;
+                while (true) {
+                    if (i < 3) {
+                        continue loop;
+                    }
+                    break loop;
+                }
+                n = 100; // Not executed
+            } while (true);
+            i + n;
             "#,
-            Some("Argument error".to_string()),
+            Some("3".to_string()),
         ),
         (
             r#"
-            push("not array", 1);
+            func find(x) {
+                for (var i = 0; i < 10; i += 1) {
+                    while (true) {
+                        if (i * i == x) {
+                            return i;
+                        }
+                        break;
+                    }
+                }
+                return -1;
+            }
+            find(16) * 10 + find(3);
+            "#,
+            Some("39".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn switch_statement_controls_flow_correctly() {
+    let tests = [
+        (
+            r#"
+            var x = 2;
+            var y = 0;
+            switch (x) {
+                case 1: {
+                    y = 10;
+                }
+                case 2: {
+                    y = 20; // No fall-through to the next case
+                }
+                case 3: {
+                    y = 30;
+                }
+                default: {
+                    y = 40;
+                }
+            }
+            y;
+            "#,
+            Some("20".to_string()),
+        ),
+        (
+            r#"
+            var y = "none";
+            switch ("b") {
+                case "a": {
+                    y = "a";
+                }
+                default: {
+                    y = "default";
+                }
+            }
+            y;
+            "#,
+            Some("default".to_string()),
+        ),
+        (
+            r#"
+            var y = 0;
+            switch (-1) {
+                case 1: {
+                    y = 1;
+                }
+                case -1: {
+                    y = -1;
+                }
+            }
+            switch (false) {
+                case true: {
+                    y = 100;
+                }
+            }
+            y;
+            "#,
+            Some("-1".to_string()),
+        ),
+        (
+            r#"
+            var n = 0;
+            var i = 0;
+            while (true) {
+                i = i + 1;
+                switch (i % 3) {
+                    case 0: {
+                        continue;
+                    }
+                    case 1: {
+                        if (i > 6) {
+                            break; // Out of the loop
+                        }
+                    }
+                }
+                n = n + i;
+            }
+            n;
+            "#,
+            Some("12".to_string()),
+        ),
+        (
+            r#"
+            func name(n) {
+                switch (n) {
+                    case 0: {
+                        return "zero";
+                    }
+                    default: {
+                        return "many";
+                    }
+                }
+            }
+            name(0) + name(5);
+            "#,
+            Some("zeromany".to_string()),
+        ),
+        (
+            r#"
+            switch (1) {
+                case "1": {}
+            }
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            var y = 0;
+            switch (1) {
+                case 1: {
+                    y = 1;
+                }
+                case "1": {
+                    y = 2;
+                }
+            }
+            y;
+            "#,
+            Some("1".to_string()),
+        ),
+        (
+            r#"
+            switch (1) {
+                case 1: {}
+                case 1: {}
+            }
+            "#,
+            Some("Syntax error: duplicate case `1`".to_string()),
+        ),
+        (
+            r#"
+            switch (1) {
+                case 99999999999999999999: {}
+            }
+            "#,
+            Some("Syntax error".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn conditional_expression_is_interpreted_correctly() {
+    let tests = [
+        (
+            r#"
+            var x = -3;
+            x > 0 ? x : -x;
+            "#,
+            Some("3".to_string()),
+        ),
+        (
+            r#"
+            func sign(n) {
+                return n > 0 ? 1 : n < 0 ? -1 : 0;
+            }
+            [sign(5), sign(-5), sign(0)];
+            "#,
+            Some("[1, -1, 0]".to_string()),
+        ),
+        (
+            r#"
+            var x = 1;
+            var y = x == 1 || x == 2 ? "small" : "large";
+            (x > 0 ? 10 : 20) + 1;
+            y + (false ? "a" : "b");
+            "#,
+            Some("smallb".to_string()),
+        ),
+        (
+            r#"
+            var count = 0;
+            var y = true ? 1 : (count = 100);
+            y = false ? [][5] : (count = count + 1);
+            [count, y];
+            "#,
+            Some("[1, 1]".to_string()),
+        ),
+        (
+            r#"
+            1 ? 2 : 3;
+            "#,
+            Some("Type error".to_string()),
+        ),
+    ];
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn builtin_function_len_works() {
+    let tests = [
+        (
+            r#"
+            var arr = [1, 2, 3, "foo", [true]];
+            len(arr);
+            "#,
+            Some("5".to_string()),
+        ),
+        (
+            r#"
+            len("Hello, World!");
+            "#,
+            Some("13".to_string()),
+        ),
+        // Error case
+        (
+            r#"
+            len();
+            "#,
+            Some("`len` expects 1 argument, got 0".to_string()),
+        ),
+        (
+            r#"
+            len([1, 2, 3], [4, 5, 6]);
+
+            "#,
+            Some("`len` expects 1 argument, got 2".to_string()),
+        ),
+        (
+            r#"
+            len(1);
+            "#,
+            Some("Type error".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn builtin_function_print_works() {
+    let tests = [
+        (
+            r#"
+            print(1, "a", [2, "b"], true, none);
+            "#,
+            Some("none".to_string()),
+        ),
+        (
+            r#"
+            var x = 1;
+            print("x =", x);
+            "#,
+            Some("none".to_string()),
+        ),
+        // Error case
+        (
+            r#"
+            print();
+            "#,
+            Some("`print` expects at least 1 argument, got 0".to_string()),
+        ),
+        (
+            r#"
+            print(1, y);
+            "#,
+            Some("Name error".to_string()),
+        ),
+    ];
+
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+
+    // What is written, with the arguments separated by spaces
+    let tests = [
+        (
+            r#"
+            print(1, "a", [2, "b"], true, none);
+            "#,
+            vec!["1 a [2, b] true none"],
+        ),
+        (
+            r#"
+            var x = 1;
+            print("x =", x);
+            print("x + 1 =", x + 1);
+            "#,
+            vec!["x = 1", "x + 1 = 2"],
+        ),
+    ];
+
+    for (src, expected) in tests {
+        let (_, stdout) = with_captured_stdio("", || interpret(src));
+        assert_eq!(stdout, expected, "Failed in test case: {}", src);
+    }
+}
+
+#[test]
+fn builtin_function_push_works() {
+    let tests = [
+        (
+            r#"
+            push([1, 2, 3], 4);
+            "#,
+            Some("[1, 2, 3, 4]".to_string()),
+        ),
+        (
+            r#"
+            var arr = [1];
+            push(arr, 2, [3], "4");
+            arr;
+            "#,
+            Some("[1, 2, [3], 4]".to_string()),
+        ),
+        // Error case
+        (
+            r#"
+            push();
+            "#,
+            Some("`push` expects at least 2 arguments, got 0".to_string()),
+        ),
+        (
+            r#"
+            push([1, 2, 3]);
+            "#,
+            Some("`push` expects at least 2 arguments, got 1".to_string()),
+        ),
+        (
+            r#"
+            push("not array", 1);
             "#,
             Some("Type error".to_string()),
         ),
@@ -1505,6 +2440,111 @@ fn builtin_function_pop_works() {
     }
 }
 
+#[test]
+fn builtin_function_math_works() {
+    let tests = [
+        (
+            r#"
+            abs(-5) + abs(5);
+            "#,
+            Some("10".to_string()),
+        ),
+        (
+            r#"
+            abs(-9223372036854775807 - 1);
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            min(3, 1, 2);
+            "#,
+            Some("1".to_string()),
+        ),
+        (
+            r#"
+            max(3, 1, 2, -4);
+            "#,
+            Some("3".to_string()),
+        ),
+        (
+            r#"
+            min(1);
+            "#,
+            Some("`min` expects at least 2 arguments, got 1".to_string()),
+        ),
+        (
+            r#"
+            max(1, true);
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            pow(-3, 3);
+            "#,
+            Some("-27".to_string()),
+        ),
+        (
+            r#"
+            pow(10, 19);
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            sqrt(15) + sqrt(16);
+            "#,
+            Some("7".to_string()),
+        ),
+        (
+            r#"
+            sqrt(9223372036854775807);
+            "#,
+            Some("3037000499".to_string()),
+        ),
+        (
+            r#"
+            sqrt(-1);
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            gcd(12, -18);
+            "#,
+            Some("6".to_string()),
+        ),
+        (
+            r#"
+            gcd(0, 0);
+            "#,
+            Some("0".to_string()),
+        ),
+        (
+            r#"
+            gcd(-9223372036854775807 - 1, 0);
+            "#,
+            Some("Out of range error".to_string()),
+        ),
+        (
+            r#"
+            gcd(4, "2");
+            "#,
+            Some("Type error".to_string()),
+        ),
+        (
+            r#"
+            sqrt();
+            "#,
+            Some("`sqrt` expects 1 argument, got 0".to_string()),
+        ),
+    ];
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
+
 #[test]
 fn generate_type_error() {
     let tests = [];
@@ -1513,3 +2553,51 @@ fn generate_type_error() {
         assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
     }
 }
+
+#[test]
+fn unreachable_statements_are_not_run() {
+    let tests = [
+        (
+            r#"
+            var x = 0;
+            func f() {
+                x = 1;
+                return;
+                x = 2;
+            }
+            f();
+            x;
+            "#,
+            Some("1".to_string()),
+        ),
+        (
+            r#"
+            var x = 0;
+            while (true) {
+                x = x + 1;
+                break;
+                x = 100;
+            }
+            x;
+            "#,
+            Some("1".to_string()),
+        ),
+        (
+            r#"
+            var x = 0;
+            for (var i = 0; i < 3; i = i + 1) {
+                if (i == 1) {
+                    continue;
+                    x = x + 100;
+                }
+                x = x + 1;
+            }
+            x;
+            "#,
+            Some("2".to_string()),
+        ),
+    ];
+    for (src, expected) in tests {
+        assert_eq!(interpret(src), expected, "Failed in test case: {}", src);
+    }
+}
@@ -0,0 +1,194 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde_json::{json, Value};
+
+const URI: &str = "file:///main.mo";
+
+const SOURCE: &str = r#"var total = 0;
+func add(x) {
+    func twice(y) { return y * 2; }
+    total = total + twice(x);
+}
+add(1);
+print(total);
+"#;
+
+// Messages the server sends in reply to `requests`, which are followed by `shutdown` and `exit`
+fn exchange(requests: &[Value]) -> Vec<Value> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_momonga-lsp"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let shutdown = [
+        json!({"jsonrpc": "2.0", "id": 999, "method": "shutdown"}),
+        json!({"jsonrpc": "2.0", "method": "exit"}),
+    ];
+    for request in requests.iter().chain(&shutdown) {
+        let content = request.to_string();
+        write!(
+            stdin,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )
+        .unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    let mut stdout = String::from_utf8(output.stdout).unwrap();
+    let mut messages: Vec<Value> = vec![];
+    while let Some((header, rest)) = stdout.split_once("\r\n\r\n") {
+        let len: usize = header["Content-Length: ".len()..].parse().unwrap();
+        messages.push(serde_json::from_str(&rest[..len]).unwrap());
+        stdout = rest[len..].to_string();
+    }
+    assert_eq!(messages.pop().unwrap()["id"], 999);
+    messages
+}
+
+fn open(text: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": {"textDocument": {"uri": URI, "languageId": "momonga", "version": 1, "text": text}},
+    })
+}
+
+fn request(id: i64, method: &str, line: usize, character: usize) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "method": method,
+        "params": {"textDocument": {"uri": URI}, "position": {"line": line, "character": character}},
+    })
+}
+
+#[test]
+fn diagnostics_are_published() {
+    let tests = [
+        (SOURCE, json!([])),
+        (
+            "var x = 1;\nprint(y);\n",
+            json!([{
+                "range": {"start": {"line": 1, "character": 6}, "end": {"line": 1, "character": 7}},
+                "severity": 2,
+                "source": "momonga",
+                "message": "Undefined name: y",
+            }]),
+        ),
+        (
+            "var x = 1;\nvar = 2;\n",
+            json!([{
+                "range": {"start": {"line": 1, "character": 4}, "end": {"line": 1, "character": 4}},
+                "severity": 1,
+                "source": "momonga",
+                "message": "Syntax error",
+            }]),
+        ),
+    ];
+    for (src, expected) in tests {
+        let messages = exchange(&[open(src)]);
+        assert_eq!(messages.len(), 1, "Failed in test case: {}", src);
+        assert_eq!(
+            messages[0]["method"], "textDocument/publishDiagnostics",
+            "Failed in test case: {}",
+            src
+        );
+        assert_eq!(
+            messages[0]["params"]["diagnostics"], expected,
+            "Failed in test case: {}",
+            src
+        );
+    }
+}
+
+#[test]
+fn requests_are_answered() {
+    let messages = exchange(&[
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {"capabilities": {}}}),
+        open(SOURCE),
+        request(2, "textDocument/hover", 3, 22),     // twice
+        request(3, "textDocument/hover", 6, 1),      // print
+        request(4, "textDocument/definition", 6, 8), // total
+        request(5, "textDocument/definition", 6, 1), // print
+        json!({"jsonrpc": "2.0", "id": 6, "method": "textDocument/documentSymbol", "params": {"textDocument": {"uri": URI}}}),
+        request(7, "textDocument/completion", 3, 4),
+        json!({"jsonrpc": "2.0", "id": 8, "method": "textDocument/formatting", "params": {}}),
+    ]);
+    let result = |id: i64| {
+        messages
+            .iter()
+            .find(|message| message["id"] == id)
+            .unwrap()
+            .get("result")
+            .cloned()
+    };
+
+    assert_eq!(result(1).unwrap()["capabilities"]["hoverProvider"], true);
+    assert_eq!(
+        result(2).unwrap()["contents"]["value"],
+        "```momonga\nfunc twice(y)\n```"
+    );
+    assert_eq!(
+        result(3).unwrap()["contents"]["value"],
+        "```momonga\nfunc print(value, ...)\n```\n\nPrints values separated by spaces, followed by a line break."
+    );
+    assert_eq!(
+        result(4).unwrap(),
+        json!({
+            "uri": URI,
+            "range": {"start": {"line": 0, "character": 4}, "end": {"line": 0, "character": 9}},
+        })
+    );
+    assert_eq!(result(5).unwrap(), Value::Null);
+
+    let symbols = result(6).unwrap();
+    assert_eq!(symbols[0]["name"], "add");
+    assert_eq!(
+        symbols[0]["range"]["end"],
+        json!({"line": 4, "character": 1})
+    );
+    assert_eq!(symbols[0]["children"][0]["name"], "twice");
+    assert_eq!(symbols[0]["children"][0]["children"], json!([]));
+    assert_eq!(symbols.as_array().unwrap().len(), 1);
+
+    let labels: Vec<Value> = result(7)
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["label"].clone())
+        .collect();
+    for label in ["func", "while", "x", "twice", "add", "total", "print"] {
+        assert!(
+            labels.contains(&json!(label)),
+            "Missing completion: {}",
+            label
+        );
+    }
+    assert!(!labels.contains(&json!("y")));
+
+    let error = &messages.iter().find(|message| message["id"] == 8).unwrap()["error"];
+    assert_eq!(error["code"], -32601);
+}
+
+#[test]
+fn positions_count_utf16_code_units() {
+    let src = "var s = \"😀\"; var t = s;\n";
+    let messages = exchange(&[open(src), request(1, "textDocument/definition", 0, 22)]);
+    assert_eq!(
+        messages[1]["result"]["range"]["start"],
+        json!({"line": 0, "character": 4})
+    );
+    let src = "var s = \"😀\"; print(u);\n";
+    let messages = exchange(&[open(src)]);
+    assert_eq!(
+        messages[0]["params"]["diagnostics"][0]["range"]["start"],
+        json!({"line": 0, "character": 20})
+    );
+}
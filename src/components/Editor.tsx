@@ -4,8 +4,13 @@ import { Box } from "@mui/material";
 import * as monaco from "monaco-editor/esm/vs/editor/editor.api";
 
 import { snippets } from "@/constants";
+import {
+  momonga_completions,
+  momonga_format,
+  momonga_hover,
+} from "../../momonga/pkg/momonga";
 import { useIsDarkMode } from "@/hooks/useIsDarkMode";
-import { MonacoTheme, SnippetKey } from "@/types/types";
+import { Completion, MonacoTheme, SnippetKey } from "@/types/types";
 
 monaco.languages.register({ id: "momonga" });
 monaco.languages.setLanguageConfiguration("momonga", {
@@ -42,15 +47,21 @@ monaco.languages.setLanguageConfiguration("momonga", {
 monaco.languages.setMonarchTokensProvider("momonga", {
   keywords: [
     "break",
+    "case",
+    "const",
     "continue",
+    "default",
+    "do",
     "else",
     "false",
     "for",
     "func",
     "if",
     "return",
+    "switch",
     "true",
     "var",
+    "while",
   ],
   tokenizer: {
     root: [
@@ -68,6 +79,43 @@ monaco.languages.setMonarchTokensProvider("momonga", {
     ],
   },
 });
+monaco.languages.registerDocumentFormattingEditProvider("momonga", {
+  provideDocumentFormattingEdits: (model) => {
+    const formatted = momonga_format(model.getValue());
+    if (formatted === undefined) return []; // Leave the source as it is on syntax error
+    return [{ range: model.getFullModelRange(), text: formatted }];
+  },
+});
+monaco.languages.registerCompletionItemProvider("momonga", {
+  provideCompletionItems: (model, position) => {
+    const word = model.getWordUntilPosition(position);
+    const range = {
+      startLineNumber: position.lineNumber,
+      endLineNumber: position.lineNumber,
+      startColumn: word.startColumn,
+      endColumn: word.endColumn,
+    };
+    const completions: Completion[] = JSON.parse(
+      momonga_completions(model.getValue(), model.getOffsetAt(position)),
+    );
+    return {
+      suggestions: completions.map((completion) => ({
+        label: completion.label,
+        kind: monaco.languages.CompletionItemKind[completion.kind],
+        detail: completion.detail,
+        insertText: completion.label,
+        range,
+      })),
+    };
+  },
+});
+monaco.languages.registerHoverProvider("momonga", {
+  provideHover: (model, position) => {
+    const hover = momonga_hover(model.getValue(), model.getOffsetAt(position));
+    if (hover === undefined) return null;
+    return { contents: [{ value: hover }] };
+  },
+});
 monaco.editor.defineTheme("monaco-theme-light", {
   base: "vs",
   inherit: true,
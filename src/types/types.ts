@@ -9,6 +9,11 @@ export type UserConfig = {
 
 export type Layout = "horizontal" | "vertical";
 export type MonacoTheme = "monaco-theme-dark" | "monaco-theme-light";
+export type Completion = {
+  label: string;
+  kind: "Keyword" | "Variable" | "Constant" | "Function";
+  detail: string;
+};
 
 export type Snippet = {
   key: SnippetKey;